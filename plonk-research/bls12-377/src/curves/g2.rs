@@ -0,0 +1,83 @@
+use crate::*;
+use ark_ec::{
+    bls12,
+    models::{ModelParameters, SWModelParameters},
+};
+use ark_ff::{field_new, Zero};
+
+pub type G2Affine = bls12::G2Affine<crate::Parameters>;
+pub type G2Projective = bls12::G2Projective<crate::Parameters>;
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Parameters;
+
+impl ModelParameters for Parameters {
+    type BaseField = Fq2;
+    type ScalarField = Fr;
+}
+
+impl SWModelParameters for Parameters {
+    /// COEFF_A = [0, 0]
+    const COEFF_A: Fq2 = field_new!(Fq2, g1::Parameters::COEFF_A, g1::Parameters::COEFF_A,);
+
+    //As per https://eprint.iacr.org/2012/072.pdf this curve has b' = b/i, where b is the COEFF_B
+    //of G1 and x^6 - i is the irreducible polynomial extending Fq2 to Fq12, here i = U.
+    /// COEFF_B = [0,
+    /// 155198655607781456406391640216936120121836107652948796323930557600032281009004493664981332883744016074664192874906]
+    #[rustfmt::skip]
+    const COEFF_B: Fq2 = field_new!(Fq2,
+        field_new!(Fq, "0"),
+        field_new!(Fq, "155198655607781456406391640216936120121836107652948796323930557600032281009004493664981332883744016074664192874906"),
+    );
+
+    /// COFACTOR =
+    /// 7923214915284317143930293550643874566881017850177945424769256759165301436616933228209277966774092486467289478618404761412630691835764674559376407658497
+    #[rustfmt::skip]
+    const COFACTOR: &'static [u64] = &[
+        0x0000000000000001,
+        0x452217cc90000000,
+        0xa0f3622fba094800,
+        0xd693e8c36676bd09,
+        0x8c505634fae2e189,
+        0xfbb36b00e1dcc40c,
+        0xddd88d99a6f6a829,
+        0x26ba558ae9562a,
+    ];
+
+    /// COFACTOR_INV = COFACTOR^{-1} mod r
+    /// = 6764900296503390671038341982857278410319949526107311149686707033187604810669
+    #[rustfmt::skip]
+    const COFACTOR_INV: Fr = field_new!(Fr, "6764900296503390671038341982857278410319949526107311149686707033187604810669");
+
+    /// AFFINE_GENERATOR_COEFFS = (G2_GENERATOR_X, G2_GENERATOR_Y)
+    const AFFINE_GENERATOR_COEFFS: (Self::BaseField, Self::BaseField) =
+        (G2_GENERATOR_X, G2_GENERATOR_Y);
+
+    #[inline(always)]
+    fn mul_by_a(_: &Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+pub const G2_GENERATOR_X: Fq2 = field_new!(Fq2, G2_GENERATOR_X_C0, G2_GENERATOR_X_C1);
+pub const G2_GENERATOR_Y: Fq2 = field_new!(Fq2, G2_GENERATOR_Y_C0, G2_GENERATOR_Y_C1);
+
+/// G2_GENERATOR_X_C0 =
+/// 233578398248691099356572568220835526895379068987715365179118596935057653620464273615301663571204657964920925606294
+#[rustfmt::skip]
+pub const G2_GENERATOR_X_C0: Fq = field_new!(Fq, "233578398248691099356572568220835526895379068987715365179118596935057653620464273615301663571204657964920925606294");
+
+/// G2_GENERATOR_X_C1 =
+/// 140913150380207355837477652521042157274541796891053068589147167627541651775299824604154852141315666357241556069118
+#[rustfmt::skip]
+pub const G2_GENERATOR_X_C1: Fq = field_new!(Fq, "140913150380207355837477652521042157274541796891053068589147167627541651775299824604154852141315666357241556069118");
+
+/// G2_GENERATOR_Y_C0 =
+/// 63160294768292073209381361943935198908131692476676907196754037919244929611450776219210369229519898517858833747423
+#[rustfmt::skip]
+pub const G2_GENERATOR_Y_C0: Fq = field_new!(Fq, "63160294768292073209381361943935198908131692476676907196754037919244929611450776219210369229519898517858833747423");
+
+/// G2_GENERATOR_Y_C1 =
+/// 149157405641012693445398062341192467754805999074082136895788947234480009303640899064710353187729182149407503257491
+#[rustfmt::skip]
+pub const G2_GENERATOR_Y_C1: Fq = field_new!(Fq, "149157405641012693445398062341192467754805999074082136895788947234480009303640899064710353187729182149407503257491");
@@ -0,0 +1,94 @@
+use ark_ec::bls12::{Bls12, Bls12Parameters, TwistType};
+
+use crate::{Fq, Fq12Parameters, Fq2Parameters, Fq6Parameters};
+
+pub mod g1;
+pub mod g2;
+
+pub use self::{
+    g1::{G1Affine, G1Projective},
+    g2::{G2Affine, G2Projective},
+};
+
+pub type Bls12_377 = Bls12<Parameters>;
+
+pub struct Parameters;
+
+impl Bls12Parameters for Parameters {
+    const X: &'static [u64] = &[0x8508c00000000001];
+    /// `x` is positive.
+    const X_IS_NEGATIVE: bool = false;
+    const TWIST_TYPE: TwistType = TwistType::D;
+    type Fp = Fq;
+    type Fp2Params = Fq2Parameters;
+    type Fp6Params = Fq6Parameters;
+    type Fp12Params = Fq12Parameters;
+    type G1Parameters = self::g1::Parameters;
+    type G2Parameters = self::g2::Parameters;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::{Fq12, Fq2, Fq6, Fr};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::{FftField, Field, FpParameters, One, PrimeField, SquareRootField, UniformRand, Zero};
+    use ark_std::test_rng;
+
+    fn check_frobenius<F: Field>(degree: usize) {
+        let mut rng = test_rng();
+        let q = <Fq as PrimeField>::Params::MODULUS;
+        for _ in 0..4 {
+            let a = F::rand(&mut rng);
+            let mut b = a;
+            for power in 0..degree {
+                let mut c = a;
+                c.frobenius_map(power);
+                assert_eq!(c, b);
+                b = b.pow(q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let mut rng = test_rng();
+        let root = Fr::two_adic_root_of_unity();
+        assert_eq!(root.pow([1u64 << 46]), -Fr::one());
+        assert_eq!(Fq::two_adic_root_of_unity().pow([1u64 << 45]), -Fq::one());
+        for _ in 0..4 {
+            let a = Fq2::rand(&mut rng);
+            let square = a.square();
+            assert_eq!(square.sqrt().unwrap().square(), square);
+            assert_eq!(a * a.inverse().unwrap(), Fq2::one());
+        }
+        check_frobenius::<Fq2>(2);
+        check_frobenius::<Fq6>(6);
+        check_frobenius::<Fq12>(12);
+    }
+
+    #[test]
+    fn test_generators() {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        assert!(g1.is_on_curve() && g1.is_in_correct_subgroup_assuming_on_curve());
+        assert!(g2.is_on_curve() && g2.is_in_correct_subgroup_assuming_on_curve());
+        let r = <Fr as PrimeField>::Params::MODULUS;
+        assert!(g1.mul(r).is_zero() && g2.mul(r).is_zero());
+        assert!(!g1.is_zero() && !g2.is_zero());
+    }
+
+    #[test]
+    fn test_pairing() {
+        let mut rng = test_rng();
+        let a = Fr::rand(&mut rng);
+        let b = Fr::rand(&mut rng);
+        let g1 = G1Projective::prime_subgroup_generator();
+        let g2 = G2Projective::prime_subgroup_generator();
+        let e = Bls12_377::pairing(g1, g2);
+        assert_ne!(e, Fq12::one());
+        assert_eq!(e.pow(<Fr as PrimeField>::Params::MODULUS), Fq12::one());
+        let lhs = Bls12_377::pairing(g1.mul(a.into_repr()), g2.mul(b.into_repr()));
+        assert_eq!(lhs, e.pow((a * b).into_repr()));
+    }
+}
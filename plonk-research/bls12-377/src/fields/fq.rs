@@ -0,0 +1,121 @@
+use ark_ff::{
+    biginteger::BigInteger384 as BigInteger,
+    field_new,
+    fields::{FftParameters, Fp384, Fp384Parameters, FpParameters},
+};
+
+pub type Fq = Fp384<FqParameters>;
+
+pub struct FqParameters;
+
+impl Fp384Parameters for FqParameters {}
+impl FftParameters for FqParameters {
+    type BigInt = BigInteger;
+
+    const TWO_ADICITY: u32 = 46;
+
+    /// TWO_ADIC_ROOT_OF_UNITY = GENERATOR^T, in Montgomery form
+    #[rustfmt::skip]
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger = BigInteger([
+        0xdfcae622791aab1e,
+        0x720bc7a4bf05c59c,
+        0x259d41860d7882d6,
+        0xd82b4258b1e4da96,
+        0xb7f9a1cc67b4e064,
+        0xfda47f566e4289,
+    ]);
+}
+impl FpParameters for FqParameters {
+    /// MODULUS = 258664426012969094010652733694893533536393512754914660539884262666720468348340822774968888139573360124440321458177
+    #[rustfmt::skip]
+    const MODULUS: BigInteger = BigInteger([
+        0x8508c00000000001,
+        0x170b5d4430000000,
+        0x1ef3622fba094800,
+        0x1a22d9f300f5138f,
+        0xc63b05c06ca1493b,
+        0x1ae3a4617c510ea,
+    ]);
+
+    const MODULUS_BITS: u32 = 377;
+
+    const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+
+    const REPR_SHAVE_BITS: u32 = 7;
+
+    /// R = 85013442423176922659824578519796707547925331718418265885885478904210582549405549618995257669764901891699128663912
+    #[rustfmt::skip]
+    const R: BigInteger = BigInteger([
+        0x2cdffffffffff68,
+        0x51409f837fffffb1,
+        0x9f7db3a98a7d3ff2,
+        0x7b4e97b76e7c6305,
+        0x4cf495bf803c84e8,
+        0x8d6661e2fdf49a,
+    ]);
+
+    #[rustfmt::skip]
+    const R2: BigInteger = BigInteger([
+        0xb786686c9400cd22,
+        0x329fcaab00431b1,
+        0x22a5f11162d6b46d,
+        0xbfdf7d03827dc3ac,
+        0x837e92f041790bf9,
+        0x6dfccb1e914b88,
+    ]);
+
+    const INV: u64 = 0x8508bfffffffffff;
+
+    /// GENERATOR = 15
+    /// Encoded in Montgomery form, so the value here is
+    /// 15 * R % q = 240543932295777463854757743017376479073305924756615346128745132896276864847719953185053312488180087877725644125972
+    #[rustfmt::skip]
+    const GENERATOR: BigInteger = BigInteger([
+        0x15eefffffffff714,
+        0x669be3a3bffffb5d,
+        0xdc8ffe3035319f32,
+        0xd10f7bf375757f17,
+        0x6968af36d106a4b2,
+        0x19016a3edcd115f,
+    ]);
+
+    #[rustfmt::skip]
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x4284600000000000,
+        0xb85aea218000000,
+        0x8f79b117dd04a400,
+        0x8d116cf9807a89c7,
+        0x631d82e03650a49d,
+        0xd71d230be28875,
+    ]);
+
+    // T and T_MINUS_ONE_DIV_TWO, where MODULUS - 1 = 2^S * T
+    // For T coprime to 2
+
+    // T = (MODULUS - 1) / 2^S =
+    // 3675842578061421676390135839012792950148785745837396071634149488243117337281387659330802195819009059
+    #[rustfmt::skip]
+    const T: BigInteger = BigInteger([
+        0x7510c00000021423,
+        0x88bee82520005c2d,
+        0x67cc03d44e3c7bcd,
+        0x1701b28524ec688b,
+        0xe9185f1443ab18ec,
+        0x6b8,
+    ]);
+
+    // (T - 1) / 2 =
+    // 1837921289030710838195067919506396475074392872918698035817074744121558668640693829665401097909504529
+    #[rustfmt::skip]
+    const T_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0xba88600000010a11,
+        0xc45f741290002e16,
+        0xb3e601ea271e3de6,
+        0xb80d94292763445,
+        0x748c2f8a21d58c76,
+        0x35c,
+    ]);
+}
+
+pub const FQ_ONE: Fq = field_new!(Fq, "1");
+pub const FQ_ZERO: Fq = field_new!(Fq, "0");
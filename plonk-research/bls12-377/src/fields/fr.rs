@@ -0,0 +1,101 @@
+use ark_ff::{
+    biginteger::BigInteger256 as BigInteger,
+    fields::{FftParameters, Fp256, Fp256Parameters, FpParameters},
+};
+
+pub type Fr = Fp256<FrParameters>;
+
+pub struct FrParameters;
+
+impl Fp256Parameters for FrParameters {}
+impl FftParameters for FrParameters {
+    type BigInt = BigInteger;
+
+    const TWO_ADICITY: u32 = 47;
+
+    /// TWO_ADIC_ROOT_OF_UNITY = GENERATOR^T, in Montgomery form
+    #[rustfmt::skip]
+    const TWO_ADIC_ROOT_OF_UNITY: BigInteger = BigInteger([
+        0xaf80da4dda3ad648,
+        0x5e223adbfc381dac,
+        0x3ba0666b2f92525,
+        0xf906c5b3befb0ce,
+    ]);
+}
+impl FpParameters for FrParameters {
+    /// MODULUS = 8444461749428370424248824938781546531375899335154063827935233455917409239041
+    #[rustfmt::skip]
+    const MODULUS: BigInteger = BigInteger([
+        0xa11800000000001,
+        0x59aa76fed0000001,
+        0x60b44d1e5c37b001,
+        0x12ab655e9a2ca556,
+    ]);
+
+    const MODULUS_BITS: u32 = 253;
+
+    const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+
+    const REPR_SHAVE_BITS: u32 = 3;
+
+    /// R = 6014086494747379908336260804527802945383293308637734276299549080986809532403
+    #[rustfmt::skip]
+    const R: BigInteger = BigInteger([
+        0x7d1c7ffffffffff3,
+        0x7257f50f6ffffff2,
+        0x16d81575512c0fee,
+        0xd4bda322bbb9a9d,
+    ]);
+
+    #[rustfmt::skip]
+    const R2: BigInteger = BigInteger([
+        0x25d577bab861857b,
+        0xcc2c27b58860591f,
+        0xa7cc008fe5dc8593,
+        0x11fdae7eff1c939,
+    ]);
+
+    const INV: u64 = 0xa117fffffffffff;
+
+    /// GENERATOR = 22
+    /// Encoded in Montgomery form, so the value here is
+    /// 22 * R % q = 5642976643016801619665363617888466827793962762719196659561577942948671127251
+    #[rustfmt::skip]
+    const GENERATOR: BigInteger = BigInteger([
+        0x296c7ffffffffed3,
+        0x929216656ffffec7,
+        0x4c01534d92860e69,
+        0xc79cfc4b9819970,
+    ]);
+
+    #[rustfmt::skip]
+    const MODULUS_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x8508c00000000000,
+        0xacd53b7f68000000,
+        0x305a268f2e1bd800,
+        0x955b2af4d1652ab,
+    ]);
+
+    // T and T_MINUS_ONE_DIV_TWO, where MODULUS - 1 = 2^S * T
+    // For T coprime to 2
+
+    // T = (MODULUS - 1) / 2^S =
+    // 60001509534603559531609739528203892656505753216962260608619555
+    #[rustfmt::skip]
+    const T: BigInteger = BigInteger([
+        0xedfda00000021423,
+        0x9a3cb86f6002b354,
+        0xcabd34594aacc168,
+        0x2556,
+    ]);
+
+    // (T - 1) / 2 =
+    // 30000754767301779765804869764101946328252876608481130304309777
+    #[rustfmt::skip]
+    const T_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([
+        0x76fed00000010a11,
+        0x4d1e5c37b00159aa,
+        0x655e9a2ca55660b4,
+        0x12ab,
+    ]);
+}
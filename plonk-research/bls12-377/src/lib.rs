@@ -0,0 +1,7 @@
+//BLS12-377 over arkworks 0.3, the parameters of the upstream ark-bls12-377 with the 0.3 field and
+//curve traits, so the curve plugs into the PairingEngine the rest of the workspace is written against.
+mod curves;
+mod fields;
+
+pub use curves::*;
+pub use fields::*;
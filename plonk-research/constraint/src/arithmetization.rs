@@ -1,11 +1,10 @@
 use crate::circuit::Circuit;
 use crate::circuit::PlonkCircuit;
 use crate::circuit::WIRE_COUNT;
+use crate::gate::SELECTOR_COUNT;
 use ark_ff::{FftField, Field};
 use ark_poly::UVPolynomial;
 use ark_poly::{domain::Radix2EvaluationDomain, univariate::DensePolynomial, EvaluationDomain};
-use rand::SeedableRng;
-use rand_chacha::ChaChaRng;
 
 pub trait Arithmetization<F: Field> {
     fn domain_size(&self) -> usize;
@@ -13,9 +12,15 @@ pub trait Arithmetization<F: Field> {
     fn circuit(&self) -> &dyn Circuit<F>;
     fn generate_pi_poly(&self) -> DensePolynomial<F>;
     fn generate_selector_polys(&self) -> Vec<DensePolynomial<F>>;
+    //evaluations over the domain, the wire polys interpolate them
+    fn generate_wire_evals(&self) -> Vec<Vec<F>>;
     fn generate_wire_polys(&self) -> Vec<DensePolynomial<F>>;
     fn generate_identify_perm_polys(&self) -> Vec<DensePolynomial<F>>;
-    fn generate_prod_perm_poly(&self) -> DensePolynomial<F>;
+    fn generate_left_coset_repr(&self) -> Vec<F>;
+    //beta and gamma are Fiat-Shamir challenges, squeezed from the transcript
+    //after the wire poly commitments are absorbed
+    fn generate_prod_perm_evals(&self, beta: F, gamma: F) -> Vec<F>;
+    fn generate_prod_perm_poly(&self, beta: F, gamma: F) -> DensePolynomial<F>;
 }
 
 pub struct PlonkArithmetization<'a, F: FftField> {
@@ -35,40 +40,29 @@ where
     F: FftField,
 {
     pub fn new(circuit: &'a PlonkCircuit<F>) -> Self {
-        Self {
+        Self::with_blinding_rows(circuit, 0)
+    }
+
+    //The execution trace is padded with no-op rows up to the domain size,
+    //and at least `blinding_rows` rows are reserved after the last gate.
+    //A no-op row has zero wire values and selectors, its wires are fixed points
+    //of the copy constraint permutation.
+    pub fn with_blinding_rows(circuit: &'a PlonkCircuit<F>, blinding_rows: usize) -> Self {
+        let mut arith = Self {
             circuit,
-            eval_domain: Radix2EvaluationDomain::new(circuit.gate_count()).unwrap(),
+            eval_domain: Radix2EvaluationDomain::new(circuit.gate_count() + blinding_rows)
+                .unwrap(),
             encode_identify_orig: vec![],
             encode_identify_perm: vec![],
-        }
-    }
-
-    pub fn generate_left_coset_repr(&self) -> Vec<F> {
-        let sg_order = self.eval_domain.size();
-        let mut k_reprs: Vec<F> = vec![];
-        let mut rng = ChaChaRng::from_seed([0u8; 32]);
-        for i in 0..WIRE_COUNT {
-            if i == 0 {
-                k_reprs.push(F::one());
-            } else {
-                let mut r_k_repr = F::rand(&mut rng);
-                //check r_k_repr must different from other left coset reperset,
-                //if left coset aH == bH, then a^-1 * b belongs to H,
-                //because H is a cycle subgroup with order r,so (a^-1 * b) ^ r = 1
-                while k_reprs.iter().any(|k_repr| {
-                    (r_k_repr.inverse().unwrap() * k_repr).pow([sg_order as u64]) == F::one()
-                }) {
-                    r_k_repr = F::rand(&mut rng);
-                }
-                k_reprs.push(r_k_repr);
-            }
-        }
-        k_reprs
+        };
+        arith.init_encode_identify();
+        arith
     }
 
     pub fn init_encode_identify(&mut self) {
         //Array contains k, represents a left coset(eg.kH,H is a cycle subgroup)
         let k_repr: Vec<F> = self.generate_left_coset_repr();
+        self.encode_identify_orig.clear();
         let cycle_sg: Vec<F> = self.eval_domain.elements().collect();
         for &g in cycle_sg.iter() {
             for &k in k_repr.iter() {
@@ -85,7 +79,8 @@ where
                 var_vec[wire_var].push((gate_id, wire_id));
             }
         }
-        self.encode_identify_perm = vec![F::zero(); self.circuit.gate_count() * WIRE_COUNT];
+        //padding rows are not in any cycle, permutation keeps them unchanged
+        self.encode_identify_perm = self.encode_identify_orig.clone();
 
         for vars in var_vec.iter_mut() {
             //vars contain same value var with the position(gate_id,wire_id) of var_table
@@ -101,6 +96,15 @@ where
             }
         }
     }
+
+    //witness at (gate_id,wire_id) of the padded execution trace
+    fn wire_value(&self, gate_id: usize, wire_id: usize) -> F {
+        if gate_id < self.circuit.gate_count() {
+            self.circuit.witness(self.circuit.var_table[gate_id][wire_id])
+        } else {
+            F::zero()
+        }
+    }
 }
 
 impl<'a, F> Arithmetization<F> for PlonkArithmetization<'a, F>
@@ -119,32 +123,64 @@ where
         self.circuit
     }
 
+    //k_i = g^i, g is the multiplicative generator of F, so k_0 = 1 keeps H itself.
+    //Cosets k_iH and k_jH (i > j) are equal iff g^(i-j) belongs to H, i.e. g^((i-j)n) = 1.
+    //g has order p-1 = 2^s * t (t odd) and n is a power of two dividing 2^s, so that needs
+    //t | i-j, impossible since 0 < i-j < WIRE_COUNT < t. Hence all cosets are disjoint.
+    fn generate_left_coset_repr(&self) -> Vec<F> {
+        let g = F::multiplicative_generator();
+        (0..WIRE_COUNT as u64).map(|i| g.pow([i])).collect()
+    }
+
     fn generate_pi_poly(&self) -> DensePolynomial<F> {
-        let evals = self.circuit.pi_value();
+        //public input is placed at the row of its pi gate
+        let mut evals = vec![F::zero(); self.eval_domain.size()];
+        for (gate_id, val) in self.circuit.pi_gates().into_iter().zip(self.circuit.pi_value()) {
+            evals[gate_id] = val;
+        }
         DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(&evals))
     }
 
     fn generate_selector_polys(&self) -> Vec<DensePolynomial<F>> {
-        let selector_polys = vec![];
-        selector_polys
+        //one poly per selector, in the order of Q_LC, Q_MUL, Q_O, Q_C
+        let mut evals_vec = vec![vec![]; SELECTOR_COUNT];
+        for gate in self.circuit.gates.iter() {
+            for (evals, selector) in evals_vec.iter_mut().zip(gate.selectors()) {
+                evals.push(selector);
+            }
+        }
+        //no-op rows
+        for evals in evals_vec.iter_mut() {
+            evals.resize(self.eval_domain.size(), F::zero());
+        }
+        evals_vec
+            .iter()
+            .map(|evals| DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(evals)))
+            .collect()
+    }
+
+    fn generate_wire_evals(&self) -> Vec<Vec<F>> {
+        (0..WIRE_COUNT)
+            .map(|wire_id| {
+                (0..self.eval_domain.size())
+                    .map(|gate_id| self.wire_value(gate_id, wire_id))
+                    .collect()
+            })
+            .collect()
     }
 
     fn generate_wire_polys(&self) -> Vec<DensePolynomial<F>> {
-        let mut evals_vec = vec![vec![];WIRE_COUNT];
-        for gate in self.circuit.var_table.iter(){
-            for i in 0..WIRE_COUNT {
-                evals_vec[i].push(self.circuit.witness(gate[i])); 
-            }
-        }
-        let wire_polys = evals_vec.iter().map(|evals|DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(evals))).collect();
-        wire_polys
+        self.generate_wire_evals()
+            .iter()
+            .map(|evals| DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(evals)))
+            .collect()
     }
 
     fn generate_identify_perm_polys(&self) -> Vec<DensePolynomial<F>> {
         let mut identify_perm_polys: Vec<DensePolynomial<F>> = vec![];
         for wire_id in 0..WIRE_COUNT {
             let mut evals = vec![];
-            for gate_id in 0..self.circuit.gate_count() {
+            for gate_id in 0..self.eval_domain.size() {
                 evals.push(self.encode_identify_perm[gate_id * WIRE_COUNT + wire_id]);
             }
             // FFT interpolation
@@ -154,19 +190,16 @@ where
         identify_perm_polys
     }
 
-    fn generate_prod_perm_poly(&self) -> DensePolynomial<F> {
-        let mut rng = ChaChaRng::from_seed([0u8; 32]);
-        let beta = F::rand(&mut rng);
-        let gamma = F::rand(&mut rng);
-        let mut evals: Vec<F> = vec![];
+    fn generate_prod_perm_evals(&self, beta: F, gamma: F) -> Vec<F> {
+        //z(w^0) = 1, z(w^(i+1)) = z(w^i) * prod_orig(i) / prod_perm(i)
+        let mut evals: Vec<F> = vec![F::one()];
         let mut eval = F::one();
-        for gate_id in 0..self.circuit.gate_count() {
+        for gate_id in 0..self.eval_domain.size() - 1 {
             let mut prod_orig = F::one();
             let mut prod_perm = F::one();
 
             for wire_id in 0..WIRE_COUNT {
-                let var_id = self.circuit.var_table[gate_id][wire_id];
-                let val = self.circuit.witness(var_id);
+                let val = self.wire_value(gate_id, wire_id);
                 let identify_orig = self.encode_identify_orig[gate_id * WIRE_COUNT + wire_id];
                 prod_orig *= val + beta * identify_orig + gamma;
                 let identify_perm = self.encode_identify_perm[gate_id * WIRE_COUNT + wire_id];
@@ -175,29 +208,32 @@ where
             eval *= prod_orig / prod_perm;
             evals.push(eval);
         }
+        evals
+    }
+
+    fn generate_prod_perm_poly(&self, beta: F, gamma: F) -> DensePolynomial<F> {
         // FFT interpolation
-        let prod_perm_poly =
-            DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(&evals));
-        prod_perm_poly
+        DensePolynomial::from_coefficients_vec(self.eval_domain.ifft(&self.generate_prod_perm_evals(beta, gamma)))
     }
 }
 
 #[cfg(test)]
-
 pub mod test {
-    use crate::arithmetization::PlonkArithmetization;
-    use crate::circuit::{Circuit, PlonkCircuit};
-    use ark_bls12_381::Fq as Fq381;
-    use ark_poly::UVPolynomial;
+    use crate::arithmetization::{Arithmetization, PlonkArithmetization};
+    use crate::circuit::{Circuit, PlonkCircuit, WIRE_COUNT};
+    use crate::gate::{Q_LC, Q_MUL, Q_O, SELECTOR_COUNT};
+    use ark_bls12_381::Fr;
+    use ark_bn254::Fr as Bn254Fr;
+    use ark_ff::{FftField, FftParameters, One, PrimeField, Zero};
+    use ark_poly::{Polynomial, UVPolynomial};
     use ark_poly::{univariate::DensePolynomial, EvaluationDomain};
 
     #[test]
     fn test_ifft_usage() {
-        let circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let mut arith = PlonkArithmetization::new(&circuit);
-        arith.init_encode_identify();
+        let circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let arith = PlonkArithmetization::new(&circuit);
         assert_eq!(arith.circuit.gate_count(), 2);
-        let evals = [Fq381::from(5u32), Fq381::from(2u32)];
+        let evals = [Fr::from(5u32), Fr::from(2u32)];
         let poly = DensePolynomial::from_coefficients_vec(arith.eval_domain.ifft(&evals));
         let poly_evals = poly.evaluate_over_domain(arith.eval_domain);
         assert_eq!(evals[0], poly_evals[0]);
@@ -205,4 +241,114 @@ pub mod test {
             assert_eq!(e, pe);
         }
     }
+
+    #[test]
+    fn test_selector_polys() {
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(2u32), false);
+        let b = circuit.create_var(Fr::from(3u32), false);
+        circuit.add(a, b);
+        circuit.mul(a, b);
+        let arith = PlonkArithmetization::new(&circuit);
+        let selector_polys = arith.generate_selector_polys();
+        assert_eq!(selector_polys.len(), SELECTOR_COUNT);
+        for (gate_id, omega) in arith.eval_domain.elements().enumerate() {
+            let selectors = circuit.gates[gate_id].selectors();
+            for (poly, selector) in selector_polys.iter().zip(selectors) {
+                assert_eq!(poly.evaluate(&omega), selector);
+            }
+        }
+        //mul gate: q_mul[0] = 1, q_o = 1
+        let omega = arith.eval_domain.element(3);
+        assert_eq!(selector_polys[Q_MUL].evaluate(&omega), Fr::from(1u32));
+        assert_eq!(selector_polys[Q_O].evaluate(&omega), Fr::from(1u32));
+        assert_eq!(selector_polys[Q_LC].evaluate(&omega), Fr::from(0u32));
+    }
+
+    fn build_circuit<F: PrimeField>(gate_count: usize) -> PlonkCircuit<F> {
+        let mut circuit: PlonkCircuit<F> = PlonkCircuit::new();
+        let mut a = circuit.create_var(F::from(2u32), false);
+        let b = circuit.create_var(F::from(3u32), false);
+        while circuit.gate_count() < gate_count {
+            a = if circuit.gate_count() % 2 == 0 {
+                circuit.add(a, b)
+            } else {
+                circuit.mul(a, b)
+            };
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_padding() {
+        for (gate_count, blinding_rows, domain_size) in
+            [(3, 0, 4), (5, 0, 8), (17, 0, 32), (3, 1, 4), (5, 3, 8), (17, 16, 64)]
+        {
+            let circuit = build_circuit::<Fr>(gate_count);
+            assert_eq!(circuit.gate_count(), gate_count);
+            let arith = PlonkArithmetization::with_blinding_rows(&circuit, blinding_rows);
+            assert_eq!(arith.domain_size(), domain_size);
+
+            let wire_polys = arith.generate_wire_polys();
+            let selector_polys = arith.generate_selector_polys();
+            let identify_perm_polys = arith.generate_identify_perm_polys();
+            let k_reprs = arith.generate_left_coset_repr();
+            for (gate_id, omega) in arith.eval_domain.elements().enumerate() {
+                for wire_id in 0..WIRE_COUNT {
+                    let wire_eval = wire_polys[wire_id].evaluate(&omega);
+                    let identify_perm_eval = identify_perm_polys[wire_id].evaluate(&omega);
+                    if gate_id < gate_count {
+                        let var = circuit.var_table[gate_id][wire_id];
+                        assert_eq!(wire_eval, circuit.witness(var));
+                    } else {
+                        //no-op row
+                        assert_eq!(wire_eval, Fr::zero());
+                        assert_eq!(identify_perm_eval, k_reprs[wire_id] * omega);
+                    }
+                }
+                if gate_id >= gate_count {
+                    for poly in selector_polys.iter() {
+                        assert_eq!(poly.evaluate(&omega), Fr::zero());
+                    }
+                }
+            }
+
+            //the grand product wraps around to 1 over the whole padded domain
+            let beta = Fr::from(7u32);
+            let gamma = Fr::from(11u32);
+            let prod_perm_poly = arith.generate_prod_perm_poly(beta, gamma);
+            let last = domain_size - 1;
+            let mut eval = prod_perm_poly.evaluate(&arith.eval_domain.element(last));
+            for wire_id in 0..WIRE_COUNT {
+                let val = arith.wire_value(last, wire_id);
+                eval *= val + beta * arith.encode_identify_orig[last * WIRE_COUNT + wire_id] + gamma;
+                eval /= val + beta * arith.encode_identify_perm[last * WIRE_COUNT + wire_id] + gamma;
+            }
+            assert_eq!(eval, Fr::one());
+        }
+    }
+
+    fn check_coset_reprs<F: PrimeField>() {
+        let circuit: PlonkCircuit<F> = build_circuit(3);
+        let arith = PlonkArithmetization::new(&circuit);
+        let k_reprs = arith.generate_left_coset_repr();
+        assert_eq!(k_reprs.len(), WIRE_COUNT);
+        assert_eq!(k_reprs[0], F::one());
+        assert_eq!(k_reprs, PlonkArithmetization::new(&circuit).generate_left_coset_repr());
+
+        //k_i^-1 * k_j must not lie in the largest 2-adic subgroup, which contains every domain
+        let max_order = 1u64 << <F as FftField>::FftParams::TWO_ADICITY;
+        for i in 0..WIRE_COUNT {
+            for j in 0..i {
+                let quot = k_reprs[j].inverse().unwrap() * k_reprs[i];
+                assert_ne!(quot.pow([max_order]), F::one());
+            }
+        }
+    }
+
+    #[test]
+    fn test_coset_reprs() {
+        check_coset_reprs::<Fr>();
+        check_coset_reprs::<Bn254Fr>();
+    }
 }
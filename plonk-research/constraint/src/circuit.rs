@@ -1,7 +1,6 @@
 use std::vec;
 
 pub(crate) use crate::gate::arithmetic::*;
-use crate::gate::*;
 use ark_ff::Field;
 
 pub type VarId = usize;
@@ -27,6 +26,8 @@ pub trait Circuit<F: Field> {
 
     fn pi_value(&self) -> Vec<F>;
 
+    fn pi_gates(&self) -> Vec<GateId>;
+
     fn create_var(&mut self, val: F, is_pi: bool) -> VarId;
 
     fn witness(&self, id: VarId) -> F;
@@ -59,7 +60,7 @@ pub struct PlonkCircuit<F: Field> {
 
     pi_vars: Vec<VarId>,
 
-    gates: Vec<Box<dyn Gate<F>>>,
+    pub(crate) gates: Vec<Box<dyn Gate<F>>>,
 
     pi_gates: Vec<GateId>,
 
@@ -117,6 +118,15 @@ where
     }
 }
 
+impl<F> Default for PlonkCircuit<F>
+where
+    F: Field,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<F> Circuit<F> for PlonkCircuit<F>
 where
     F: Field,
@@ -137,6 +147,10 @@ where
         self.pi_vars.iter().map(|&id|self.witness(id)).collect()
     }
 
+    fn pi_gates(&self) -> Vec<GateId> {
+        self.pi_gates.clone()
+    }
+
     fn witness(&self, id: VarId) -> F {
         self.witness[id]
     }
@@ -215,10 +229,8 @@ where
         }
         //check other gate
         for id in 0..self.gate_count() {
-            if !self.is_pi_gate(id) {
-                if !self.check_gate(id, F::zero()) {
-                    return false;
-                }
+            if !self.is_pi_gate(id) && !self.check_gate(id, F::zero()) {
+                return false;
             }
         }
         true
@@ -226,83 +238,82 @@ where
 }
 
 #[cfg(test)]
-
 pub mod test {
     use crate::circuit::*;
-    use ark_bls12_381::Fq as Fq381;
+    use ark_bls12_381::Fr;
 
     #[test]
     fn test_add_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(2u32), false);
-        let b = circuit.create_var(Fq381::from(3u32), false);
-        let c = circuit.create_var(Fq381::from(5u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(2u32), false);
+        let b = circuit.create_var(Fr::from(3u32), false);
+        let c = circuit.create_var(Fr::from(5u32), false);
         circuit.add_gate(a, b, c);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_sub_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(3u32), false);
-        let b = circuit.create_var(Fq381::from(2u32), false);
-        let c = circuit.create_var(Fq381::from(1u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(3u32), false);
+        let b = circuit.create_var(Fr::from(2u32), false);
+        let c = circuit.create_var(Fr::from(1u32), false);
         circuit.sub_gate(a, b, c);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_mul_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(2u32), false);
-        let b = circuit.create_var(Fq381::from(3u32), false);
-        let c = circuit.create_var(Fq381::from(6u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(2u32), false);
+        let b = circuit.create_var(Fr::from(3u32), false);
+        let c = circuit.create_var(Fr::from(6u32), false);
         circuit.mul_gate(a, b, c);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_equal_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(6u32), false);
-        let b = circuit.create_var(Fq381::from(6u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(6u32), false);
+        let b = circuit.create_var(Fr::from(6u32), false);
         circuit.equal_gate(a, b);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_const_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let val = Fq381::from(3u32);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let val = Fr::from(3u32);
         let a = circuit.create_var(val, false);
         circuit.const_gate(a, val);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_pi_gate() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let val = Fq381::from(3u32);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let val = Fr::from(3u32);
         circuit.create_var(val, true);
         assert!(circuit.check_circuit(&[val]));
     }
 
     #[test]
     fn test_add() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(6u32), false);
-        let b = circuit.create_var(Fq381::from(6u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(6u32), false);
+        let b = circuit.create_var(Fr::from(6u32), false);
         circuit.add(a, b);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_sub() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(6u32), false);
-        let b = circuit.create_var(Fq381::from(2u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(6u32), false);
+        let b = circuit.create_var(Fr::from(2u32), false);
         circuit.sub(a, b);
         assert!(circuit.check_circuit(&[]));
     }
     #[test]
     fn test_mul() {
-        let mut circuit: PlonkCircuit<Fq381> = PlonkCircuit::new();
-        let a = circuit.create_var(Fq381::from(6u32), false);
-        let b = circuit.create_var(Fq381::from(6u32), false);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(6u32), false);
+        let b = circuit.create_var(Fr::from(6u32), false);
         circuit.mul(a, b);
         assert!(circuit.check_circuit(&[]));
     }
@@ -7,6 +7,14 @@ pub const INPUT_COUNT: usize = 4;
 
 pub const MUL_SELECTOR_COUNT: usize = 2;
 
+//Selector polys are ordered as q_lc[0..INPUT_COUNT], q_mul[0..MUL_SELECTOR_COUNT], q_o, q_c.
+//The arithmetization, prover, verifier and transcript all follow this order.
+pub const SELECTOR_COUNT: usize = INPUT_COUNT + MUL_SELECTOR_COUNT + 2;
+pub const Q_LC: usize = 0;
+pub const Q_MUL: usize = Q_LC + INPUT_COUNT;
+pub const Q_O: usize = Q_MUL + MUL_SELECTOR_COUNT;
+pub const Q_C: usize = Q_O + 1;
+
 pub trait Gate<F: Field>: Downcast {
     fn name(&self) -> &str;
 
@@ -25,4 +33,13 @@ pub trait Gate<F: Field>: Downcast {
     fn q_c(&self) -> F {
         F::zero()
     }
+
+    fn selectors(&self) -> [F; SELECTOR_COUNT] {
+        let mut selectors = [F::zero(); SELECTOR_COUNT];
+        selectors[Q_LC..Q_MUL].copy_from_slice(&self.q_lc());
+        selectors[Q_MUL..Q_O].copy_from_slice(&self.q_mul());
+        selectors[Q_O] = self.q_o();
+        selectors[Q_C] = self.q_c();
+        selectors
+    }
 }
@@ -1,3 +1,3 @@
 pub mod arithmetization;
-mod circuit;
-mod gate;
+pub mod circuit;
+pub mod gate;
@@ -0,0 +1,65 @@
+//Times setup, preprocess, prove and verify with KZG10 on BLS12-381 for circuits of 2^k gates,
+//k defaults to 16 and 20: cargo bench -p iop [--features parallel] -- 16 20
+//Each phase also prints the peak resident memory of the process so far, where Linux reports it.
+use ark_bls12_381::{Bls12_381, Fr};
+use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+use constraint::circuit::{Circuit, PlonkCircuit};
+use iop::snark::{PlonkSnark, Snark};
+use pcs::kzg10::KZG10;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+
+type Plonk = PlonkSnark<KZG10<Bls12_381>>;
+
+fn build_circuit(log_gates: usize) -> PlonkCircuit<Fr> {
+    let mut circuit = PlonkCircuit::new();
+    let mut a = circuit.create_var(Fr::from(3u32), false);
+    let b = circuit.create_var(Fr::from(5u32), false);
+    while circuit.gate_count() < 1 << log_gates {
+        a = circuit.mul(a, b);
+    }
+    circuit
+}
+
+//VmHWM of /proc/self/status
+fn peak_memory() -> String {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+            let kb: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+            Some(format!("{} MB", kb / 1024))
+        })
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+fn bench(log_gates: usize) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let circuit = build_circuit(log_gates);
+    let arith = PlonkArithmetization::new(&circuit);
+    println!("2^{} gates, domain size {}", log_gates, arith.domain_size());
+
+    let start = Instant::now();
+    let srs = Plonk::setup(arith.domain_size() + 2, &mut rng);
+    println!("  setup      {:>10.2?}  peak {}", start.elapsed(), peak_memory());
+    let start = Instant::now();
+    let (pk, vk) = Plonk::preprocess(&srs, &arith);
+    println!("  preprocess {:>10.2?}  peak {}", start.elapsed(), peak_memory());
+    let start = Instant::now();
+    let proof = Plonk::prove(&arith, &mut rng, &pk);
+    println!("  prove      {:>10.2?}  peak {}", start.elapsed(), peak_memory());
+    let start = Instant::now();
+    assert!(Plonk::verify(&vk, &proof, &[]));
+    println!("  verify     {:>10.2?}  peak {}", start.elapsed(), peak_memory());
+}
+
+fn main() {
+    let mut sizes: Vec<usize> = std::env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+    if sizes.is_empty() {
+        sizes = vec![16, 20];
+    }
+    for log_gates in sizes {
+        bench(log_gates);
+    }
+}
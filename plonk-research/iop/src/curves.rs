@@ -0,0 +1,111 @@
+//PLONK with KZG10 over the pairing friendly curves enabled by cargo features,
+//proofs are over the scalar field Fr of the curve.
+
+#[cfg(feature = "bn254")]
+pub mod bn254 {
+    use crate::snark::PlonkSnark;
+    use crate::transcript::{KeccakTranscript, PoseidonTranscript};
+    use pcs::kzg10::KZG10;
+
+    pub use ark_bn254::{Bn254, Fr};
+
+    pub type Plonk = PlonkSnark<KZG10<Bn254>>;
+
+    //Challenges from keccak256, proofs the generated Solidity verifier accepts
+    pub type KeccakPlonk = PlonkSnark<KZG10<Bn254>, KeccakTranscript<Fr>>;
+
+    //Challenges from a Poseidon sponge over Fr, for proofs verified inside a circuit
+    pub type PoseidonPlonk = PlonkSnark<KZG10<Bn254>, PoseidonTranscript<Fr>>;
+}
+
+#[cfg(feature = "bls12-381")]
+pub mod bls12_381 {
+    use crate::snark::PlonkSnark;
+    use crate::transcript::PoseidonTranscript;
+    use pcs::kzg10::KZG10;
+
+    pub use ark_bls12_381::{Bls12_381, Fr};
+
+    pub type Plonk = PlonkSnark<KZG10<Bls12_381>>;
+
+    //Challenges from a Poseidon sponge over Fr, for proofs verified inside a circuit
+    pub type PoseidonPlonk = PlonkSnark<KZG10<Bls12_381>, PoseidonTranscript<Fr>>;
+}
+
+#[cfg(feature = "bls12-377")]
+pub mod bls12_377 {
+    use crate::snark::PlonkSnark;
+    use pcs::kzg10::KZG10;
+
+    pub use ark_bls12_377::{Bls12_377, Fr};
+
+    //No Poseidon transcript: 5 divides r - 1, x^5 is not a permutation of this Fr
+    pub type Plonk = PlonkSnark<KZG10<Bls12_377>>;
+}
+
+#[cfg(all(test, any(feature = "bn254", feature = "bls12-381", feature = "bls12-377")))]
+pub mod test {
+    use crate::serialize::{Compression, Versioned};
+    use crate::snark::test::build_chain_circuit;
+    use crate::snark::{Hiding, OpeningMode, PlonkSnark, Proof, Snark, Vk};
+    use ark_ec::PairingEngine;
+    use ark_ff::One;
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use constraint::circuit::Circuit;
+    use pcs::kzg10::KZG10;
+    use pcs::{CurveAffine, PolynomialCommitment};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn check_prove_and_verify<E>(seed: u64)
+    where
+        E: PairingEngine,
+        E::G1Affine: CurveAffine,
+        E::G2Affine: CurveAffine,
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let circuits = [build_chain_circuit::<E::Fr>(6, 5), build_chain_circuit(20, 11)];
+        let ariths: Vec<_> = circuits.iter().map(PlonkArithmetization::new).collect();
+        let pis: Vec<Vec<E::Fr>> = circuits.iter().map(|c| c.pi_value()).collect();
+        let srs = PlonkSnark::<KZG10<E>>::setup(ariths[1].domain_size() + 2, &mut rng);
+        let (mut pk, vk) = PlonkSnark::<KZG10<E>>::preprocess(&srs, &ariths[0]);
+        for mode in [OpeningMode::PerPoint, OpeningMode::MultiPoint] {
+            let proof = PlonkSnark::<KZG10<E>>::prove_with_mode(&ariths[0], &mut rng, &pk, mode, Hiding::Blinded);
+            assert!(PlonkSnark::<KZG10<E>>::verify(&vk, &proof, &pis[0]));
+            assert!(!PlonkSnark::<KZG10<E>>::verify(&vk, &proof, &[pis[0][0] + E::Fr::one()]));
+
+            let bytes = proof.to_bytes(Compression::Compressed);
+            let decoded = Proof::<KZG10<E>>::from_bytes(&bytes).unwrap();
+            assert!(PlonkSnark::<KZG10<E>>::verify(&vk, &decoded, &pis[0]));
+        }
+        let decoded = Vk::<KZG10<E>>::from_bytes(&vk.to_bytes(Compression::Uncompressed)).unwrap();
+        assert!(KZG10::<E>::same_params(&decoded.vk, &vk.vk));
+
+        pk.precompute_commit_tables(4);
+        let proof = PlonkSnark::<KZG10<E>>::prove(&ariths[0], &mut rng, &pk);
+        assert!(PlonkSnark::<KZG10<E>>::verify(&vk, &proof, &pis[0]));
+
+        let (pk2, vk2) = PlonkSnark::<KZG10<E>>::preprocess(&srs, &ariths[1]);
+        let proof = PlonkSnark::<KZG10<E>>::batch_prove(&[&ariths[0], &ariths[1]], &mut rng, &[&pk, &pk2]).unwrap();
+        assert!(PlonkSnark::<KZG10<E>>::batch_verify(&[&vk, &vk2], &proof, &[&pis[0], &pis[1]]));
+        assert!(!PlonkSnark::<KZG10<E>>::batch_verify(&[&vk2, &vk], &proof, &[&pis[1], &pis[0]]));
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_bn254() {
+        check_prove_and_verify::<ark_bn254::Bn254>(0);
+    }
+
+    #[cfg(feature = "bls12-381")]
+    #[test]
+    fn test_bls12_381() {
+        check_prove_and_verify::<ark_bls12_381::Bls12_381>(1);
+    }
+
+    #[cfg(feature = "bls12-377")]
+    #[test]
+    fn test_bls12_377() {
+        check_prove_and_verify::<ark_bls12_377::Bls12_377>(2);
+    }
+}
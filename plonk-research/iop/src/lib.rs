@@ -1,6 +1,13 @@
-#![feature(let_chains)]
-
-mod prover;
-mod snark;
-mod transcript;
+pub mod curves;
+pub mod poseidon;
+pub mod prover;
+mod quotient;
+pub mod serialize;
+pub mod snark;
+#[cfg(feature = "bn254")]
+pub mod solidity;
+pub mod transcript;
 mod utils;
+pub mod verifier;
+#[cfg(test)]
+mod yul;
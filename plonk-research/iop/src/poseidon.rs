@@ -0,0 +1,257 @@
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use std::collections::VecDeque;
+
+//Poseidon permutation with the x^5 S-box over the scalar field of a supported curve.
+//Round constants and the MDS matrix come from the Grain LFSR of the reference script
+//(generate_parameters_grain.sage of the Poseidon authors) seeded with the field size, width and
+//round numbers, any implementation of that script reproduces them.
+pub const ALPHA: u64 = 5;
+
+//rate 2 and capacity 1
+pub const WIDTH: usize = 3;
+
+pub const FULL_ROUNDS: usize = 8;
+
+//The reference round numbers for 128 bit security with x^5 and width 3 over the 254 and 255 bit
+//scalar fields of BN254, BLS12-381 and Pallas, those of the reference test vectors and circomlib
+pub const PARTIAL_ROUNDS: usize = 57;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams<F: PrimeField> {
+    pub width: usize,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    //width constants per round
+    pub round_constants: Vec<Vec<F>>,
+    pub mds: Vec<Vec<F>>,
+}
+
+//Grain LFSR in self shrinking mode, its 80 bit state starts from the parameters of the permutation
+struct Grain {
+    bits: VecDeque<bool>,
+}
+
+impl Grain {
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut bits = VecDeque::with_capacity(80);
+        //prime field, x^alpha S-box
+        for (value, len) in [
+            (1, 2),
+            (0, 4),
+            (field_bits, 12),
+            (width, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+            ((1 << 30) - 1, 30),
+        ] {
+            bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        let mut grain = Self { bits };
+        for _ in 0..160 {
+            grain.clock();
+        }
+        grain
+    }
+
+    fn clock(&mut self) -> bool {
+        let b = &self.bits;
+        let bit = b[62] ^ b[51] ^ b[38] ^ b[23] ^ b[13] ^ b[0];
+        self.bits.pop_front();
+        self.bits.push_back(bit);
+        bit
+    }
+
+    //a pair of bits outputs the second one if the first one is set
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let first = self.clock();
+            let second = self.clock();
+            if first {
+                return second;
+            }
+        }
+    }
+
+    //field size many bits, most significant first
+    fn next_int<F: PrimeField>(&mut self) -> F::BigInt {
+        let bits: Vec<bool> = (0..F::size_in_bits()).map(|_| self.next_bit()).collect();
+        F::BigInt::from_bits_be(&bits)
+    }
+
+    //round constants are sampled by rejection
+    fn next_constant<F: PrimeField>(&mut self) -> F {
+        loop {
+            if let Some(c) = F::from_repr(self.next_int::<F>()) {
+                return c;
+            }
+        }
+    }
+
+    //the MDS entries are reduced modulo the field order instead
+    fn next_reduced<F: PrimeField>(&mut self) -> F {
+        F::from_be_bytes_mod_order(&self.next_int::<F>().to_bytes_be())
+    }
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    pub fn new(width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        assert!(
+            !Self::alpha_divides_order(),
+            "x^{} is not a permutation of the field",
+            ALPHA
+        );
+        assert!(full_rounds.is_multiple_of(2), "full rounds are split before and after the partial rounds");
+        let mut grain = Grain::new(F::size_in_bits(), width, full_rounds, partial_rounds);
+        let round_constants = (0..full_rounds + partial_rounds)
+            .map(|_| (0..width).map(|_| grain.next_constant()).collect())
+            .collect();
+        let mds = Self::cauchy_matrix(&mut grain, width);
+        Self {
+            width,
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    //x^alpha is a permutation iff alpha does not divide p - 1
+    fn alpha_divides_order() -> bool {
+        let mut order = F::Params::MODULUS;
+        order.sub_noborrow(&F::BigInt::from(1));
+        order
+            .to_bytes_be()
+            .iter()
+            .fold(0u64, |rem, b| (rem * 256 + *b as u64) % ALPHA)
+            == 0
+    }
+
+    //M[i][j] = 1 / (x_i + y_j) for 2 * width distinct samples x and y, resampled until every sum
+    //is invertible
+    fn cauchy_matrix(grain: &mut Grain, width: usize) -> Vec<Vec<F>> {
+        loop {
+            let mut samples: Vec<F> = (0..2 * width).map(|_| grain.next_reduced()).collect();
+            while (0..samples.len()).any(|i| samples[..i].contains(&samples[i])) {
+                samples = (0..2 * width).map(|_| grain.next_reduced()).collect();
+            }
+            let (xs, ys) = samples.split_at(width);
+            let mds: Option<Vec<Vec<F>>> = xs
+                .iter()
+                .map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+                .collect();
+            if let Some(mds) = mds {
+                return mds;
+            }
+        }
+    }
+
+    fn sbox(x: &mut F) {
+        *x = x.pow([ALPHA]);
+    }
+
+    fn mix(&self, state: &mut [F]) {
+        let mixed: Vec<F> = self
+            .mds
+            .iter()
+            .map(|row| row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum())
+            .collect();
+        state.copy_from_slice(&mixed);
+    }
+
+    //full rounds apply the S-box to every element, partial rounds to the first one only
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+        let half = self.full_rounds / 2;
+        for (r, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants.iter()) {
+                *s += c;
+            }
+            if r < half || r >= half + self.partial_rounds {
+                state.iter_mut().for_each(Self::sbox);
+            } else {
+                Self::sbox(&mut state[0]);
+            }
+            self.mix(state);
+        }
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonParams<F> {
+    fn default() -> Self {
+        Self::new(WIDTH, FULL_ROUNDS, PARTIAL_ROUNDS)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::PoseidonParams;
+    use ark_ff::{BigInteger, PrimeField};
+
+    fn to_hex<F: PrimeField>(f: &F) -> String {
+        f.into_repr()
+            .to_bytes_be()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn permute<F: PrimeField>(input: [u64; 3]) -> Vec<String> {
+        let params = PoseidonParams::<F>::default();
+        let mut state: Vec<F> = input.iter().map(|i| F::from(*i)).collect();
+        params.permute(&mut state);
+        state.iter().map(to_hex).collect()
+    }
+
+    fn check_params<F: PrimeField>() {
+        let params = PoseidonParams::<F>::default();
+        assert_eq!(params, PoseidonParams::<F>::default());
+        assert_eq!(params.round_constants.len(), 65);
+        assert!(params.round_constants.iter().all(|rc| rc.len() == 3));
+        assert!(params.mds.iter().flatten().all(|m| !m.is_zero()));
+        //different round numbers seed a different stream
+        let other = PoseidonParams::<F>::new(3, 8, 56);
+        assert_ne!(params.round_constants[0], other.round_constants[0]);
+    }
+
+    //The reference test vector poseidonperm_x5_254_3, its first element is circomlib's poseidon([1, 2])
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_bn254_reference_vector() {
+        check_params::<ark_bn254::Fr>();
+        assert_eq!(
+            permute::<ark_bn254::Fr>([0, 1, 2]),
+            [
+                "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+                "0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29",
+                "0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c",
+            ]
+        );
+    }
+
+    //The reference test vector poseidonperm_x5_255_3
+    #[test]
+    fn test_bls12_381_reference_vector() {
+        check_params::<ark_bls12_381::Fr>();
+        assert_eq!(
+            permute::<ark_bls12_381::Fr>([0, 1, 2]),
+            [
+                "28ce19420fc246a05553ad1e8c98f5c9d67166be2c18e9e4cb4b4e317dd2a78a",
+                "51f3e312c95343a896cfd8945ea82ba956c1118ce9b9859b6ea56637b4b1ddc4",
+                "3b2b69139b235626a0bfb56c9527ae66a7bf486ad8c11c14d1da0c69bbe0f79a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pallas_known_answer() {
+        check_params::<ark_pallas::Fr>();
+        assert_eq!(
+            permute::<ark_pallas::Fr>([0, 1, 2]),
+            [
+                "24b6d44bc557642abe1925687dc53ad7965c26c8e8d254c4c99df90d871ed537",
+                "30c55e9908901dd4ce7f7778858cef8a09358f98b6f435b2e8bceccce25a3660",
+                "06ac4f54eff34aa156d70057a5fa0c08670af3e131faad8b68f08312c6df4c2f",
+            ]
+        );
+    }
+}
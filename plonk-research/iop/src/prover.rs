@@ -1,57 +1,352 @@
-use std::borrow::Cow;
-use ark_poly_commit::kzg10::Commitment;
-use rand::RngCore;
-use rand::CryptoRng;
-use constraint::arithmetization::Arithmetization;
-use crate::snark::Ck;
-use ark_ec::PairingEngine;
-use ark_ff::Field;
+use crate::quotient::{quot_domain_size, quot_lin_scalars, split_quot_poly};
+use crate::snark::{Challenge, OpeningMode, Openings, Oracle, Pk};
+use crate::utils::selector_terms;
+use ark_ff::{Field, One, Zero};
 use ark_poly::univariate::DensePolynomial;
-use ark_poly::{Radix2EvaluationDomain, EvaluationDomain};
-use ark_poly_commit::kzg10::{Powers, KZG10};
+use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain, UVPolynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use constraint::arithmetization::Arithmetization;
+use constraint::circuit::WIRE_COUNT;
+use pcs::shplonk::SHPLONK;
+use pcs::PolynomialCommitment;
+use rand::RngCore;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+//Degree of the random multiples of Z_H(X) added to wire polys and z(X) for hiding
+const WIRE_BLINDER_DEGREE: usize = 1;
+const PROD_PERM_BLINDER_DEGREE: usize = 2;
 
-pub struct Prover<E: PairingEngine> {
-    domain: Radix2EvaluationDomain<E::Fr>,
+//Coefficients of every quotient chunk, blinded t(X) has degree at most WIRE_COUNT * (n + 1) + 2
+pub(crate) fn quot_chunk_size(domain_size: usize) -> usize {
+    domain_size + PROD_PERM_BLINDER_DEGREE
 }
 
+pub struct Prover<PC: PolynomialCommitment> {
+    pub(crate) domain: Radix2EvaluationDomain<PC::Field>,
+
+    //larger domain, its coset is used to evaluate the quotient poly
+    pub(crate) quot_domain: Radix2EvaluationDomain<PC::Field>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ProofEvaluations<F: Field> {
+    //wire polys evaluate at zeta
     pub(crate) wire_evals: Vec<F>,
+    //first WIRE_COUNT - 1 identify permutation polys evaluate at zeta
     pub(crate) identify_perm_evals: Vec<F>,
+    //product permutation poly evaluate at zeta * omega
+    pub(crate) prod_perm_eval: F,
 }
 
-impl<E> Prover<E>
-where E: PairingEngine,
+impl<PC> Prover<PC>
+where
+    PC: PolynomialCommitment,
 {
     pub(crate) fn new(domain_size: usize) -> Self {
-        let domain = Radix2EvaluationDomain::<E::Fr>::new(domain_size).unwrap(); 
-        Self{domain}
+        let domain = Radix2EvaluationDomain::<PC::Field>::new(domain_size).unwrap();
+        let quot_domain = Radix2EvaluationDomain::<PC::Field>::new(quot_domain_size(domain_size)).unwrap();
+        Self {
+            domain,
+            quot_domain,
+        }
+    }
+
+    //The key is borrowed by every commitment, with the parallel feature they run on the rayon pool
+    pub(crate) fn commit_polynomials(
+        ck: &PC::CommitterKey,
+        polys: &[DensePolynomial<PC::Field>],
+    ) -> Vec<PC::Commitment> {
+        #[cfg(feature = "parallel")]
+        let polys = polys.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let polys = polys.iter();
+        polys.map(|poly| Self::commit_polynomial(ck, poly)).collect()
     }
 
-    fn commit_polynomials(ck: &Ck<E>, polys: &[DensePolynomial<E::Fr>]) -> Vec<Commitment<E>>{
+    pub(crate) fn commit_polynomial(ck: &PC::CommitterKey, poly: &DensePolynomial<PC::Field>) -> PC::Commitment {
+        PC::commit(ck, poly).unwrap()
+    }
 
-        let mut commits = vec![];
-        for poly in polys.iter(){
-            commits.push(Self::commit_polynomial(ck, poly));
+    //Random multiple b(X) of the vanishing poly with deg(b) = degree, zero without rng
+    fn sample_blinder<R: RngCore>(degree: usize, rng: Option<&mut R>) -> DensePolynomial<PC::Field> {
+        match rng {
+            Some(rng) => DensePolynomial::rand(degree, rng),
+            None => DensePolynomial::zero(),
         }
-        commits
     }
 
-    fn commit_polynomial(ck: &Ck<E>, poly: &DensePolynomial<E::Fr>) -> Commitment<E>{
-        let powers = Powers {
-            powers_of_g: Cow::Owned(ck.0.clone()),
-            powers_of_gamma_g: Cow::Owned(vec![]),
-        };
-        let (commit,_) = KZG10::commit(&powers, poly, None, None).unwrap();
-        commit
+    //Commit the poly with evals over the domain plus blinder(X) * Z_H(X) straight from the evals
+    //with the Lagrange basis key, and interpolate it
+    fn commit_blinded_evals(
+        &self,
+        lk: &PC::LagrangeKey,
+        evals: &[PC::Field],
+        blinder: &DensePolynomial<PC::Field>,
+    ) -> (PC::Commitment, DensePolynomial<PC::Field>) {
+        let commit = PC::commit_evals(lk, evals, blinder).unwrap();
+        let poly = DensePolynomial::from_coefficients_vec(self.domain.ifft(evals));
+        (commit, &poly + &blinder.mul_by_vanishing_poly(self.domain))
+    }
+
+    //Round 1: commit wire polys, blinded with (b1 * X + b2) * Z_H(X) if rng is given.
+    //Blinders are sampled in order, the wires are committed in parallel with the parallel feature
+    pub(crate) fn round1<A: Arithmetization<PC::Field>, R: RngCore>(
+        &self,
+        lk: &PC::LagrangeKey,
+        a: &A,
+        mut rng: Option<&mut R>,
+    ) -> (Vec<PC::Commitment>, Vec<DensePolynomial<PC::Field>>) {
+        let wire_evals = a.generate_wire_evals();
+        let blinders: Vec<_> = (0..wire_evals.len())
+            .map(|_| Self::sample_blinder(WIRE_BLINDER_DEGREE, rng.as_deref_mut()))
+            .collect();
+        #[cfg(feature = "parallel")]
+        let wire_evals = wire_evals.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let wire_evals = wire_evals.iter();
+        wire_evals
+            .zip(blinders)
+            .map(|(evals, blinder)| self.commit_blinded_evals(lk, evals, &blinder))
+            .unzip()
+    }
+
+    //Round 2: commit product permutation poly z(X) with challenge beta and gamma,
+    //blinded with (b3 * X^2 + b4 * X + b5) * Z_H(X) if rng is given
+    pub(crate) fn round2<A: Arithmetization<PC::Field>, R: RngCore>(
+        &self,
+        lk: &PC::LagrangeKey,
+        a: &A,
+        challenge: &Challenge<PC::Field>,
+        rng: Option<&mut R>,
+    ) -> (PC::Commitment, DensePolynomial<PC::Field>) {
+        let evals = a.generate_prod_perm_evals(challenge.beta, challenge.gamma);
+        let blinder = Self::sample_blinder(PROD_PERM_BLINDER_DEGREE, rng);
+        self.commit_blinded_evals(lk, &evals, &blinder)
+    }
+
+    //Round 3: commit quotient poly t(X), split into WIRE_COUNT chunks of degree < n + 2
+    pub(crate) fn round3(
+        &self,
+        ck: &PC::CommitterKey,
+        pk: &Pk<PC>,
+        challenge: &Challenge<PC::Field>,
+        oracle: &Oracle<PC::Field>,
+    ) -> (Vec<PC::Commitment>, Vec<DensePolynomial<PC::Field>>) {
+        let quot_poly = self.compute_quotient_poly(pk, challenge, oracle);
+        let quot_polys = split_quot_poly(&quot_poly, quot_chunk_size(self.domain.size()));
+        let quot_poly_commits = Self::commit_polynomials(ck, &quot_polys);
+        (quot_poly_commits, quot_polys)
+    }
+
+    //Round 4: evaluate wire polys, identify permutation polys at zeta,
+    //product permutation poly at zeta * omega
+    pub(crate) fn round4(
+        &self,
+        pk: &Pk<PC>,
+        challenge: &Challenge<PC::Field>,
+        oracle: &Oracle<PC::Field>,
+    ) -> ProofEvaluations<PC::Field> {
+        let zeta = challenge.zeta;
+        let wire_evals = oracle
+            .wire_polys
+            .iter()
+            .map(|poly| poly.evaluate(&zeta))
+            .collect();
+        let identify_perm_evals = pk.identify_perm_polys[..WIRE_COUNT - 1]
+            .iter()
+            .map(|poly| poly.evaluate(&zeta))
+            .collect();
+        let prod_perm_eval = oracle
+            .prod_perm_poly
+            .evaluate(&(zeta * self.domain.group_gen));
+        ProofEvaluations {
+            wire_evals,
+            identify_perm_evals,
+            prod_perm_eval,
+        }
+    }
+
+    //Round 5: open linearization poly, wire polys and identify permutation polys at zeta,
+    //product permutation poly at zeta * omega
+    pub(crate) fn round5(
+        &self,
+        pk: &Pk<PC>,
+        challenge: &Challenge<PC::Field>,
+        oracle: &Oracle<PC::Field>,
+        evals: &ProofEvaluations<PC::Field>,
+        quot_polys: &[DensePolynomial<PC::Field>],
+        mode: OpeningMode,
+    ) -> Openings<PC> {
+        let vanish_eval = self.domain.evaluate_vanishing_polynomial(challenge.zeta);
+        let chunk_size = quot_chunk_size(self.domain.size());
+        let (mut polys, mut scalars) = self.compute_lin_terms(pk, challenge, oracle, evals);
+        polys.extend(quot_polys.iter());
+        scalars.extend(quot_lin_scalars(quot_polys.len(), challenge.zeta, vanish_eval, chunk_size));
+
+        //batch polys opened at zeta with challenge v, in the order the verifier combines them
+        let mut v_pow = PC::Field::one();
+        for poly in oracle
+            .wire_polys
+            .iter()
+            .chain(pk.identify_perm_polys[..WIRE_COUNT - 1].iter())
+        {
+            v_pow *= challenge.v;
+            polys.push(poly);
+            scalars.push(v_pow);
+        }
+        Self::open_claims(
+            &pk.ck,
+            &[polys, vec![&oracle.prod_perm_poly]],
+            &[scalars, vec![PC::Field::one()]],
+            &[challenge.zeta, challenge.zeta * self.domain.group_gen],
+            mode,
+            challenge.u,
+        )
+    }
+
+    //Open claim i, sum scalars[i][j] * polys[i][j], at points[i]. Claim 0 is the batch at zeta,
+    //the others are product permutation polys at shifted points.
+    pub(crate) fn open_claims(
+        ck: &PC::CommitterKey,
+        polys: &[Vec<&DensePolynomial<PC::Field>>],
+        scalars: &[Vec<PC::Field>],
+        points: &[PC::Field],
+        mode: OpeningMode,
+        u: PC::Field,
+    ) -> Openings<PC> {
+        match mode {
+            OpeningMode::PerPoint => {
+                let mut proofs = (0..polys.len())
+                    .map(|i| PC::open_combination(ck, &polys[i], &scalars[i], points[i]).unwrap());
+                Openings::PerPoint {
+                    opening_proof: proofs.next().unwrap(),
+                    shifted_opening_proofs: proofs.collect(),
+                }
+            }
+            OpeningMode::MultiPoint => {
+                let point_sets: Vec<Vec<PC::Field>> = points.iter().map(|p| vec![*p]).collect();
+                Openings::MultiPoint(SHPLONK::open(ck, polys, scalars, &point_sets, u).unwrap())
+            }
+        }
+    }
+
+    //Terms of linearization poly r(X) without the quotient part, r(zeta) - Z_H(zeta) * t(zeta) = -r0 where
+    //r0 = PI(zeta) - alpha^2 * L1(zeta) - alpha * z(zeta * omega) * (a5 + gamma) * prod(a_i + beta * s_i + gamma).
+    //r(X) = sum scalars[i] * polys[i], in the order of the verifier's commitment terms
+    pub(crate) fn compute_lin_terms<'a>(
+        &self,
+        pk: &'a Pk<PC>,
+        challenge: &Challenge<PC::Field>,
+        oracle: &'a Oracle<PC::Field>,
+        evals: &ProofEvaluations<PC::Field>,
+    ) -> (Vec<&'a DensePolynomial<PC::Field>>, Vec<PC::Field>) {
+        let beta = challenge.beta;
+        let gamma = challenge.gamma;
+        let alpha = challenge.alpha;
+        let zeta = challenge.zeta;
+        let wire_evals = &evals.wire_evals;
+
+        //gate constraint
+        let mut polys: Vec<&DensePolynomial<PC::Field>> = pk.selector_polys.iter().collect();
+        let mut scalars = selector_terms(wire_evals, |a, b| *a * b, |a| -*a, PC::Field::one());
+
+        //copy constraint
+        let vanish_eval = self.domain.evaluate_vanishing_polynomial(zeta);
+        let l1_eval = vanish_eval / (self.domain.size_as_field_element * (zeta - PC::Field::one()));
+        let prod_orig = wire_evals
+            .iter()
+            .zip(pk.vk.k_reprs.iter())
+            .fold(PC::Field::one(), |acc, (&a, &k)| {
+                acc * (a + beta * k * zeta + gamma)
+            });
+        polys.push(&oracle.prod_perm_poly);
+        scalars.push(alpha * prod_orig + alpha * alpha * l1_eval);
+        let prod_perm = wire_evals
+            .iter()
+            .zip(evals.identify_perm_evals.iter())
+            .fold(PC::Field::one(), |acc, (&a, &s)| acc * (a + beta * s + gamma));
+        polys.push(&pk.identify_perm_polys[WIRE_COUNT - 1]);
+        scalars.push(-alpha * beta * evals.prod_perm_eval * prod_perm);
+        (polys, scalars)
     }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::prover::Prover;
+    use crate::snark::{Challenge, PlonkSnark, Snark};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Zero;
+    use ark_poly::Polynomial;
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use constraint::circuit::{Circuit, PlonkCircuit};
+    use pcs::fri::FRI;
+    use pcs::kzg10::KZG10;
+    use pcs::PolynomialCommitment;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
-    pub(crate) fn round1<A: Arithmetization<E::Fr>, R: CryptoRng + RngCore>(&self,rng: &R, ck: &Ck<E>, a: &A) -> (Vec<Commitment<E>>, Vec<DensePolynomial<E::Fr>>,DensePolynomial<E::Fr>){
+    pub fn build_circuit() -> PlonkCircuit<Fr> {
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(3u32), false);
+        let b = circuit.create_var(Fr::from(4u32), false);
+        let c = circuit.add(a, b);
+        circuit.mul(c, b);
+        circuit
+    }
 
-        let wire_polys = a.generate_wire_polys();
-        let wire_poly_commits = Self::commit_polynomials(&ck,&wire_polys);
-        todo!()
+    #[test]
+    fn test_prove() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circuit = build_circuit();
+        assert_eq!(circuit.gate_count(), 4);
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, _) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert_eq!(proof.wire_poly_commits.len(), constraint::circuit::WIRE_COUNT);
+        assert_eq!(proof.quot_poly_commits.len(), constraint::circuit::WIRE_COUNT);
+        assert_eq!(
+            proof.poly_evals.identify_perm_evals.len(),
+            constraint::circuit::WIRE_COUNT - 1
+        );
+    }
 
+    //Commitments from evaluations in the Lagrange basis equal the commitments of the blinded polys
+    fn check_lagrange_commits<PC: PolynomialCommitment<Field = Fr>>() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let circuit = build_circuit();
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<PC>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, _) = PlonkSnark::<PC>::preprocess(&srs, &arith);
+        let prover = Prover::<PC>::new(arith.domain_size());
 
+        let (wire_poly_commits, wire_polys) = prover.round1(&pk.lk, &arith, Some(&mut rng));
+        assert_eq!(wire_poly_commits, Prover::<PC>::commit_polynomials(&pk.ck, &wire_polys));
+        //blinding keeps the evaluations over the domain
+        for (poly, unblinded) in wire_polys.iter().zip(arith.generate_wire_polys().iter()) {
+            assert_eq!(poly.degree(), arith.domain_size() + 1);
+            let (_, rem) = (poly - unblinded).divide_by_vanishing_poly(prover.domain).unwrap();
+            assert!(rem.is_zero());
+        }
+        let challenge = Challenge {
+            beta: Fr::from(3u32),
+            gamma: Fr::from(5u32),
+            ..Default::default()
+        };
+        let (prod_perm_poly_commit, prod_perm_poly) = prover.round2(&pk.lk, &arith, &challenge, Some(&mut rng));
+        assert_eq!(prod_perm_poly_commit, Prover::<PC>::commit_polynomial(&pk.ck, &prod_perm_poly));
+        assert_eq!(prod_perm_poly.degree(), arith.domain_size() + 2);
+        let (prod_perm_poly_commit, prod_perm_poly) =
+            prover.round2::<_, StdRng>(&pk.lk, &arith, &challenge, None);
+        assert_eq!(prod_perm_poly, arith.generate_prod_perm_poly(challenge.beta, challenge.gamma));
+        assert_eq!(prod_perm_poly_commit, Prover::<PC>::commit_polynomial(&pk.ck, &prod_perm_poly));
     }
 
+    #[test]
+    fn test_lagrange_commits() {
+        check_lagrange_commits::<KZG10<Bls12_381>>();
+        check_lagrange_commits::<FRI<Fr>>();
+    }
 }
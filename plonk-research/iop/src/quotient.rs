@@ -0,0 +1,261 @@
+use crate::prover::{quot_chunk_size, Prover};
+use crate::snark::{Challenge, Oracle, Pk};
+use crate::utils::write_selector_terms;
+use ark_ff::{batch_inversion, FftField, Field, One, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain, UVPolynomial};
+use constraint::circuit::WIRE_COUNT;
+use constraint::gate::SELECTOR_COUNT;
+use pcs::PolynomialCommitment;
+
+//Size of the domain whose coset the quotient poly is evaluated on. The grand product
+//term has degree (WIRE_COUNT + 1) * n plus blinders, so the domain is 8x larger than
+//the trace domain, and more for tiny domains where the blinders dominate.
+pub(crate) fn quot_domain_size(domain_size: usize) -> usize {
+    (WIRE_COUNT + 1) * quot_chunk_size(domain_size)
+}
+
+//Evaluations of poly on the coset offset * H of domain H into evals. A coefficient of degree k >= |H|
+//lands on k mod |H| since omega^|H| = 1, so polys of any degree are evaluated with one FFT of size |H|.
+fn coset_evals<F: FftField>(
+    domain: &Radix2EvaluationDomain<F>,
+    poly: &DensePolynomial<F>,
+    offset: F,
+    evals: &mut Vec<F>,
+) {
+    let n = domain.size();
+    evals.clear();
+    evals.resize(n, F::zero());
+    let mut pow = F::one();
+    for (k, c) in poly.coeffs.iter().enumerate() {
+        evals[k % n] += *c * pow;
+        pow *= offset;
+    }
+    domain.fft_in_place(evals);
+}
+
+impl<PC> Prover<PC>
+where
+    PC: PolynomialCommitment,
+{
+    //Evaluate gate identity and permutation identity on the coset gH' of quot_domain,
+    //divide by Z_H pointwise and interpolate t(X).
+    //gH' is the union of the ratio cosets g * omega'^c * H of the trace domain H, they are evaluated
+    //one after another so only polys of size n are live besides t(X), not every poly over gH'.
+    pub(crate) fn compute_quotient_poly(
+        &self,
+        pk: &Pk<PC>,
+        challenge: &Challenge<PC::Field>,
+        oracle: &Oracle<PC::Field>,
+    ) -> DensePolynomial<PC::Field> {
+        let n = self.domain.size();
+        let m = self.quot_domain.size();
+        let ratio = m / n;
+        let beta = challenge.beta;
+        let gamma = challenge.gamma;
+        let alpha = challenge.alpha;
+        let k_reprs = &pk.vk.k_reprs;
+
+        let buffers = |count: usize| vec![Vec::with_capacity(n); count];
+        let mut wire_evals = buffers(WIRE_COUNT);
+        let mut selector_evals = buffers(SELECTOR_COUNT);
+        let mut identify_perm_evals = buffers(WIRE_COUNT);
+        let mut z_evals = Vec::with_capacity(n);
+        let mut pi_evals = Vec::with_capacity(n);
+        let mut lagrange_evals = vec![PC::Field::zero(); n];
+
+        let mut w = vec![PC::Field::zero(); WIRE_COUNT];
+        let mut terms = vec![PC::Field::one(); SELECTOR_COUNT];
+        let mut quot_evals = vec![PC::Field::zero(); m];
+        for c in 0..ratio {
+            //x_(c + ratio * i) = s * omega^i with s = g * omega'^c, omega = omega'^ratio
+            let s = PC::Field::multiplicative_generator() * self.quot_domain.group_gen.pow([c as u64]);
+            let evaluate = |polys: &[DensePolynomial<PC::Field>], evals: &mut [Vec<PC::Field>]| {
+                for (poly, evals) in polys.iter().zip(evals.iter_mut()) {
+                    coset_evals(&self.domain, poly, s, evals);
+                }
+            };
+            evaluate(&oracle.wire_polys, &mut wire_evals);
+            evaluate(&pk.selector_polys, &mut selector_evals);
+            evaluate(&pk.identify_perm_polys, &mut identify_perm_evals);
+            coset_evals(&self.domain, &oracle.prod_perm_poly, s, &mut z_evals);
+            coset_evals(&self.domain, &oracle.pi_poly, s, &mut pi_evals);
+
+            //Z_H(x) = s^n - 1 on the whole coset, it is not zero since s is outside H
+            let vanish_inv = (s.pow([n as u64]) - PC::Field::one()).inverse().unwrap();
+
+            //z(X) starts with 1, L1(x) / Z_H(x) = 1 / (n * (x - 1))
+            let mut x = s;
+            for l in lagrange_evals.iter_mut() {
+                *l = self.domain.size_as_field_element * (x - PC::Field::one());
+                x *= self.domain.group_gen;
+            }
+            batch_inversion(&mut lagrange_evals);
+
+            let mut x = s;
+            for i in 0..n {
+                for (w, evals) in w.iter_mut().zip(wire_evals.iter()) {
+                    *w = evals[i];
+                }
+
+                //gate constraint, as PlonkCircuit::check_gate plus PI
+                write_selector_terms(&w, &mut terms, |a, b| *a * b, |a| -*a);
+                let gate = selector_evals
+                    .iter()
+                    .zip(terms.iter())
+                    .fold(pi_evals[i], |acc, (q, &t)| acc + q[i] * t);
+
+                //copy constraint, z(omega * x) is the next evaluation of the coset
+                let mut prod_orig = z_evals[i];
+                let mut prod_perm = z_evals[(i + 1) % n];
+                for wire_id in 0..WIRE_COUNT {
+                    prod_orig *= w[wire_id] + beta * k_reprs[wire_id] * x + gamma;
+                    prod_perm *= w[wire_id] + beta * identify_perm_evals[wire_id][i] + gamma;
+                }
+                let first = (z_evals[i] - PC::Field::one()) * lagrange_evals[i];
+
+                let numerator = gate + alpha * (prod_orig - prod_perm);
+                quot_evals[c + ratio * i] = numerator * vanish_inv + alpha * alpha * first;
+                x *= self.domain.group_gen;
+            }
+        }
+
+        self.quot_domain.coset_ifft_in_place(&mut quot_evals);
+        DensePolynomial::from_coefficients_vec(quot_evals)
+    }
+}
+
+//Split t(X) into WIRE_COUNT chunks of chunk_size coefficients, higher coefficients
+//are zero unless the witness is wrong, then the proof fails anyway
+pub(crate) fn split_quot_poly<F: Field>(
+    quot_poly: &DensePolynomial<F>,
+    chunk_size: usize,
+) -> Vec<DensePolynomial<F>> {
+    let mut coeffs = quot_poly.coeffs.clone();
+    coeffs.resize(chunk_size * WIRE_COUNT, F::zero());
+    coeffs
+        .chunks(chunk_size)
+        .map(DensePolynomial::from_coefficients_slice)
+        .collect()
+}
+
+//Scalars of the quotient part of the linearization poly, -Z_H(zeta) * zeta^(i * chunk_size) * t_i(X)
+pub(crate) fn quot_lin_scalars<F: Field>(
+    chunk_count: usize,
+    zeta: F,
+    vanish_eval: F,
+    chunk_size: usize,
+) -> Vec<F> {
+    let zeta_n = zeta.pow([chunk_size as u64]);
+    let mut scalars = Vec::with_capacity(chunk_count);
+    let mut coeff = -vanish_eval;
+    for _ in 0..chunk_count {
+        scalars.push(coeff);
+        coeff *= zeta_n;
+    }
+    scalars
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::prover::test::build_circuit;
+    use crate::prover::{quot_chunk_size, Prover};
+    use crate::quotient::{coset_evals, quot_domain_size};
+    use crate::snark::{Challenge, Oracle, PlonkSnark, Snark};
+    use crate::utils::selector_terms;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::{Field, One, UniformRand};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain, UVPolynomial};
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use pcs::kzg10::KZG10;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_quot_domain() {
+        for domain_size in [16, 64, 1024] {
+            let prover: Prover<KZG10<Bls12_381>> = Prover::new(domain_size);
+            assert_eq!(prover.quot_domain.size(), 8 * domain_size);
+        }
+        //blinders dominate tiny domains
+        let prover: Prover<KZG10<Bls12_381>> = Prover::new(4);
+        assert_eq!(prover.quot_domain.size(), 64);
+        assert!(quot_domain_size(4) <= 64);
+    }
+
+    //polys longer than the domain, like the blinded wire polys, fold onto it
+    #[test]
+    fn test_coset_evals() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let domain = Radix2EvaluationDomain::<Fr>::new(8).unwrap();
+        let offset = Fr::rand(&mut rng);
+        let mut evals = vec![];
+        for degree in [3, 7, 10, 20] {
+            let poly = DensePolynomial::<Fr>::rand(degree, &mut rng);
+            coset_evals(&domain, &poly, offset, &mut evals);
+            let expected: Vec<Fr> = domain.elements().map(|x| poly.evaluate(&(offset * x))).collect();
+            assert_eq!(evals, expected);
+        }
+    }
+
+    #[test]
+    fn test_quotient_polys() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circuit = build_circuit();
+        let arith = PlonkArithmetization::new(&circuit);
+        let domain_size = arith.domain_size();
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(domain_size + 2, &mut rng);
+        let (pk, _) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let prover: Prover<KZG10<Bls12_381>> = Prover::new(domain_size);
+
+        let challenge = Challenge {
+            alpha: Fr::rand(&mut rng),
+            beta: Fr::rand(&mut rng),
+            gamma: Fr::rand(&mut rng),
+            ..Default::default()
+        };
+        let (_, wire_polys) = prover.round1(&pk.lk, &arith, Some(&mut rng));
+        let pi_poly = arith.generate_pi_poly();
+        let (_, prod_perm_poly) = prover.round2(&pk.lk, &arith, &challenge, Some(&mut rng));
+        let omega = prover.domain.group_gen;
+        assert_eq!(prod_perm_poly.evaluate(&Fr::one()), Fr::one());
+        let oracle = Oracle {
+            wire_polys,
+            pi_poly,
+            prod_perm_poly,
+        };
+        let (_, quot_polys) = prover.round3(&pk.ck, &pk, &challenge, &oracle);
+        assert_eq!(quot_polys.len(), constraint::circuit::WIRE_COUNT);
+
+        //t(X) * Z_H(X) must equal the constraint numerator at a random point
+        let x = Fr::rand(&mut rng);
+        let x_n = x.pow([quot_chunk_size(domain_size) as u64]);
+        let quot_eval = quot_polys
+            .iter()
+            .rev()
+            .fold(Fr::from(0u32), |acc, poly| acc * x_n + poly.evaluate(&x));
+        let w: Vec<Fr> = oracle.wire_polys.iter().map(|p| p.evaluate(&x)).collect();
+        let z = oracle.prod_perm_poly.evaluate(&x);
+        let z_shift = oracle.prod_perm_poly.evaluate(&(x * omega));
+        let mut prod_orig = z;
+        let mut prod_perm = z_shift;
+        for (i, &k) in pk.vk.k_reprs.iter().enumerate() {
+            prod_orig *= w[i] + challenge.beta * k * x + challenge.gamma;
+            prod_perm *=
+                w[i] + challenge.beta * pk.identify_perm_polys[i].evaluate(&x) + challenge.gamma;
+        }
+        let l1 = prover.domain.evaluate_all_lagrange_coefficients(x)[0];
+        let gate = selector_terms(&w, |a, b| *a * b, |a| -*a, Fr::one())
+            .iter()
+            .zip(pk.selector_polys.iter())
+            .fold(oracle.pi_poly.evaluate(&x), |acc, (t, q)| acc + q.evaluate(&x) * t);
+        let numerator = gate
+            + challenge.alpha * (prod_orig - prod_perm)
+            + challenge.alpha * challenge.alpha * (z - Fr::one()) * l1;
+        assert_eq!(
+            quot_eval * prover.domain.evaluate_vanishing_polynomial(x),
+            numerator
+        );
+    }
+}
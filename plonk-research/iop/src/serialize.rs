@@ -0,0 +1,710 @@
+use crate::prover::ProofEvaluations;
+use crate::snark::{BatchProof, Openings, Pk, Proof, Srs, Vk};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use constraint::circuit::WIRE_COUNT;
+use constraint::gate::SELECTOR_COUNT;
+use pcs::shplonk;
+use pcs::{Error, PolynomialCommitment};
+use serde_json::{json, Value};
+
+//Every encoded object starts with MAGIC, the format VERSION, its kind and its compression
+const MAGIC: [u8; 4] = *b"PLNK";
+
+pub const VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    //points as x coordinates and flags, decoding recovers y and checks the subgroup
+    #[default]
+    Compressed,
+
+    //points as both coordinates, twice the size but faster to decode
+    Uncompressed,
+}
+
+//Proofs, keys and srs as a versioned binary file, a proof is never read back as a key
+pub trait Versioned: CanonicalSerialize + CanonicalDeserialize {
+    const KIND: u8;
+
+    fn write_versioned<W: Write>(&self, mut writer: W, compression: Compression) -> Result<(), Error> {
+        let compressed = compression == Compression::Compressed;
+        writer
+            .write_all(&MAGIC)
+            .and_then(|_| writer.write_all(&[VERSION, Self::KIND, compressed as u8]))
+            .map_err(|_| Error::MalformedEncoding("write failed"))?;
+        if compressed {
+            self.serialize(&mut writer)?;
+        } else {
+            self.serialize_uncompressed(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn read_versioned<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut header = [0u8; 7];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| Error::MalformedEncoding("truncated header"))?;
+        if header[..4] != MAGIC {
+            return Err(Error::MalformedEncoding("wrong magic"));
+        }
+        if header[4] != VERSION {
+            return Err(Error::UnsupportedVersion(header[4]));
+        }
+        if header[5] != Self::KIND {
+            return Err(Error::MalformedEncoding("wrong kind"));
+        }
+        match header[6] {
+            1 => Ok(Self::deserialize(&mut reader)?),
+            0 => Ok(Self::deserialize_uncompressed(&mut reader)?),
+            _ => Err(Error::MalformedEncoding("unknown compression")),
+        }
+    }
+
+    fn to_bytes(&self, compression: Compression) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_versioned(&mut bytes, compression).unwrap();
+        bytes
+    }
+
+    fn from_bytes(mut bytes: &[u8]) -> Result<Self, Error> {
+        let value = Self::read_versioned(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(Error::MalformedEncoding("trailing bytes"));
+        }
+        Ok(value)
+    }
+}
+
+impl<PC: PolynomialCommitment> Versioned for Proof<PC> {
+    const KIND: u8 = 1;
+}
+
+impl<PC: PolynomialCommitment> Versioned for BatchProof<PC> {
+    const KIND: u8 = 2;
+}
+
+impl<PC: PolynomialCommitment> Versioned for Vk<PC> {
+    const KIND: u8 = 3;
+}
+
+impl<PC: PolynomialCommitment> Versioned for Pk<PC> {
+    const KIND: u8 = 4;
+}
+
+impl<PC: PolynomialCommitment> Versioned for Srs<PC> {
+    const KIND: u8 = 5;
+}
+
+//Compressed points are checked by arkworks when they are decompressed, uncompressed points
+//are read unchecked since arkworks does not check them on the curve. Either way the decoded
+//object goes through the checks of the commitment scheme before it is returned.
+trait CheckedDeserialize: Sized {
+    fn read_fields<R: Read>(reader: R, compressed: bool) -> Result<Self, SerializationError>;
+
+    fn is_valid(&self) -> bool;
+}
+
+fn read<T: CanonicalDeserialize, R: Read>(reader: R, compressed: bool) -> Result<T, SerializationError> {
+    if compressed {
+        T::deserialize(reader)
+    } else {
+        T::deserialize_unchecked(reader)
+    }
+}
+
+fn read_checked<T: CheckedDeserialize, R: Read>(reader: R, compressed: bool) -> Result<T, SerializationError> {
+    let value = T::read_fields(reader, compressed)?;
+    if value.is_valid() {
+        Ok(value)
+    } else {
+        Err(SerializationError::InvalidData)
+    }
+}
+
+macro_rules! impl_checked_deserialize {
+    ($name:ident) => {
+        impl<PC: PolynomialCommitment> CanonicalDeserialize for $name<PC> {
+            fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                read_checked(reader, true)
+            }
+
+            fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                read_checked(reader, false)
+            }
+
+            //trusted input only
+            fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+                Self::read_fields(reader, false)
+            }
+        }
+    };
+}
+
+impl_checked_deserialize!(Openings);
+impl_checked_deserialize!(Proof);
+impl_checked_deserialize!(BatchProof);
+impl_checked_deserialize!(Vk);
+impl_checked_deserialize!(Pk);
+impl_checked_deserialize!(Srs);
+
+//Tagged with the opening mode
+impl<PC: PolynomialCommitment> CanonicalSerialize for Openings<PC> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => {
+                0u8.serialize(&mut writer)?;
+                opening_proof.serialize(&mut writer)?;
+                shifted_opening_proofs.serialize(&mut writer)
+            }
+            Openings::MultiPoint(proof) => {
+                1u8.serialize(&mut writer)?;
+                proof.serialize(&mut writer)
+            }
+        }
+    }
+
+    fn serialized_size(&self) -> usize {
+        1 + match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => opening_proof.serialized_size() + shifted_opening_proofs.serialized_size(),
+            Openings::MultiPoint(proof) => proof.serialized_size(),
+        }
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => {
+                0u8.serialize(&mut writer)?;
+                opening_proof.serialize_uncompressed(&mut writer)?;
+                shifted_opening_proofs.serialize_uncompressed(&mut writer)
+            }
+            Openings::MultiPoint(proof) => {
+                1u8.serialize(&mut writer)?;
+                proof.serialize_uncompressed(&mut writer)
+            }
+        }
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        1 + match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => opening_proof.uncompressed_size() + shifted_opening_proofs.uncompressed_size(),
+            Openings::MultiPoint(proof) => proof.uncompressed_size(),
+        }
+    }
+}
+
+impl<PC: PolynomialCommitment> CheckedDeserialize for Openings<PC> {
+    fn read_fields<R: Read>(mut reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        match u8::deserialize(&mut reader)? {
+            0 => Ok(Openings::PerPoint {
+                opening_proof: read(&mut reader, compressed)?,
+                shifted_opening_proofs: read(&mut reader, compressed)?,
+            }),
+            1 => Ok(Openings::MultiPoint(read(&mut reader, compressed)?)),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => shifted_opening_proofs
+                .iter()
+                .chain([opening_proof])
+                .all(PC::check_proof),
+            Openings::MultiPoint(proof) => {
+                PC::check_commitment(&proof.quot_commit) && PC::check_proof(&proof.opening_proof)
+            }
+        }
+    }
+}
+
+//Shape of the proof is checked against the vk by the verifier
+impl<PC: PolynomialCommitment> CheckedDeserialize for Proof<PC> {
+    fn read_fields<R: Read>(mut reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        Ok(Proof {
+            wire_poly_commits: read(&mut reader, compressed)?,
+            prod_perm_poly_commit: read(&mut reader, compressed)?,
+            quot_poly_commits: read(&mut reader, compressed)?,
+            openings: Openings::read_fields(&mut reader, compressed)?,
+            poly_evals: read(&mut reader, compressed)?,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.wire_poly_commits
+            .iter()
+            .chain(self.quot_poly_commits.iter())
+            .chain([&self.prod_perm_poly_commit])
+            .all(PC::check_commitment)
+            && self.openings.is_valid()
+    }
+}
+
+impl<PC: PolynomialCommitment> CheckedDeserialize for BatchProof<PC> {
+    fn read_fields<R: Read>(mut reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        Ok(BatchProof {
+            wire_poly_commits_vec: read(&mut reader, compressed)?,
+            prod_perm_poly_commit_vec: read(&mut reader, compressed)?,
+            quot_poly_commits: read(&mut reader, compressed)?,
+            openings: Openings::read_fields(&mut reader, compressed)?,
+            poly_evals_vec: read(&mut reader, compressed)?,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.wire_poly_commits_vec
+            .iter()
+            .flatten()
+            .chain(self.prod_perm_poly_commit_vec.iter())
+            .chain(self.quot_poly_commits.iter())
+            .all(PC::check_commitment)
+            && self.openings.is_valid()
+    }
+}
+
+//The verifier trusts the vk, so its shape is checked here
+impl<PC: PolynomialCommitment> CheckedDeserialize for Vk<PC> {
+    fn read_fields<R: Read>(mut reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        Ok(Vk {
+            pi_count: read(&mut reader, compressed)?,
+            domain_size: read(&mut reader, compressed)?,
+            pi_gates: read(&mut reader, compressed)?,
+            identify_perm_commits: read(&mut reader, compressed)?,
+            selector_commits: read(&mut reader, compressed)?,
+            k_reprs: read(&mut reader, compressed)?,
+            vk: read(&mut reader, compressed)?,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        let domain = Radix2EvaluationDomain::<PC::Field>::new(self.domain_size);
+        domain.map(|domain| domain.size()) == Some(self.domain_size)
+            && self.pi_gates.len() == self.pi_count
+            && self.pi_gates.iter().all(|gate| *gate < self.domain_size)
+            && self.identify_perm_commits.len() == WIRE_COUNT
+            && self.selector_commits.len() == SELECTOR_COUNT
+            && self.k_reprs.len() == WIRE_COUNT
+            && self
+                .identify_perm_commits
+                .iter()
+                .chain(self.selector_commits.iter())
+                .all(PC::check_commitment)
+            && PC::check_verifier_key(&self.vk)
+    }
+}
+
+impl<PC: PolynomialCommitment> CheckedDeserialize for Pk<PC> {
+    fn read_fields<R: Read>(mut reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        Ok(Pk {
+            identify_perm_polys: read(&mut reader, compressed)?,
+            selector_polys: read(&mut reader, compressed)?,
+            ck: read(&mut reader, compressed)?,
+            lk: read(&mut reader, compressed)?,
+            vk: Vk::read_fields(&mut reader, compressed)?,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.vk.is_valid()
+            && self.identify_perm_polys.len() == WIRE_COUNT
+            && self.selector_polys.len() == SELECTOR_COUNT
+            && self
+                .identify_perm_polys
+                .iter()
+                .chain(self.selector_polys.iter())
+                .all(|poly| poly.coeffs.len() <= self.vk.domain_size)
+            && PC::check_committer_key(&self.ck)
+            && PC::check_lagrange_key(&self.lk)
+    }
+}
+
+impl<PC: PolynomialCommitment> CheckedDeserialize for Srs<PC> {
+    fn read_fields<R: Read>(reader: R, compressed: bool) -> Result<Self, SerializationError> {
+        Ok(Srs(read(reader, compressed)?))
+    }
+
+    fn is_valid(&self) -> bool {
+        PC::check_params(&self.0)
+    }
+}
+
+//Human readable form for the vk and proofs, every element is the hex of its compressed encoding
+impl<PC: PolynomialCommitment> Vk<PC> {
+    pub fn to_json(&self) -> String {
+        json!({
+            "format": "plonk-vk",
+            "version": VERSION,
+            "domain_size": self.domain_size,
+            "pi_count": self.pi_count,
+            "pi_gates": self.pi_gates,
+            "identify_perm_commits": to_hex_vec(&self.identify_perm_commits),
+            "selector_commits": to_hex_vec(&self.selector_commits),
+            "k_reprs": to_hex_vec(&self.k_reprs),
+            "vk": to_hex(&self.vk),
+        })
+        .to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let json = parse_json(json, "plonk-vk")?;
+        let vk = Vk {
+            pi_count: usize_field(&json, "pi_count")?,
+            domain_size: usize_field(&json, "domain_size")?,
+            pi_gates: field(&json, "pi_gates")?
+                .as_array()
+                .ok_or(Error::MalformedEncoding("pi_gates"))?
+                .iter()
+                .map(|gate| usize_value(gate, "pi_gates"))
+                .collect::<Result<_, _>>()?,
+            identify_perm_commits: from_hex_vec(&json, "identify_perm_commits")?,
+            selector_commits: from_hex_vec(&json, "selector_commits")?,
+            k_reprs: from_hex_vec(&json, "k_reprs")?,
+            vk: from_hex(field(&json, "vk")?, "vk")?,
+        };
+        if !vk.is_valid() {
+            return Err(Error::InvalidEncodedData);
+        }
+        Ok(vk)
+    }
+}
+
+impl<PC: PolynomialCommitment> Proof<PC> {
+    pub fn to_json(&self) -> String {
+        let openings = match &self.openings {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => json!({
+                "mode": "per_point",
+                "opening_proof": to_hex(opening_proof),
+                "shifted_opening_proofs": to_hex_vec(shifted_opening_proofs),
+            }),
+            Openings::MultiPoint(proof) => json!({
+                "mode": "multi_point",
+                "quot_commit": to_hex(&proof.quot_commit),
+                "opening_proof": to_hex(&proof.opening_proof),
+            }),
+        };
+        json!({
+            "format": "plonk-proof",
+            "version": VERSION,
+            "wire_poly_commits": to_hex_vec(&self.wire_poly_commits),
+            "prod_perm_poly_commit": to_hex(&self.prod_perm_poly_commit),
+            "quot_poly_commits": to_hex_vec(&self.quot_poly_commits),
+            "openings": openings,
+            "poly_evals": {
+                "wire_evals": to_hex_vec(&self.poly_evals.wire_evals),
+                "identify_perm_evals": to_hex_vec(&self.poly_evals.identify_perm_evals),
+                "prod_perm_eval": to_hex(&self.poly_evals.prod_perm_eval),
+            },
+        })
+        .to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let json = parse_json(json, "plonk-proof")?;
+        let openings = field(&json, "openings")?;
+        let openings = match field(openings, "mode")?.as_str() {
+            Some("per_point") => Openings::PerPoint {
+                opening_proof: from_hex(field(openings, "opening_proof")?, "opening_proof")?,
+                shifted_opening_proofs: from_hex_vec(openings, "shifted_opening_proofs")?,
+            },
+            Some("multi_point") => Openings::MultiPoint(shplonk::Proof {
+                quot_commit: from_hex(field(openings, "quot_commit")?, "quot_commit")?,
+                opening_proof: from_hex(field(openings, "opening_proof")?, "opening_proof")?,
+            }),
+            _ => return Err(Error::MalformedEncoding("mode")),
+        };
+        let poly_evals = field(&json, "poly_evals")?;
+        let proof = Proof {
+            wire_poly_commits: from_hex_vec(&json, "wire_poly_commits")?,
+            prod_perm_poly_commit: from_hex(field(&json, "prod_perm_poly_commit")?, "prod_perm_poly_commit")?,
+            quot_poly_commits: from_hex_vec(&json, "quot_poly_commits")?,
+            openings,
+            poly_evals: ProofEvaluations {
+                wire_evals: from_hex_vec(poly_evals, "wire_evals")?,
+                identify_perm_evals: from_hex_vec(poly_evals, "identify_perm_evals")?,
+                prod_perm_eval: from_hex(field(poly_evals, "prod_perm_eval")?, "prod_perm_eval")?,
+            },
+        };
+        if !proof.is_valid() {
+            return Err(Error::InvalidEncodedData);
+        }
+        Ok(proof)
+    }
+}
+
+fn parse_json(json: &str, format: &str) -> Result<Value, Error> {
+    let json: Value = serde_json::from_str(json).map_err(|_| Error::MalformedEncoding("invalid json"))?;
+    if field(&json, "format")?.as_str() != Some(format) {
+        return Err(Error::MalformedEncoding("wrong format"));
+    }
+    let version = usize_field(&json, "version")?;
+    if version != VERSION as usize {
+        return Err(Error::UnsupportedVersion(version.min(u8::MAX as usize) as u8));
+    }
+    Ok(json)
+}
+
+//Missing or mistyped fields are reported by name
+fn field<'a>(json: &'a Value, name: &'static str) -> Result<&'a Value, Error> {
+    json.get(name).ok_or(Error::MalformedEncoding(name))
+}
+
+fn usize_value(value: &Value, name: &'static str) -> Result<usize, Error> {
+    value
+        .as_u64()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or(Error::MalformedEncoding(name))
+}
+
+fn usize_field(json: &Value, name: &'static str) -> Result<usize, Error> {
+    usize_value(field(json, name)?, name)
+}
+
+fn to_hex<T: CanonicalSerialize>(value: &T) -> Value {
+    let mut bytes = vec![];
+    value.serialize(&mut bytes).unwrap();
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Value::String(format!("0x{}", hex))
+}
+
+fn to_hex_vec<T: CanonicalSerialize>(values: &[T]) -> Value {
+    Value::Array(values.iter().map(to_hex).collect())
+}
+
+//Compressed decoding checks points, the caller checks the decoded object
+fn from_hex<T: CanonicalDeserialize>(value: &Value, name: &'static str) -> Result<T, Error> {
+    let hex = value
+        .as_str()
+        .and_then(|s| s.strip_prefix("0x"))
+        .filter(|hex| hex.len() % 2 == 0 && hex.is_ascii())
+        .ok_or(Error::MalformedEncoding(name))?;
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Error::MalformedEncoding(name))?;
+    let mut reader = &bytes[..];
+    let value = T::deserialize(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(Error::MalformedEncoding(name));
+    }
+    Ok(value)
+}
+
+fn from_hex_vec<T: CanonicalDeserialize>(json: &Value, name: &'static str) -> Result<Vec<T>, Error> {
+    field(json, name)?
+        .as_array()
+        .ok_or(Error::MalformedEncoding(name))?
+        .iter()
+        .map(|value| from_hex(value, name))
+        .collect()
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::serialize::{Compression, Versioned};
+    use crate::snark::test::build_chain_circuit;
+    use crate::snark::{BatchProof, Hiding, OpeningMode, Pk, PlonkSnark, Proof, Snark, Srs, Vk};
+    use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine};
+    use ark_ff::{One, Zero};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use constraint::circuit::Circuit;
+    use pcs::fri::FRI;
+    use pcs::ipa::IPA;
+    use pcs::kzg10::{Commitment, KZG10};
+    use pcs::{Error, PolynomialCommitment};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn round_trip<T: Versioned>(value: &T, compression: Compression) -> T {
+        let bytes = value.to_bytes(compression);
+        let decoded = T::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(compression), bytes);
+        decoded
+    }
+
+    //Keys and proofs decoded in both compressions still prove and verify
+    fn check_round_trip<PC: PolynomialCommitment>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let circuits = [build_chain_circuit::<PC::Field>(6, 5), build_chain_circuit(20, 11)];
+        let ariths: Vec<_> = circuits.iter().map(PlonkArithmetization::new).collect();
+        let pis: Vec<Vec<PC::Field>> = circuits.iter().map(|c| c.pi_value()).collect();
+        let srs = PlonkSnark::<PC>::setup(34, &mut rng);
+        for compression in [Compression::Compressed, Compression::Uncompressed] {
+            let srs = round_trip(&srs, compression);
+            let (pk, vk) = PlonkSnark::<PC>::preprocess(&srs, &ariths[0]);
+            let pk = round_trip(&pk, compression);
+            let vk = round_trip(&vk, compression);
+            assert!(vk.vk == pk.vk.vk);
+            for mode in [OpeningMode::PerPoint, OpeningMode::MultiPoint] {
+                let proof = PlonkSnark::<PC>::prove_with_mode(&ariths[0], &mut rng, &pk, mode, Hiding::Blinded);
+                let decoded = round_trip(&proof, compression);
+                assert_eq!(decoded.openings.mode(), mode);
+                assert!(PlonkSnark::<PC>::verify(&vk, &decoded, &pis[0]));
+
+                let decoded = Proof::<PC>::from_json(&proof.to_json()).unwrap();
+                assert_eq!(decoded.to_bytes(compression), proof.to_bytes(compression));
+            }
+            let decoded = Vk::<PC>::from_json(&vk.to_json()).unwrap();
+            assert_eq!(decoded.to_bytes(compression), vk.to_bytes(compression));
+
+            let (pk2, vk2) = PlonkSnark::<PC>::preprocess(&srs, &ariths[1]);
+            let proof = PlonkSnark::<PC>::batch_prove(&[&ariths[0], &ariths[1]], &mut rng, &[&pk, &pk2]).unwrap();
+            let proof = round_trip(&proof, compression);
+            assert!(PlonkSnark::<PC>::batch_verify(&[&vk, &vk2], &proof, &[&pis[0], &pis[1]]));
+        }
+
+        //the header is checked before the body
+        let (pk, vk) = PlonkSnark::<PC>::preprocess(&srs, &ariths[0]);
+        let bytes = vk.to_bytes(Compression::Compressed);
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(Vk::<PC>::from_bytes(&bad).err().unwrap(), Error::MalformedEncoding("wrong magic"));
+        bad = bytes.clone();
+        bad[4] = 2;
+        assert_eq!(Vk::<PC>::from_bytes(&bad).err().unwrap(), Error::UnsupportedVersion(2));
+        assert_eq!(Proof::<PC>::from_bytes(&bytes).err().unwrap(), Error::MalformedEncoding("wrong kind"));
+        assert_eq!(Pk::<PC>::from_bytes(&bytes).err().unwrap(), Error::MalformedEncoding("wrong kind"));
+        bad = bytes.clone();
+        bad[6] = 2;
+        assert_eq!(Vk::<PC>::from_bytes(&bad).err().unwrap(), Error::MalformedEncoding("unknown compression"));
+        assert_eq!(
+            Vk::<PC>::from_bytes(&bytes[..bytes.len() - 1]).err().unwrap(),
+            Error::MalformedEncoding("truncated data")
+        );
+        assert_eq!(
+            Vk::<PC>::from_bytes(&[&bytes[..], &[0]].concat()).err().unwrap(),
+            Error::MalformedEncoding("trailing bytes")
+        );
+        assert!(Srs::<PC>::from_bytes(&pk.to_bytes(Compression::Uncompressed)).is_err());
+        assert!(BatchProof::<PC>::from_bytes(&[]).is_err());
+
+        //a vk the verifier could not use is rejected
+        let mut bad_vk = vk.clone();
+        bad_vk.domain_size = 6;
+        assert_eq!(
+            Vk::<PC>::from_bytes(&bad_vk.to_bytes(Compression::Compressed)).err().unwrap(),
+            Error::InvalidEncodedData
+        );
+        let mut bad_vk = vk.clone();
+        bad_vk.pi_gates[0] = vk.domain_size;
+        assert!(Vk::<PC>::from_json(&bad_vk.to_json()).is_err());
+        let mut bad_vk = vk.clone();
+        bad_vk.selector_commits.pop();
+        assert!(Vk::<PC>::from_bytes(&bad_vk.to_bytes(Compression::Uncompressed)).is_err());
+
+        let json = vk.to_json();
+        assert_eq!(
+            Vk::<PC>::from_json(&json.replace("\"version\":1", "\"version\":3")).err().unwrap(),
+            Error::UnsupportedVersion(3)
+        );
+        assert_eq!(
+            Vk::<PC>::from_json(&json.replace("\"k_reprs\"", "\"k\"")).err().unwrap(),
+            Error::MalformedEncoding("k_reprs")
+        );
+        assert_eq!(Vk::<PC>::from_json("{").err().unwrap(), Error::MalformedEncoding("invalid json"));
+        let proof = PlonkSnark::<PC>::prove(&ariths[0], &mut rng, &pk);
+        assert_eq!(
+            Proof::<PC>::from_json(&json).err().unwrap(),
+            Error::MalformedEncoding("wrong format")
+        );
+        assert_eq!(
+            Proof::<PC>::from_json(&proof.to_json().replace("0x", "0xzz")).err().unwrap(),
+            Error::MalformedEncoding("opening_proof")
+        );
+    }
+
+    #[test]
+    fn test_kzg_round_trip() {
+        check_round_trip::<KZG10<Bls12_381>>(0);
+    }
+
+    #[test]
+    fn test_ipa_round_trip() {
+        check_round_trip::<IPA<ark_pallas::Affine>>(1);
+    }
+
+    #[test]
+    fn test_fri_round_trip() {
+        check_round_trip::<FRI<Fr>>(2);
+    }
+
+    //Points off the curve or outside the prime order subgroup are rejected in every encoding
+    #[test]
+    fn test_invalid_points() {
+        type Plonk = PlonkSnark<KZG10<Bls12_381>>;
+        let mut rng = StdRng::seed_from_u64(3);
+        let circuit = build_chain_circuit::<Fr>(6, 5);
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = Plonk::setup(arith.domain_size() + 2, &mut rng);
+        let (_, vk) = Plonk::preprocess(&srs, &arith);
+        let proof = Plonk::prove(&arith, &mut rng, &Plonk::preprocess(&srs, &arith).0);
+
+        let off_curve = G1Affine::new(Fq::one(), Fq::one(), false);
+        let mut x = Fq::zero();
+        let outside_subgroup = loop {
+            x += Fq::one();
+            if let Some(point) = G1Affine::get_point_from_x(x, false) {
+                break point;
+            }
+        };
+        assert!(!outside_subgroup.is_in_correct_subgroup_assuming_on_curve());
+
+        let mut bad_proof = proof.clone();
+        bad_proof.wire_poly_commits[1] = Commitment(off_curve);
+        //arkworks reads uncompressed points without the curve check
+        let mut bytes = vec![];
+        bad_proof.serialize_uncompressed(&mut bytes).unwrap();
+        assert!(Proof::<KZG10<Bls12_381>>::deserialize_unchecked(&bytes[..]).is_ok());
+        assert!(Proof::<KZG10<Bls12_381>>::deserialize_uncompressed(&bytes[..]).is_err());
+        assert_eq!(
+            Proof::<KZG10<Bls12_381>>::from_bytes(&bad_proof.to_bytes(Compression::Uncompressed)).err().unwrap(),
+            Error::InvalidEncodedData
+        );
+
+        bad_proof.wire_poly_commits[1] = Commitment(outside_subgroup);
+        for compression in [Compression::Compressed, Compression::Uncompressed] {
+            assert_eq!(
+                Proof::<KZG10<Bls12_381>>::from_bytes(&bad_proof.to_bytes(compression)).err().unwrap(),
+                Error::InvalidEncodedData
+            );
+        }
+        assert_eq!(
+            Proof::<KZG10<Bls12_381>>::from_json(&bad_proof.to_json()).err().unwrap(),
+            Error::InvalidEncodedData
+        );
+
+        let mut bad_vk = vk.clone();
+        bad_vk.vk.beta_h = vk.vk.h;
+        bad_vk.selector_commits[0] = Commitment(off_curve);
+        assert_eq!(
+            Vk::<KZG10<Bls12_381>>::from_bytes(&bad_vk.to_bytes(Compression::Uncompressed)).err().unwrap(),
+            Error::InvalidEncodedData
+        );
+        let mut bad_srs = Srs::<KZG10<Bls12_381>>(srs.0.clone());
+        bad_srs.0.powers_of_g[3] = outside_subgroup;
+        assert_eq!(
+            Srs::<KZG10<Bls12_381>>::from_bytes(&bad_srs.to_bytes(Compression::Uncompressed)).err().unwrap(),
+            Error::InvalidEncodedData
+        );
+    }
+}
@@ -1,19 +1,25 @@
-use ark_ec::short_weierstrass_jacobian::GroupAffine;
-use ark_ec::SWModelParameters;
-use crate::prover::Prover;
+use crate::prover::{quot_chunk_size, ProofEvaluations, Prover};
+use crate::quotient::{quot_lin_scalars, split_quot_poly};
 use crate::transcript::Transcript;
 use crate::transcript::PlonkTranscript;
-use ark_ec::PairingEngine;
-use ark_ff::Field;
+use crate::verifier::{batch_domain_sizes, Verifier};
+use ark_ec::short_weierstrass_jacobian::GroupAffine;
+use ark_ec::{PairingEngine, SWModelParameters};
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::univariate::DensePolynomial;
-use ark_poly_commit::kzg10::{Commitment, UniversalParams, VerifierKey};
-use ark_poly_commit::kzg10::{Powers, KZG10};
+use ark_poly::EvaluationDomain;
+use ark_serialize::{CanonicalSerialize, SerializationError, Write};
+use constraint::circuit::WIRE_COUNT;
 use constraint::arithmetization::Arithmetization;
+use pcs::kzg10::KZG10;
+use pcs::ptau::{self, PpotFile};
+use pcs::shplonk;
+use pcs::{powers, CurveAffine, PolynomialCommitment};
 use rand::{CryptoRng, RngCore};
-use std::borrow::Cow;
+use std::io::{Read, Seek};
 use std::marker::PhantomData;
 
-pub trait Snark<E: PairingEngine> {
+pub trait Snark<F: Field> {
     type Srs;
 
     type Proof;
@@ -24,22 +30,25 @@ pub trait Snark<E: PairingEngine> {
 
     fn setup<R: RngCore + CryptoRng>(degree: usize, rng: &mut R) -> Self::Srs;
 
-    fn preprocess<A: Arithmetization<E::Fr>>(srs: &Self::Srs, arith: &A) -> (Self::Pk, Self::Vk);
+    fn preprocess<A: Arithmetization<F>>(srs: &Self::Srs, arith: &A) -> (Self::Pk, Self::Vk);
 
-    fn prove<C, R>(circuit: &C, rng: &mut R, pk: &Self::Pk) -> Self::Proof;
+    fn prove<A, R>(arith: &A, rng: &mut R, pk: &Self::Pk) -> Self::Proof
+    where
+        A: Arithmetization<F>,
+        R: RngCore + CryptoRng;
 
-    fn verify(vk: &Self::Vk, proof: &Self::Proof, pi: &[E::Fr]) -> bool;
+    fn verify(vk: &Self::Vk, proof: &Self::Proof, pi: &[F]) -> bool;
 }
 
 #[derive(Default, Clone, Debug, )]
 pub struct Challenge<F: Field>{
-    pub(crate) tau:F,
-    pub(crate) alpha:F,
-    pub(crate) beta:F,
-    pub(crate) gamma:F,
-    pub(crate) zeta:F,
-    pub(crate) u:F,
-    pub(crate) v:F,
+    pub tau:F,
+    pub alpha:F,
+    pub beta:F,
+    pub gamma:F,
+    pub zeta:F,
+    pub u:F,
+    pub v:F,
 }
 
 #[derive(Default, Clone, Debug, )]
@@ -47,169 +56,1208 @@ pub struct Oracle<F:Field>{
     pub(crate) wire_polys: Vec<DensePolynomial<F>>,
     pub(crate) pi_poly: DensePolynomial<F>,
     pub(crate) prod_perm_poly: DensePolynomial<F>,
-    
 }
 
-pub struct Srs<E: PairingEngine>(pub UniversalParams<E>);
+#[derive(CanonicalSerialize)]
+pub struct Srs<PC: PolynomialCommitment>(pub PC::UniversalParams);
 
-pub struct Ck<E: PairingEngine>(pub Vec<E::G1Affine>);
+//Degree of the srs a circuit of domain_size rows needs,
+//z(X) blinded with a degree 2 multiple of Z_H(X) is the largest committed poly
+pub fn srs_degree(domain_size: usize) -> usize {
+    domain_size + 2
+}
 
-pub struct Pk<E: PairingEngine> {
-    identify_perm_polys: Vec<DensePolynomial<E::Fr>>,
+//KZG10 srs from the output of a powers of tau ceremony on a curve like BN254,
+//keeping only the powers circuits up to domain_size rows need
+impl<E, P1, P2> Srs<KZG10<E>>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+    P1::BaseField: PrimeField,
+    P2::BaseField: Field<BasePrimeField = P1::BaseField>,
+{
+    //snarkjs .ptau file
+    pub fn from_ptau<R: Read + Seek>(reader: R, domain_size: usize) -> Result<Self, pcs::Error> {
+        ptau::read_ptau(reader, srs_degree(domain_size)).map(Srs)
+    }
 
-    selector_polys: Vec<DensePolynomial<E::Fr>>,
+    //perpetual powers of tau challenge or response of a ceremony of 2^power powers
+    pub fn from_ppot<R: Read + Seek>(
+        reader: R,
+        file: PpotFile,
+        power: usize,
+        domain_size: usize,
+    ) -> Result<Self, pcs::Error> {
+        ptau::read_ppot(reader, file, power, srs_degree(domain_size)).map(Srs)
+    }
 
-    ck: Ck<E>,
+    pub fn trim_to_domain(&self, domain_size: usize) -> Result<Self, pcs::Error> {
+        self.0.truncate(srs_degree(domain_size)).map(Srs)
+    }
+}
+
+#[derive(CanonicalSerialize)]
+pub struct Pk<PC: PolynomialCommitment> {
+    pub(crate) identify_perm_polys: Vec<DensePolynomial<PC::Field>>,
+
+    pub(crate) selector_polys: Vec<DensePolynomial<PC::Field>>,
 
-    vk: Vk<E>,
+    pub(crate) ck: PC::CommitterKey,
+
+    //Lagrange basis key of the domain, wire polys and z(X) are committed from their evaluations
+    pub(crate) lk: PC::LagrangeKey,
+
+    pub(crate) vk: Vk<PC>,
 }
 
-#[derive(Clone)]
-pub struct Vk<E: PairingEngine> {
+//Fixed base tables of the KZG10 powers, quotient chunks and opening witnesses are then
+//committed without the doublings of the msm, at the memory cost of the tables
+impl<E> Pk<KZG10<E>>
+where
+    E: PairingEngine,
+    E::G1Affine: CurveAffine,
+    E::G2Affine: CurveAffine,
+{
+    pub fn precompute_commit_tables(&mut self, window: usize) {
+        self.ck.precompute(window);
+    }
+}
+
+#[derive(CanonicalSerialize)]
+pub struct Vk<PC: PolynomialCommitment> {
     pub(crate) pi_count: usize,
 
     pub(crate) domain_size: usize,
 
-    pub(crate) identify_perm_commits: Vec<Commitment<E>>,
+    //rows of pi gates, used to evaluate PI(X)
+    pub(crate) pi_gates: Vec<usize>,
+
+    pub(crate) identify_perm_commits: Vec<PC::Commitment>,
+
+    pub(crate) selector_commits: Vec<PC::Commitment>,
+
+    //left coset representatives k_i = g^i used to encode copy constraint,
+    //the verifier computes the identity permutation terms k_i * zeta from them
+    pub(crate) k_reprs: Vec<PC::Field>,
 
-    pub(crate) selector_commits: Vec<Commitment<E>>,
+    pub(crate) vk: PC::VerifierKey,
+}
 
-    vk: VerifierKey<E>,
+impl<PC: PolynomialCommitment> Vk<PC> {
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
 }
 
-pub struct Proof<E: PairingEngine> {
-    wire_poly_commits: Vec<Commitment<E>>,
+impl<PC: PolynomialCommitment> Clone for Vk<PC> {
+    fn clone(&self) -> Self {
+        Self {
+            pi_count: self.pi_count,
+            domain_size: self.domain_size,
+            pi_gates: self.pi_gates.clone(),
+            identify_perm_commits: self.identify_perm_commits.clone(),
+            selector_commits: self.selector_commits.clone(),
+            k_reprs: self.k_reprs.clone(),
+            vk: self.vk.clone(),
+        }
+    }
+}
+
+//How the prover opens the batch at zeta and the product permutation polys at zeta * omega
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OpeningMode {
+    //one opening proof for every point, the verifier merges the checks with challenge u
+    #[default]
+    PerPoint,
 
-    prod_perm_poly_commit: Commitment<E>,
+    //one SHPLONK opening of all points combined with challenge u, with KZG10 it is
+    //two group elements and a single pairing check
+    MultiPoint,
+}
+
+//Whether the prover blinds wire polys and z(X) with multiples of Z_H drawn from its rng
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Hiding {
+    //zero knowledge proofs
+    #[default]
+    Blinded,
+
+    //plain interpolants, the proof only depends on the witness and the rng is not used
+    Disabled,
+}
+
+impl Hiding {
+    fn rng<R>(self, rng: &mut R) -> Option<&mut R> {
+        match self {
+            Hiding::Blinded => Some(rng),
+            Hiding::Disabled => None,
+        }
+    }
+}
+
+pub enum Openings<PC: PolynomialCommitment> {
+    PerPoint {
+        //batch opening proof at zeta
+        opening_proof: PC::Proof,
+
+        //opening proofs of product permutation polys at zeta * omega,
+        //one for every distinct domain size in ascending order
+        shifted_opening_proofs: Vec<PC::Proof>,
+    },
+
+    MultiPoint(shplonk::Proof<PC>),
+}
+
+impl<PC: PolynomialCommitment> Openings<PC> {
+    pub fn mode(&self) -> OpeningMode {
+        match self {
+            Openings::PerPoint { .. } => OpeningMode::PerPoint,
+            Openings::MultiPoint(_) => OpeningMode::MultiPoint,
+        }
+    }
+}
+
+impl<PC: PolynomialCommitment> Clone for Openings<PC> {
+    fn clone(&self) -> Self {
+        match self {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => Openings::PerPoint {
+                opening_proof: opening_proof.clone(),
+                shifted_opening_proofs: shifted_opening_proofs.clone(),
+            },
+            Openings::MultiPoint(proof) => Openings::MultiPoint(proof.clone()),
+        }
+    }
+}
+
+#[derive(CanonicalSerialize)]
+pub struct Proof<PC: PolynomialCommitment> {
+    pub wire_poly_commits: Vec<PC::Commitment>,
+
+    pub prod_perm_poly_commit: PC::Commitment,
+
+    pub quot_poly_commits: Vec<PC::Commitment>,
+
+    //openings at zeta and zeta * omega
+    pub openings: Openings<PC>,
+
+    pub poly_evals: ProofEvaluations<PC::Field>,
+}
+
+impl<PC: PolynomialCommitment> Clone for Proof<PC> {
+    fn clone(&self) -> Self {
+        Self {
+            wire_poly_commits: self.wire_poly_commits.clone(),
+            prod_perm_poly_commit: self.prod_perm_poly_commit.clone(),
+            quot_poly_commits: self.quot_poly_commits.clone(),
+            openings: self.openings.clone(),
+            poly_evals: self.poly_evals.clone(),
+        }
+    }
 }
 
-pub struct BatchProof<E: PairingEngine> {
-    wire_poly_commits_vec: Vec<Vec<Commitment<E>>>,
+#[derive(CanonicalSerialize)]
+pub struct BatchProof<PC: PolynomialCommitment> {
+    pub wire_poly_commits_vec: Vec<Vec<PC::Commitment>>,
 
-    prod_perm_poly_commit_vec: Vec<Commitment<E>>,
+    pub prod_perm_poly_commit_vec: Vec<PC::Commitment>,
+
+    //chunks of the quotient poly combined over all circuits
+    pub quot_poly_commits: Vec<PC::Commitment>,
+
+    //openings of all circuits at zeta, and at zeta * omega for every distinct domain size
+    pub openings: Openings<PC>,
+
+    pub poly_evals_vec: Vec<ProofEvaluations<PC::Field>>,
+}
+
+impl<PC: PolynomialCommitment> Clone for BatchProof<PC> {
+    fn clone(&self) -> Self {
+        Self {
+            wire_poly_commits_vec: self.wire_poly_commits_vec.clone(),
+            prod_perm_poly_commit_vec: self.prod_perm_poly_commit_vec.clone(),
+            quot_poly_commits: self.quot_poly_commits.clone(),
+            openings: self.openings.clone(),
+            poly_evals_vec: self.poly_evals_vec.clone(),
+        }
+    }
 }
 
+//Transcript rounds the prover squeezes challenges in, the verifier squeezes u in one more round
+const PROVER_ROUNDS: usize = 4;
+
+//Label of the transcript of a single proof, a verifier contract starts from its hash
+pub(crate) const TRANSCRIPT_LABEL: &str = "plonk transcript";
 
-pub struct PlonkSnark<E: PairingEngine>(PhantomData<E>);
-impl<E,F,P> PlonkSnark<E>
+//Prover and verifier derive the challenges with transcript T, merlin unless chosen otherwise
+pub struct PlonkSnark<PC: PolynomialCommitment, T = PlonkTranscript<<PC as PolynomialCommitment>::Field>>(
+    PhantomData<(PC, T)>,
+);
+
+impl<PC, T> PlonkSnark<PC, T>
 where
-    E: PairingEngine<Fq = F,G1Affine = GroupAffine<P>>,
-    F: Field,
-    P: SWModelParameters<BaseField = F>,
+    PC: PolynomialCommitment,
+    T: Transcript<PC::Field>,
 {
-    fn generate_ck_and_vk(srs: &UniversalParams<E>, degree: usize) -> (Powers<E>, VerifierKey<E>) 
+    //Committer key supports polys of degree < size
+    fn generate_ck_and_vk(srs: &PC::UniversalParams, size: usize) -> (PC::CommitterKey, PC::VerifierKey) {
+        PC::trim(srs, size - 1).unwrap()
+    }
+
+    //Permutation challenges beta and gamma are squeezed only after the wire commitments
+    //are absorbed, so the prover can not choose the witness depending on them.
+    //Prover and verifier both derive them here.
+    fn get_perm_challenges(transcript: &mut T, wire_poly_commits: &[PC::Commitment]) -> (PC::Field, PC::Field)
     {
-        let powers_of_g = srs.powers_of_g[..degree].to_vec();
-        let powers_of_gamma_g = vec![];
+        transcript.append_commitments("wire polynomial commitments", wire_poly_commits);
+        let beta = transcript.get_challenge("beta");
+        let gamma = transcript.get_challenge("gamma");
+        (beta, gamma)
+    }
 
-        let powers = Powers {
-            powers_of_g: Cow::Owned(powers_of_g),
-            powers_of_gamma_g: Cow::Owned(powers_of_gamma_g),
+    //Per point openings are absorbed before u is squeezed. A multi point opening is made after u,
+    //which combines its claims, so the prover squeezes u right after v.
+    fn get_opening_challenge(transcript: &mut T, openings: &Openings<PC>) -> PC::Field
+    {
+        if let Openings::PerPoint {
+            opening_proof,
+            shifted_opening_proofs,
+        } = openings
+        {
+            transcript.append_commitment("opening proof", opening_proof);
+            transcript.append_commitments("shifted opening proof", shifted_opening_proofs);
+        }
+        let u = transcript.get_challenge("u");
+        debug_assert_eq!(
+            transcript.round(),
+            PROVER_ROUNDS + (openings.mode() == OpeningMode::PerPoint) as usize
+        );
+        u
+    }
+
+    //Wire polys and z(X) are blinded with randomness from rng, None gives a proof without hiding
+    fn prove_iop<A, R>(
+        arith: &A,
+        pk: &Pk<PC>,
+        mode: OpeningMode,
+        mut rng: Option<&mut R>,
+    ) -> (Proof<PC>, Oracle<PC::Field>, Challenge<PC::Field>)
+    where
+        A: Arithmetization<PC::Field>,
+        R: RngCore,
+    {
+        let mut transcript = T::new(TRANSCRIPT_LABEL);
+        transcript.append_vk_and_pi(&pk.vk, &arith.circuit().pi_value());
+        let mut challenge = Challenge::default();
+        let prover: Prover<PC> = Prover::new(arith.domain_size());
+
+        //round 1
+        let (wire_poly_commits, wire_polys) = prover.round1(&pk.lk, arith, rng.as_deref_mut());
+        let pi_poly = arith.generate_pi_poly();
+        (challenge.beta, challenge.gamma) =
+            Self::get_perm_challenges(&mut transcript, &wire_poly_commits);
+
+        //round 2
+        let (prod_perm_poly_commit, prod_perm_poly) = prover.round2(&pk.lk, arith, &challenge, rng);
+        transcript.append_commitment("product permutation polynomial commitment", &prod_perm_poly_commit);
+        challenge.alpha = transcript.get_challenge("alpha");
+        let oracle = Oracle {
+            wire_polys,
+            pi_poly,
+            prod_perm_poly,
         };
 
-        let vk = VerifierKey {
-            g: srs.powers_of_g[0],
-            gamma_g: E::G1Affine::default(),
-            h: srs.h,
-            beta_h: srs.beta_h,
-            prepared_h: srs.prepared_h.clone(),
-            prepared_beta_h: srs.prepared_beta_h.clone(),
+        //round 3
+        let (quot_poly_commits, quot_polys) = prover.round3(&pk.ck, pk, &challenge, &oracle);
+        transcript.append_commitments("quotient polynomial commitments", &quot_poly_commits);
+        challenge.zeta = transcript.get_challenge("zeta");
+
+        //round 4
+        let poly_evals = prover.round4(pk, &challenge, &oracle);
+        transcript.append_proof_eval(&poly_evals);
+        challenge.v = transcript.get_challenge("v");
+        if mode == OpeningMode::MultiPoint {
+            challenge.u = transcript.get_challenge("u");
+        }
+        debug_assert_eq!(transcript.round(), PROVER_ROUNDS);
+
+        //round 5
+        let openings = prover.round5(pk, &challenge, &oracle, &poly_evals, &quot_polys, mode);
+        let proof = Proof {
+            wire_poly_commits,
+            prod_perm_poly_commit,
+            quot_poly_commits,
+            openings,
+            poly_evals,
         };
-        (powers, vk)
+        (proof, oracle, challenge)
     }
 
-    fn batch_prove_iop<C, R,T>(arith: &[&C], rng: R, pk: &[&Pk<E>]) -> (BatchProof<E>, Vec<Oracle<E::Fr>>, Challenge<E::Fr>)
+    fn compute_challenges(vk: &Vk<PC>, proof: &Proof<PC>, pi: &[PC::Field]) -> Challenge<PC::Field>
+    {
+        let mut transcript = T::new(TRANSCRIPT_LABEL);
+        transcript.append_vk_and_pi(vk, pi);
+        let mut challenge = Challenge::default();
+
+        (challenge.beta, challenge.gamma) =
+            Self::get_perm_challenges(&mut transcript, &proof.wire_poly_commits);
+        transcript.append_commitment("product permutation polynomial commitment", &proof.prod_perm_poly_commit);
+        challenge.alpha = transcript.get_challenge("alpha");
+        transcript.append_commitments("quotient polynomial commitments", &proof.quot_poly_commits);
+        challenge.zeta = transcript.get_challenge("zeta");
+        transcript.append_proof_eval(&proof.poly_evals);
+        challenge.v = transcript.get_challenge("v");
+        challenge.u = Self::get_opening_challenge(&mut transcript, &proof.openings);
+        challenge
+    }
+
+    fn verify_iop(vk: &Vk<PC>, proof: &Proof<PC>, pi: &[PC::Field]) -> bool
+    {
+        let verifier: Verifier<PC> = match Verifier::new(vk.domain_size) {
+            Some(verifier) => verifier,
+            None => return false,
+        };
+        if !verifier.validate_proof(vk, proof, pi) {
+            return false;
+        }
+        let challenge = Self::compute_challenges(vk, proof, pi);
+        verifier.batch_check(vk, proof, pi, &challenge)
+    }
+
+    //Prove many circuits in one proof, they share the transcript, challenges, quotient and
+    //openings, see Verifier::batch_check_proofs for how the circuits are combined.
+    //Circuits may have different domain sizes, all pks must come from the same srs.
+    #[allow(clippy::type_complexity)]
+    fn batch_prove_iop<C, R>(
+        arith: &[&C],
+        pk: &[&Pk<PC>],
+        mode: OpeningMode,
+        mut rng: Option<&mut R>,
+    ) -> Result<(BatchProof<PC>, Vec<Oracle<PC::Field>>, Challenge<PC::Field>), pcs::Error>
     where
-        C: Arithmetization<E::Fr>,
-        R: CryptoRng + RngCore,
-        T: Transcript<F>,
+        C: Arithmetization<PC::Field>,
+        R: RngCore,
     {
-        let domain_size = arith[0].domain_size();
-        let mut transcript = T::new("plonk transcript");
-        for (pk,a) in pk.iter().zip(arith.iter()){
-            transcript.append_vk_and_pi(&pk.vk,&a.circuit().pi_value());
+        if arith.is_empty() {
+            return Err(pcs::Error::InvalidBatch("no circuit"));
+        }
+        if arith.len() != pk.len() {
+            return Err(pcs::Error::InvalidBatch("one pk per circuit"));
+        }
+        if arith.iter().zip(pk.iter()).any(|(a, pk)| a.domain_size() != pk.vk.domain_size) {
+            return Err(pcs::Error::InvalidBatch("pk of another domain size"));
+        }
+        let mut transcript = T::new("plonk batch transcript");
+        for (pk, a) in pk.iter().zip(arith.iter()) {
+            transcript.append_vk_and_pi(&pk.vk, &a.circuit().pi_value());
         }
         let mut challenge = Challenge::default();
-        let mut oracles = vec![Oracle::default();arith.len()];
-        let prover: Prover<E>= Prover::new(domain_size);
+        let provers: Vec<Prover<PC>> = arith.iter().map(|a| Prover::new(a.domain_size())).collect();
+        //the pk of the largest domain has the largest commitment key
+        let max_id = (0..arith.len()).max_by_key(|&i| arith[i].domain_size()).unwrap();
+        let ck = &pk[max_id].ck;
 
         //round 1
         let mut wire_poly_commits_vec = vec![];
-        let mut prod_perm_poly_commit_vec= vec![];
-        (BatchProof{ wire_poly_commits_vec, prod_perm_poly_commit_vec},oracles,challenge)
+        let mut oracles = vec![];
+        for (i, prover) in provers.iter().enumerate() {
+            let (wire_poly_commits, wire_polys) =
+                prover.round1(&pk[i].lk, arith[i], rng.as_deref_mut());
+            transcript.append_commitments("wire polynomial commitments", &wire_poly_commits);
+            wire_poly_commits_vec.push(wire_poly_commits);
+            oracles.push(Oracle {
+                wire_polys,
+                pi_poly: arith[i].generate_pi_poly(),
+                ..Default::default()
+            });
+        }
+        challenge.beta = transcript.get_challenge("beta");
+        challenge.gamma = transcript.get_challenge("gamma");
+
+        //round 2
+        let mut prod_perm_poly_commit_vec = vec![];
+        for (i, prover) in provers.iter().enumerate() {
+            let (prod_perm_poly_commit, prod_perm_poly) =
+                prover.round2(&pk[i].lk, arith[i], &challenge, rng.as_deref_mut());
+            transcript.append_commitment("product permutation polynomial commitment", &prod_perm_poly_commit);
+            prod_perm_poly_commit_vec.push(prod_perm_poly_commit);
+            oracles[i].prod_perm_poly = prod_perm_poly;
+        }
+        challenge.alpha = transcript.get_challenge("alpha");
+
+        //round 3, t(X) = sum alpha^(3i) * t_i(X)
+        let alpha_base = challenge.alpha.pow([3u64]);
+        let mut alpha_pow = PC::Field::one();
+        let mut quot_poly = DensePolynomial::zero();
+        for (i, prover) in provers.iter().enumerate() {
+            quot_poly += (alpha_pow, &prover.compute_quotient_poly(pk[i], &challenge, &oracles[i]));
+            alpha_pow *= alpha_base;
+        }
+        let chunk_size = quot_chunk_size(arith[max_id].domain_size());
+        let quot_polys = split_quot_poly(&quot_poly, chunk_size);
+        let quot_poly_commits = Prover::<PC>::commit_polynomials(ck, &quot_polys);
+        transcript.append_commitments("quotient polynomial commitments", &quot_poly_commits);
+        challenge.zeta = transcript.get_challenge("zeta");
+
+        //round 4
+        let mut poly_evals_vec = vec![];
+        for (i, prover) in provers.iter().enumerate() {
+            let poly_evals = prover.round4(pk[i], &challenge, &oracles[i]);
+            transcript.append_proof_eval(&poly_evals);
+            poly_evals_vec.push(poly_evals);
+        }
+        challenge.v = transcript.get_challenge("v");
+        if mode == OpeningMode::MultiPoint {
+            challenge.u = transcript.get_challenge("u");
+        }
+        debug_assert_eq!(transcript.round(), PROVER_ROUNDS);
+
+        //round 5, circuit i is scaled by alpha^(3i) * Z_N(zeta) / Z_i(zeta)
+        let zeta = challenge.zeta;
+        let vanish_eval = provers[max_id].domain.evaluate_vanishing_polynomial(zeta);
+        let mut polys: Vec<&DensePolynomial<PC::Field>> = vec![];
+        let mut scalars: Vec<PC::Field> = vec![];
+        let mut alpha_pow = PC::Field::one();
+        for (i, prover) in provers.iter().enumerate() {
+            let lambda = vanish_eval / prover.domain.evaluate_vanishing_polynomial(zeta);
+            let (p, s) = prover.compute_lin_terms(pk[i], &challenge, &oracles[i], &poly_evals_vec[i]);
+            polys.extend(p);
+            scalars.extend(s.iter().map(|s| alpha_pow * lambda * s));
+            alpha_pow *= alpha_base;
+        }
+        polys.extend(quot_polys.iter());
+        scalars.extend(quot_lin_scalars(quot_polys.len(), zeta, vanish_eval, chunk_size));
+
+        //batch polys opened at zeta with challenge v, in the order the verifier combines them
+        let mut v_pow = PC::Field::one();
+        for (oracle, pk) in oracles.iter().zip(pk.iter()) {
+            for poly in oracle
+                .wire_polys
+                .iter()
+                .chain(pk.identify_perm_polys[..WIRE_COUNT - 1].iter())
+            {
+                v_pow *= challenge.v;
+                polys.push(poly);
+                scalars.push(v_pow);
+            }
+        }
+        let mut claim_polys = vec![polys];
+        let mut claim_scalars = vec![scalars];
+        let mut points = vec![zeta];
+
+        //product permutation polys with the same domain share the opening point zeta * omega
+        let vks: Vec<&Vk<PC>> = pk.iter().map(|pk| &pk.vk).collect();
+        for domain_size in batch_domain_sizes(&vks) {
+            let (group_provers, group_oracles): (Vec<_>, Vec<_>) = provers
+                .iter()
+                .zip(oracles.iter())
+                .filter(|(prover, _)| prover.domain.size() == domain_size)
+                .unzip();
+            let shifted_polys: Vec<&DensePolynomial<PC::Field>> =
+                group_oracles.iter().map(|oracle| &oracle.prod_perm_poly).collect();
+            let omega = group_provers[0].domain.group_gen;
+            claim_scalars.push(powers(challenge.v, shifted_polys.len()));
+            claim_polys.push(shifted_polys);
+            points.push(zeta * omega);
+        }
+        let openings =
+            Prover::<PC>::open_claims(ck, &claim_polys, &claim_scalars, &points, mode, challenge.u);
+
+        let proof = BatchProof {
+            wire_poly_commits_vec,
+            prod_perm_poly_commit_vec,
+            quot_poly_commits,
+            openings,
+            poly_evals_vec,
+        };
+        Ok((proof, oracles, challenge))
+    }
+
+    fn compute_batch_challenges(vks: &[&Vk<PC>], proof: &BatchProof<PC>, pi: &[&[PC::Field]]) -> Challenge<PC::Field>
+    {
+        let mut transcript = T::new("plonk batch transcript");
+        for (vk, pi) in vks.iter().zip(pi.iter()) {
+            transcript.append_vk_and_pi(vk, pi);
+        }
+        let mut challenge = Challenge::default();
+
+        for wire_poly_commits in proof.wire_poly_commits_vec.iter() {
+            transcript.append_commitments("wire polynomial commitments", wire_poly_commits);
+        }
+        challenge.beta = transcript.get_challenge("beta");
+        challenge.gamma = transcript.get_challenge("gamma");
+        for prod_perm_poly_commit in proof.prod_perm_poly_commit_vec.iter() {
+            transcript.append_commitment("product permutation polynomial commitment", prod_perm_poly_commit);
+        }
+        challenge.alpha = transcript.get_challenge("alpha");
+        transcript.append_commitments("quotient polynomial commitments", &proof.quot_poly_commits);
+        challenge.zeta = transcript.get_challenge("zeta");
+        for poly_evals in proof.poly_evals_vec.iter() {
+            transcript.append_proof_eval(poly_evals);
+        }
+        challenge.v = transcript.get_challenge("v");
+        challenge.u = Self::get_opening_challenge(&mut transcript, &proof.openings);
+        challenge
+    }
 
+    fn batch_verify_iop(vks: &[&Vk<PC>], proof: &BatchProof<PC>, pi: &[&[PC::Field]]) -> bool
+    {
+        let verifiers: Vec<Verifier<PC>> = match vks.iter().map(|vk| Verifier::new(vk.domain_size)).collect() {
+            Some(verifiers) => verifiers,
+            None => return false,
+        };
+        if !Verifier::validate_batch_proof(&verifiers, vks, proof, pi) {
+            return false;
+        }
+        let challenge = Self::compute_batch_challenges(vks, proof, pi);
+        Verifier::batch_check_proofs(&verifiers, vks, proof, pi, &challenge)
+    }
+
+    pub fn batch_prove<C, R>(arith: &[&C], rng: &mut R, pk: &[&Pk<PC>]) -> Result<BatchProof<PC>, pcs::Error>
+    where
+        C: Arithmetization<PC::Field>,
+        R: RngCore + CryptoRng,
+    {
+        Self::batch_prove_with_mode(arith, rng, pk, OpeningMode::default(), Hiding::default())
+    }
+
+    pub fn batch_prove_with_mode<C, R>(
+        arith: &[&C],
+        rng: &mut R,
+        pk: &[&Pk<PC>],
+        mode: OpeningMode,
+        hiding: Hiding,
+    ) -> Result<BatchProof<PC>, pcs::Error>
+    where
+        C: Arithmetization<PC::Field>,
+        R: RngCore + CryptoRng,
+    {
+        let (proof, _, _) = Self::batch_prove_iop::<C, R>(arith, pk, mode, hiding.rng(rng))?;
+        Ok(proof)
+    }
+
+    pub fn prove_with_mode<A, R>(
+        arith: &A,
+        rng: &mut R,
+        pk: &Pk<PC>,
+        mode: OpeningMode,
+        hiding: Hiding,
+    ) -> Proof<PC>
+    where
+        A: Arithmetization<PC::Field>,
+        R: RngCore + CryptoRng,
+    {
+        let (proof, _, _) = Self::prove_iop::<A, R>(arith, pk, mode, hiding.rng(rng));
+        proof
+    }
+
+    //Costs two pairings no matter how many circuits are in the batch
+    pub fn batch_verify(vks: &[&Vk<PC>], proof: &BatchProof<PC>, pi: &[&[PC::Field]]) -> bool {
+        Self::batch_verify_iop(vks, proof, pi)
     }
 }
 
-impl<E,F,P> Snark<E> for PlonkSnark<E>
+impl<PC, T> Snark<PC::Field> for PlonkSnark<PC, T>
 where
-    E: PairingEngine<Fq = F,G1Affine = GroupAffine<P>>,
-    F: Field,
-    P: SWModelParameters<BaseField = F>,
+    PC: PolynomialCommitment,
+    T: Transcript<PC::Field>,
 {
-    type Srs = Srs<E>;
+    type Srs = Srs<PC>;
 
-    type Proof = Proof<E>;
+    type Proof = Proof<PC>;
 
-    type Pk = Pk<E>;
+    type Pk = Pk<PC>;
 
-    type Vk = Vk<E>;
+    type Vk = Vk<PC>;
 
     fn setup<R: RngCore + CryptoRng>(degree: usize, rng: &mut R) -> Self::Srs {
-        let srs = KZG10::<E, DensePolynomial<E::Fr>>::setup(degree, false, rng).unwrap();
+        let srs = PC::setup(degree, rng).unwrap();
         Srs(srs)
     }
 
-    fn preprocess<A: Arithmetization<<E as PairingEngine>::Fr>>(
+    fn preprocess<A: Arithmetization<PC::Field>>(
         srs: &Self::Srs,
         arith: &A,
     ) -> (Self::Pk, Self::Vk) {
         let domain_size = arith.domain_size();
-        let srs_size = domain_size + 2;
+        let srs_size = srs_degree(domain_size) + 1;
         let pi_count = arith.pi_count();
+        let pi_gates = arith.circuit().pi_gates();
         let selector_polys = arith.generate_selector_polys();
         let identify_perm_polys = arith.generate_identify_perm_polys();
+        let k_reprs = arith.generate_left_coset_repr();
 
-        let (powers, ok) = PlonkSnark::generate_ck_and_vk(&srs.0, srs_size);
-        let selector_commits: Vec<Commitment<E>> = selector_polys
-            .iter()
-            .map(|poly| {
-                let (commit, _) = KZG10::commit(&powers, poly, None, None).unwrap();
-                commit
-            })
-            .collect();
-        let identify_perm_commits: Vec<Commitment<E>> = identify_perm_polys
-            .iter()
-            .map(|poly| {
-                let (commit, _) = KZG10::commit(&powers, poly, None, None).unwrap();
-                commit
-            })
-            .collect();
+        let (ck, ok) = Self::generate_ck_and_vk(&srs.0, srs_size);
+        let lk = PC::lagrange_key(&ck, domain_size).unwrap();
+        let selector_commits = Prover::<PC>::commit_polynomials(&ck, &selector_polys);
+        let identify_perm_commits = Prover::<PC>::commit_polynomials(&ck, &identify_perm_polys);
         let vk = Vk {
             pi_count,
             domain_size,
+            pi_gates,
             identify_perm_commits,
             selector_commits,
+            k_reprs,
             vk: ok,
         };
-        let ck = Ck(powers.powers_of_g.to_vec());
         let pk = Pk {
             identify_perm_polys,
             selector_polys,
             ck,
+            lk,
             vk: vk.clone(),
         };
         (pk, vk)
     }
 
-    fn prove<C, R>(circuit: &C, rng: &mut R, pk: &Self::Pk) -> Self::Proof {
-        Proof {
-            wire_poly_commits: todo!(),
-            prod_perm_poly_commit: todo!(),
+    fn prove<A, R>(arith: &A, rng: &mut R, pk: &Self::Pk) -> Self::Proof
+    where
+        A: Arithmetization<PC::Field>,
+        R: RngCore + CryptoRng,
+    {
+        Self::prove_with_mode(arith, rng, pk, OpeningMode::default(), Hiding::default())
+    }
+
+    fn verify(vk: &Self::Vk, proof: &Self::Proof, pi: &[PC::Field]) -> bool {
+        Self::verify_iop(vk, proof, pi)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::snark::{srs_degree, Hiding, OpeningMode, Openings, Pk, PlonkSnark, Proof, Snark, Vk};
+    use crate::transcript::{KeccakTranscript, PoseidonTranscript, Transcript};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{One, PrimeField};
+    use ark_pallas::Fr as PallasFr;
+    use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain};
+    use ark_serialize::CanonicalSerialize;
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use constraint::circuit::{Circuit, PlonkCircuit};
+    use pcs::fri::FRI;
+    use pcs::ipa::IPA;
+    use pcs::kzg10::KZG10;
+    use pcs::PolynomialCommitment;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn build_circuit() -> PlonkCircuit<Fr> {
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(3u32), false);
+        let b = circuit.create_var(Fr::from(4u32), false);
+        let c = circuit.add(a, b);
+        let d = circuit.mul(c, b);
+        let e = circuit.sub(d, a);
+        let f = circuit.mul(e, e);
+        let g = circuit.add(f, c);
+        let h = circuit.create_var(Fr::from(632u32), false);
+        circuit.equal_gate(g, h);
+        circuit
+    }
+
+    #[test]
+    fn test_trimmed_srs() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let circuit = build_circuit();
+        let arith = PlonkArithmetization::new(&circuit);
+        let domain_size = arith.domain_size();
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(4 * domain_size, &mut rng);
+        let trimmed = srs.trim_to_domain(domain_size).unwrap();
+        assert_eq!(trimmed.0.max_degree(), Ok(srs_degree(domain_size)));
+        assert!(srs.trim_to_domain(4 * domain_size).is_err());
+
+        let (mut pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&trimmed, &arith);
+        assert_eq!(vk.domain_size(), domain_size);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[]));
+
+        pk.precompute_commit_tables(6);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[]));
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circuit = build_circuit();
+        assert_eq!(circuit.gate_count(), 8);
+        assert!(circuit.check_circuit(&[]));
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[]));
+
+        //wrong public input size
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[Fr::one()]));
+
+        //tampered commitment
+        let mut bad_proof = proof.clone();
+        bad_proof.wire_poly_commits[0].0 = (bad_proof.wire_poly_commits[0].0.into_projective()
+            + vk.vk.g.into_projective())
+        .into_affine();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &bad_proof, &[]));
+
+        let mut bad_proof = proof.clone();
+        bad_proof.quot_poly_commits.swap(0, 1);
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &bad_proof, &[]));
+
+        //tampered evaluation
+        let mut bad_proof = proof.clone();
+        bad_proof.poly_evals.wire_evals[2] += Fr::one();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &bad_proof, &[]));
+
+        let mut bad_proof = proof.clone();
+        bad_proof.poly_evals.prod_perm_eval += Fr::one();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &bad_proof, &[]));
+
+        //tampered opening proof
+        let mut bad_proof = proof;
+        if let Openings::PerPoint {
+            opening_proof,
+            shifted_opening_proofs,
+        } = &mut bad_proof.openings
+        {
+            *opening_proof = shifted_opening_proofs[0];
+        }
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &bad_proof, &[]));
+    }
+
+    #[test]
+    fn test_verify_public_input() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(3u32), true);
+        let b = circuit.create_var(Fr::from(4u32), false);
+        circuit.mul(a, b);
+        let pi = circuit.pi_value();
+        assert_eq!(pi, vec![Fr::from(3u32)]);
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &pi));
+
+        //tampered public input
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[Fr::from(5u32)]));
+
+        //the challenges bind the rows of the public inputs
+        let mut moved_vk = vk.clone();
+        moved_vk.pi_gates[0] += 1;
+        let challenge = PlonkSnark::<KZG10<Bls12_381>>::compute_challenges(&vk, &proof, &pi);
+        let moved_challenge = PlonkSnark::<KZG10<Bls12_381>>::compute_challenges(&moved_vk, &proof, &pi);
+        assert_ne!(challenge.beta, moved_challenge.beta);
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&moved_vk, &proof, &pi));
+
+        //malformed vks are rejected, not indexed out of bounds
+        let mut bad_vk = vk.clone();
+        bad_vk.identify_perm_commits.pop();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&bad_vk, &proof, &pi));
+        let mut bad_vk = vk.clone();
+        bad_vk.k_reprs.pop();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&bad_vk, &proof, &pi));
+        let mut bad_vk = vk.clone();
+        bad_vk.pi_gates.clear();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&bad_vk, &proof, &pi));
+        //no domain of 2^40 elements in the scalar field of BLS12-381
+        let mut bad_vk = vk.clone();
+        bad_vk.domain_size = 1 << 40;
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&bad_vk, &proof, &pi));
+        let batch_proof = PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&[&arith], &mut rng, &[&pk]).unwrap();
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&[&vk], &batch_proof, &[&pi]));
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&[&bad_vk], &batch_proof, &[&pi]));
+    }
+
+    #[test]
+    fn test_verify_wrong_witness() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(3u32), false);
+        let b = circuit.create_var(Fr::from(4u32), false);
+        //a + b != c
+        let c = circuit.create_var(Fr::from(8u32), false);
+        circuit.add_gate(a, b, c);
+        circuit.equal_gate(c, c);
+        assert!(!circuit.check_circuit(&[]));
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[]));
+    }
+
+    #[test]
+    fn test_verify_padded_circuit() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for (gate_count, blinding_rows) in [(3, 0), (5, 0), (17, 0), (5, 3), (17, 16)] {
+            let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+            let mut a = circuit.create_var(Fr::from(2u32), true);
+            let b = circuit.create_var(Fr::from(3u32), false);
+            while circuit.gate_count() < gate_count {
+                a = if circuit.gate_count() % 2 == 0 {
+                    circuit.add(a, b)
+                } else {
+                    circuit.mul(a, b)
+                };
+            }
+            assert_eq!(circuit.gate_count(), gate_count);
+            let pi = circuit.pi_value();
+            let arith = PlonkArithmetization::with_blinding_rows(&circuit, blinding_rows);
+            let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+            let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+            let proof = PlonkSnark::<KZG10<Bls12_381>>::prove(&arith, &mut rng, &pk);
+            assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &pi));
+            assert!(!PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[Fr::one()]));
+        }
+    }
+
+    #[test]
+    fn test_perm_challenges_from_transcript() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let circuit = build_circuit();
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+        let (proof, oracle, challenge) =
+            PlonkSnark::<KZG10<Bls12_381>>::prove_iop::<_, StdRng>(&arith, &pk, OpeningMode::PerPoint, None);
+
+        //verifier derives the same challenges from the proof
+        let verifier_challenge =
+            PlonkSnark::<KZG10<Bls12_381>>::compute_challenges(&vk, &proof, &[]);
+        assert_eq!(challenge.beta, verifier_challenge.beta);
+        assert_eq!(challenge.gamma, verifier_challenge.gamma);
+        assert_eq!(challenge.alpha, verifier_challenge.alpha);
+        assert_eq!(challenge.zeta, verifier_challenge.zeta);
+        assert_eq!(challenge.v, verifier_challenge.v);
+        assert_eq!(
+            oracle.prod_perm_poly,
+            arith.generate_prod_perm_poly(verifier_challenge.beta, verifier_challenge.gamma)
+        );
+
+        //beta and gamma are bound to the wire commitments
+        let mut bad_proof = proof;
+        bad_proof.wire_poly_commits.swap(0, 1);
+        let bad_challenge =
+            PlonkSnark::<KZG10<Bls12_381>>::compute_challenges(&vk, &bad_proof, &[]);
+        assert_ne!(challenge.beta, bad_challenge.beta);
+        assert_ne!(challenge.gamma, bad_challenge.gamma);
+    }
+
+    #[test]
+    fn test_hiding_proof() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let circuit = build_circuit();
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, &arith);
+
+        //without hiding the proof only depends on the witness, whatever rng is passed
+        let bytes = |proof: &Proof<KZG10<Bls12_381>>| {
+            let mut bytes = Vec::new();
+            proof.serialize(&mut bytes).unwrap();
+            bytes
+        };
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::prove_with_mode(
+            &arith,
+            &mut StdRng::seed_from_u64(1),
+            &pk,
+            OpeningMode::PerPoint,
+            Hiding::Disabled,
+        );
+        let same_proof = PlonkSnark::<KZG10<Bls12_381>>::prove_with_mode(
+            &arith,
+            &mut StdRng::seed_from_u64(2),
+            &pk,
+            OpeningMode::PerPoint,
+            Hiding::Disabled,
+        );
+        assert_eq!(bytes(&proof), bytes(&same_proof));
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &proof, &[]));
+        let blinded = PlonkSnark::<KZG10<Bls12_381>>::prove_with_mode(
+            &arith,
+            &mut rng,
+            &pk,
+            OpeningMode::PerPoint,
+            Hiding::Blinded,
+        );
+        assert_ne!(proof.wire_poly_commits, blinded.wire_poly_commits);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &blinded, &[]));
+
+        //the same switch applies to batches
+        let batch_bytes = |seed: u64, hiding: Hiding| {
+            let proof = PlonkSnark::<KZG10<Bls12_381>>::batch_prove_with_mode(
+                &[&arith, &arith],
+                &mut StdRng::seed_from_u64(seed),
+                &[&pk, &pk],
+                OpeningMode::PerPoint,
+                hiding,
+            ).unwrap();
+            assert!(PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&[&vk, &vk], &proof, &[&[], &[]]));
+            let mut bytes = Vec::new();
+            proof.serialize(&mut bytes).unwrap();
+            bytes
+        };
+        assert_eq!(batch_bytes(1, Hiding::Disabled), batch_bytes(2, Hiding::Disabled));
+        assert_ne!(batch_bytes(1, Hiding::Blinded), batch_bytes(2, Hiding::Blinded));
+
+        //blinded wire polys and z(X) still satisfy the constraints over the domain
+        let (hiding_proof, oracle, challenge) = PlonkSnark::<KZG10<Bls12_381>>::prove_iop::<_, _>(&arith, &pk, OpeningMode::PerPoint, Some(&mut rng));
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::verify(&vk, &hiding_proof, &[]));
+        let domain_size = arith.domain_size();
+        assert_eq!(oracle.wire_polys[0].degree(), domain_size + 1);
+        assert_eq!(oracle.prod_perm_poly.degree(), domain_size + 2);
+        let wire_polys = arith.generate_wire_polys();
+        let prod_perm_poly = arith.generate_prod_perm_poly(challenge.beta, challenge.gamma);
+        let domain = Radix2EvaluationDomain::<Fr>::new(domain_size).unwrap();
+        for x in domain.elements() {
+            for (blinded, poly) in oracle.wire_polys.iter().zip(wire_polys.iter()) {
+                assert_eq!(blinded.evaluate(&x), poly.evaluate(&x));
+            }
+            assert_eq!(oracle.prod_perm_poly.evaluate(&x), prod_perm_poly.evaluate(&x));
         }
+        assert_ne!(proof.wire_poly_commits, hiding_proof.wire_poly_commits);
+        assert_ne!(proof.poly_evals.wire_evals, hiding_proof.poly_evals.wire_evals);
     }
 
-    fn verify(vk: &Self::Vk, proof: &Self::Proof, pi: &[<E as PairingEngine>::Fr]) -> bool {
-        true
+    pub fn build_chain_circuit<F: PrimeField>(gate_count: usize, pi: u32) -> PlonkCircuit<F> {
+        let mut circuit: PlonkCircuit<F> = PlonkCircuit::new();
+        let mut a = circuit.create_var(F::from(pi), true);
+        let b = circuit.create_var(F::from(3u32), false);
+        while circuit.gate_count() < gate_count {
+            a = if circuit.gate_count() % 2 == 0 {
+                circuit.add(a, b)
+            } else {
+                circuit.mul(a, b)
+            };
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_batch_prove_and_verify() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let circuits = [
+            build_chain_circuit::<Fr>(3, 2),
+            build_chain_circuit(6, 5),
+            build_chain_circuit(6, 7),
+            build_chain_circuit(20, 11),
+        ];
+        let ariths: Vec<_> = circuits.iter().map(PlonkArithmetization::new).collect();
+        let max_domain_size = ariths.iter().map(|a| a.domain_size()).max().unwrap();
+        assert_eq!(max_domain_size, 32);
+        let srs = PlonkSnark::<KZG10<Bls12_381>>::setup(max_domain_size + 2, &mut rng);
+        let (pks, vks): (Vec<_>, Vec<_>) = ariths
+            .iter()
+            .map(|arith| PlonkSnark::<KZG10<Bls12_381>>::preprocess(&srs, arith))
+            .unzip();
+        let pis: Vec<Vec<Fr>> = circuits.iter().map(|c| c.pi_value()).collect();
+        let arith_refs: Vec<_> = ariths.iter().collect();
+        let pk_refs: Vec<_> = pks.iter().collect();
+        let vk_refs: Vec<_> = vks.iter().collect();
+        let pi_refs: Vec<&[Fr]> = pis.iter().map(|pi| pi.as_slice()).collect();
+
+        let proof = PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&arith_refs, &mut rng, &pk_refs).unwrap();
+        //domain sizes 4, 8 and 32
+        assert!(matches!(
+            &proof.openings,
+            Openings::PerPoint { shifted_opening_proofs, .. } if shifted_opening_proofs.len() == 3
+        ));
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &proof, &pi_refs));
+
+        //a single circuit batch
+        let single_proof = PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&arith_refs[..1], &mut rng, &pk_refs[..1]).unwrap();
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs[..1], &single_proof, &pi_refs[..1]));
+
+        //batches built from runtime data fail with an error
+        let no_circuit: [&PlonkArithmetization<Fr>; 0] = [];
+        assert_eq!(
+            PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&no_circuit, &mut rng, &[]).err(),
+            Some(pcs::Error::InvalidBatch("no circuit"))
+        );
+        assert_eq!(
+            PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&arith_refs, &mut rng, &pk_refs[1..]).err(),
+            Some(pcs::Error::InvalidBatch("one pk per circuit"))
+        );
+        assert_eq!(
+            PlonkSnark::<KZG10<Bls12_381>>::batch_prove(&arith_refs[..1], &mut rng, &pk_refs[3..]).err(),
+            Some(pcs::Error::InvalidBatch("pk of another domain size"))
+        );
+
+        //prover and verifier derive the same challenges
+        let (proof, _, challenge) = PlonkSnark::<KZG10<Bls12_381>>::batch_prove_iop::<_, StdRng>(&arith_refs, &pk_refs, OpeningMode::PerPoint, None).unwrap();
+        let verifier_challenge = PlonkSnark::<KZG10<Bls12_381>>::compute_batch_challenges(&vk_refs, &proof, &pi_refs);
+        assert_eq!(challenge.alpha, verifier_challenge.alpha);
+        assert_eq!(challenge.zeta, verifier_challenge.zeta);
+        assert_eq!(challenge.v, verifier_challenge.v);
+        assert!(PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &proof, &pi_refs));
+
+        //tampered public input
+        let bad_pi = [Fr::one()];
+        let mut bad_pi_refs = pi_refs.clone();
+        bad_pi_refs[2] = &bad_pi;
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &proof, &bad_pi_refs));
+
+        //circuits swapped
+        let mut swapped_vk_refs = vk_refs.clone();
+        swapped_vk_refs.swap(0, 3);
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&swapped_vk_refs, &proof, &pi_refs));
+
+        //tampered commitment and evaluations
+        let mut bad_proof = proof.clone();
+        bad_proof.prod_perm_poly_commit_vec.swap(1, 2);
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &bad_proof, &pi_refs));
+
+        let mut bad_proof = proof.clone();
+        bad_proof.poly_evals_vec[3].wire_evals[0] += Fr::one();
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &bad_proof, &pi_refs));
+
+        let mut bad_proof = proof.clone();
+        if let Openings::PerPoint {
+            shifted_opening_proofs,
+            ..
+        } = &mut bad_proof.openings
+        {
+            shifted_opening_proofs.swap(0, 1);
+        }
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs, &bad_proof, &pi_refs));
+
+        //missing circuit
+        assert!(!PlonkSnark::<KZG10<Bls12_381>>::batch_verify(&vk_refs[..3], &proof, &pi_refs[..3]));
+    }
+
+    //Circuits preprocessed under one srs sized for the largest, with their public inputs
+    struct Preprocessed<'a, PC: PolynomialCommitment> {
+        ariths: Vec<PlonkArithmetization<'a, PC::Field>>,
+        pks: Vec<Pk<PC>>,
+        vks: Vec<Vk<PC>>,
+        pis: Vec<Vec<PC::Field>>,
+    }
+
+    fn preprocess_circuits<'a, PC: PolynomialCommitment>(
+        circuits: &'a [PlonkCircuit<PC::Field>],
+        rng: &mut StdRng,
+    ) -> Preprocessed<'a, PC> {
+        let ariths: Vec<_> = circuits.iter().map(PlonkArithmetization::new).collect();
+        let max_domain_size = ariths.iter().map(|a| a.domain_size()).max().unwrap();
+        let srs = PlonkSnark::<PC>::setup(max_domain_size + 2, rng);
+        let (pks, vks) = ariths
+            .iter()
+            .map(|arith| PlonkSnark::<PC>::preprocess(&srs, arith))
+            .unzip();
+        let pis = circuits.iter().map(|c| c.pi_value()).collect();
+        Preprocessed { ariths, pks, vks, pis }
+    }
+
+    //Single and batch proofs of chain circuits verify, tampered ones do not
+    fn check_prove_and_verify<PC: PolynomialCommitment>(seed: u64, mode: OpeningMode) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let circuits = [
+            build_chain_circuit::<PC::Field>(3, 2),
+            build_chain_circuit(6, 5),
+            build_chain_circuit(20, 11),
+        ];
+        let Preprocessed { ariths, pks, vks, pis } = preprocess_circuits::<PC>(&circuits, &mut rng);
+        for i in 0..circuits.len() {
+            let proof = PlonkSnark::<PC>::prove_with_mode(&ariths[i], &mut rng, &pks[i], mode, Hiding::Blinded);
+            assert_eq!(proof.openings.mode(), mode);
+            assert!(PlonkSnark::<PC>::verify(&vks[i], &proof, &pis[i]));
+            assert!(!PlonkSnark::<PC>::verify(&vks[i], &proof, &[PC::Field::one()]));
+
+            let mut bad_proof = proof.clone();
+            bad_proof.poly_evals.wire_evals[0] += PC::Field::one();
+            assert!(!PlonkSnark::<PC>::verify(&vks[i], &bad_proof, &pis[i]));
+            let mut bad_proof = proof;
+            bad_proof.quot_poly_commits.swap(0, 1);
+            assert!(!PlonkSnark::<PC>::verify(&vks[i], &bad_proof, &pis[i]));
+        }
+
+        let arith_refs: Vec<_> = ariths.iter().collect();
+        let pk_refs: Vec<_> = pks.iter().collect();
+        let vk_refs: Vec<_> = vks.iter().collect();
+        let pi_refs: Vec<&[PC::Field]> = pis.iter().map(|pi| pi.as_slice()).collect();
+        let proof = PlonkSnark::<PC>::batch_prove_with_mode(&arith_refs, &mut rng, &pk_refs, mode, Hiding::Blinded).unwrap();
+        assert!(PlonkSnark::<PC>::batch_verify(&vk_refs, &proof, &pi_refs));
+        let mut bad_proof = proof.clone();
+        bad_proof.poly_evals_vec[1].prod_perm_eval += PC::Field::one();
+        assert!(!PlonkSnark::<PC>::batch_verify(&vk_refs, &bad_proof, &pi_refs));
+        assert!(!PlonkSnark::<PC>::batch_verify(&vk_refs[..2], &proof, &pi_refs[..2]));
+    }
+
+    #[test]
+    fn test_ipa_prove_and_verify() {
+        //no trusted setup, the generators are hashed to the curve
+        check_prove_and_verify::<IPA<ark_pallas::Affine>>(7, OpeningMode::PerPoint);
+    }
+
+    #[test]
+    fn test_fri_prove_and_verify() {
+        //hash based, no pairings and no trusted setup
+        check_prove_and_verify::<FRI<Fr>>(8, OpeningMode::PerPoint);
+    }
+
+    //Proofs verify with the transcript they are made with and not with another one
+    fn check_transcript<PC: PolynomialCommitment, T: Transcript<PC::Field>>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let circuits = [build_chain_circuit::<PC::Field>(3, 2), build_chain_circuit(6, 5)];
+        let ariths: Vec<_> = circuits.iter().map(PlonkArithmetization::new).collect();
+        let pis: Vec<Vec<PC::Field>> = circuits.iter().map(|c| c.pi_value()).collect();
+        let srs = PlonkSnark::<PC, T>::setup(ariths[1].domain_size() + 2, &mut rng);
+        let (pks, vks): (Vec<_>, Vec<_>) = ariths
+            .iter()
+            .map(|arith| PlonkSnark::<PC, T>::preprocess(&srs, arith))
+            .unzip();
+        let proof = PlonkSnark::<PC, T>::prove(&ariths[0], &mut rng, &pks[0]);
+        assert!(PlonkSnark::<PC, T>::verify(&vks[0], &proof, &pis[0]));
+        assert!(!PlonkSnark::<PC, T>::verify(&vks[0], &proof, &[PC::Field::one()]));
+        assert!(!PlonkSnark::<PC>::verify(&vks[0], &proof, &pis[0]));
+
+        let proof = PlonkSnark::<PC, T>::batch_prove(&[&ariths[0], &ariths[1]], &mut rng, &[&pks[0], &pks[1]]).unwrap();
+        assert!(PlonkSnark::<PC, T>::batch_verify(&[&vks[0], &vks[1]], &proof, &[&pis[0], &pis[1]]));
+        assert!(!PlonkSnark::<PC>::batch_verify(&[&vks[0], &vks[1]], &proof, &[&pis[0], &pis[1]]));
+    }
+
+    #[test]
+    fn test_keccak_transcript() {
+        check_transcript::<KZG10<Bls12_381>, KeccakTranscript<Fr>>(11);
+        check_transcript::<IPA<ark_pallas::Affine>, KeccakTranscript<PallasFr>>(12);
+        check_transcript::<FRI<Fr>, KeccakTranscript<Fr>>(13);
+    }
+
+    #[test]
+    fn test_poseidon_transcript() {
+        check_transcript::<KZG10<Bls12_381>, PoseidonTranscript<Fr>>(14);
+        check_transcript::<IPA<ark_pallas::Affine>, PoseidonTranscript<PallasFr>>(15);
+        check_transcript::<FRI<Fr>, PoseidonTranscript<Fr>>(16);
+    }
+
+    #[test]
+    fn test_multi_point_opening() {
+        type Plonk = PlonkSnark<KZG10<Bls12_381>>;
+        //three shifted points and zeta in one opening for the batch
+        check_prove_and_verify::<KZG10<Bls12_381>>(9, OpeningMode::MultiPoint);
+
+        let mut rng = StdRng::seed_from_u64(10);
+        let circuits = [build_chain_circuit::<Fr>(3, 2), build_chain_circuit(6, 5)];
+        let Preprocessed { ariths, pks, vks, pis } = preprocess_circuits::<KZG10<Bls12_381>>(&circuits, &mut rng);
+        let mut bad_proof = Plonk::prove_with_mode(&ariths[0], &mut rng, &pks[0], OpeningMode::MultiPoint, Hiding::Blinded);
+        if let Openings::MultiPoint(multi_proof) = &mut bad_proof.openings {
+            std::mem::swap(&mut multi_proof.quot_commit, &mut multi_proof.opening_proof);
+        }
+        assert!(!Plonk::verify(&vks[0], &bad_proof, &pis[0]));
+
+        //prover and verifier derive the same challenges, u is squeezed before the opening
+        let (proof, _, challenge) = Plonk::prove_iop::<_, StdRng>(
+            &ariths[1],
+            &pks[1],
+            OpeningMode::MultiPoint,
+            None,
+        );
+        let verifier_challenge = Plonk::compute_challenges(&vks[1], &proof, &pis[1]);
+        assert_eq!(challenge.v, verifier_challenge.v);
+        assert_eq!(challenge.u, verifier_challenge.u);
     }
 }
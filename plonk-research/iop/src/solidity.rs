@@ -0,0 +1,671 @@
+use crate::prover::quot_chunk_size;
+use crate::serialize::Versioned;
+use crate::snark::{Openings, Proof, Vk, TRANSCRIPT_LABEL};
+use crate::transcript::KeccakTranscript;
+use crate::utils::selector_terms;
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, Field, FpParameters, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use constraint::circuit::WIRE_COUNT;
+use pcs::kzg10::KZG10;
+use pcs::Error;
+use sha3::{Digest, Keccak256};
+
+//Entry point of the verifier contract, the proof encoded by encode_proof and the public inputs
+pub const VERIFY_SIGNATURE: &str = "verifyProof(bytes,uint256[])";
+
+//Byte offsets in the encoded proof, all values are 32 byte big endian words and commitments are
+//x followed by y: wire commitments, z(X), quotient chunks, opening proofs at zeta and zeta * omega,
+//then wire evaluations, identify permutation evaluations and z(zeta * omega)
+const WIRES: usize = 0;
+const PROD: usize = WIRES + 0x40 * WIRE_COUNT;
+const QUOT: usize = PROD + 0x40;
+const OPENING: usize = QUOT + 0x40 * WIRE_COUNT;
+const SHIFTED_OPENING: usize = OPENING + 0x40;
+const EVALS: usize = SHIFTED_OPENING + 0x40;
+const EVAL_COUNT: usize = 2 * WIRE_COUNT;
+pub const PROOF_LEN: usize = EVALS + 0x20 * EVAL_COUNT;
+
+//Memory of the contract above the precompile scratch space 0x00..0xc0, the transcript buffer is
+//last since it grows with the public inputs
+const MEMORY: [(&str, usize); 17] = [
+    ("STATE", 0x100),
+    ("ACC", 0x120),
+    ("LEFT", 0x160),
+    ("PROOF", 0x1a0),
+    ("BETA", 0x1c0),
+    ("GAMMA", 0x1e0),
+    ("ALPHA", 0x200),
+    ("ZETA", 0x220),
+    ("V", 0x240),
+    ("U", 0x260),
+    ("VANISH", 0x280),
+    ("L1", 0x2a0),
+    ("PI_EVAL", 0x2c0),
+    ("PROD_ORIG", 0x2e0),
+    ("PROD_PERM", 0x300),
+    ("PAIRING", 0x320),
+    ("TRANSCRIPT", 0x4a0),
+];
+
+//Every %NAME% is replaced by a constant of the verifying key, a proof offset or a memory slot
+const CONTRACT: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+// PLONK verifier of a single circuit over BN254 with KZG10 openings, generated from its verifying key.
+// Proofs are made with the keccak256 transcript and one opening proof for each point.
+contract PlonkVerifier {
+    function verifyProof(bytes calldata, uint256[] calldata) external view returns (bool) {
+        assembly {
+            function fail() {
+                mstore(0x00, 0)
+                return(0x00, 0x20)
+            }
+
+            // b^e mod r with the modexp precompile
+            function expmod(b, e) -> result {
+                mstore(0x00, 0x20)
+                mstore(0x20, 0x20)
+                mstore(0x40, 0x20)
+                mstore(0x60, b)
+                mstore(0x80, e)
+                mstore(0xa0, %R%)
+                if iszero(staticcall(gas(), 0x05, 0x00, 0xc0, 0x00, 0x20)) {
+                    fail()
+                }
+                result := mload(0x00)
+            }
+
+            function inverse(x) -> result {
+                result := expmod(x, %R_MINUS_2%)
+            }
+
+            function neg(x) -> result {
+                result := addmod(0, sub(%R%, x), %R%)
+            }
+
+            // on y^2 = x^3 + 3 or the point at infinity (0, 0), G1 has cofactor one
+            function checkPoint(x, y) {
+                if iszero(and(lt(x, %Q%), lt(y, %Q%))) {
+                    fail()
+                }
+                let lhs := mulmod(y, y, %Q%)
+                let rhs := addmod(mulmod(mulmod(x, x, %Q%), x, %Q%), 3, %Q%)
+                if iszero(or(iszero(or(x, y)), eq(lhs, rhs))) {
+                    fail()
+                }
+            }
+
+            function checkScalar(s) {
+                if iszero(lt(s, %R%)) {
+                    fail()
+                }
+            }
+
+            function proofWord(offset) -> word {
+                word := calldataload(add(mload(%PROOF%), offset))
+            }
+
+            function proofEval(i) -> eval {
+                eval := proofWord(add(%EVALS%, mul(i, 0x20)))
+            }
+
+            // (x, y) at acc += s * (x, y)
+            function mulAcc(acc, x, y, s) {
+                mstore(0x00, x)
+                mstore(0x20, y)
+                mstore(0x40, s)
+                if iszero(staticcall(gas(), 0x07, 0x00, 0x60, 0x00, 0x40)) {
+                    fail()
+                }
+                mstore(0x40, mload(acc))
+                mstore(0x60, mload(add(acc, 0x20)))
+                if iszero(staticcall(gas(), 0x06, 0x00, 0x80, acc, 0x40)) {
+                    fail()
+                }
+            }
+
+            function mulAccProof(acc, offset, s) {
+                mulAcc(acc, proofWord(offset), proofWord(add(offset, 0x20)), s)
+            }
+
+            // challenge from keccak256 of the state and len bytes of calldata at src,
+            // the hash is the next state
+            function squeeze(src, len) -> challenge {
+                mstore(%TRANSCRIPT%, mload(%STATE%))
+                calldatacopy(add(%TRANSCRIPT%, 0x20), src, len)
+                let h := keccak256(%TRANSCRIPT%, add(0x20, len))
+                mstore(%STATE%, h)
+                challenge := mod(h, %R%)
+            }
+
+            // proof and public inputs of the expected lengths
+            let proofPtr := add(0x04, calldataload(0x04))
+            let piPtr := add(0x04, calldataload(0x24))
+            if iszero(and(eq(calldataload(proofPtr), %PROOF_LEN%), eq(calldataload(piPtr), %PI_COUNT%))) {
+                fail()
+            }
+            proofPtr := add(proofPtr, 0x20)
+            piPtr := add(piPtr, 0x20)
+            if or(gt(add(proofPtr, %PROOF_LEN%), calldatasize()), gt(add(piPtr, %PI_BYTES%), calldatasize())) {
+                fail()
+            }
+            mstore(%PROOF%, proofPtr)
+
+            // commitments on the curve, evaluations and public inputs reduced
+            for { let i := 0 } lt(i, %POINT_COUNT%) { i := add(i, 1) } {
+                checkPoint(proofWord(mul(i, 0x40)), proofWord(add(mul(i, 0x40), 0x20)))
+            }
+            for { let i := 0 } lt(i, %EVAL_COUNT%) { i := add(i, 1) } {
+                checkScalar(proofEval(i))
+            }
+            for { let i := 0 } lt(i, %PI_COUNT%) { i := add(i, 1) } {
+                checkScalar(calldataload(add(piPtr, mul(i, 0x20))))
+            }
+
+            // challenges, the transcript starts with the label hash, the verifying key digest
+            // and the public inputs
+            {
+                mstore(%TRANSCRIPT%, %LABEL_HASH%)
+                mstore(add(%TRANSCRIPT%, 0x20), %VK_DIGEST%)
+                calldatacopy(add(%TRANSCRIPT%, 0x40), piPtr, %PI_BYTES%)
+                calldatacopy(add(%TRANSCRIPT%, add(0x40, %PI_BYTES%)), add(proofPtr, %WIRES%), %PROD%)
+                let h := keccak256(%TRANSCRIPT%, add(add(0x40, %PI_BYTES%), %PROD%))
+                mstore(%STATE%, h)
+                mstore(%BETA%, mod(h, %R%))
+                mstore(%GAMMA%, squeeze(0, 0))
+                mstore(%ALPHA%, squeeze(add(proofPtr, %PROD%), 0x40))
+                mstore(%ZETA%, squeeze(add(proofPtr, %QUOT%), %QUOT_BYTES%))
+                mstore(%V%, squeeze(add(proofPtr, %EVALS%), %EVAL_BYTES%))
+                mstore(%U%, squeeze(add(proofPtr, %OPENING%), 0x80))
+            }
+
+            // Z_H(zeta) = zeta^n - 1 is not zero, L_1(zeta) = Z_H(zeta) / (n * (zeta - 1)),
+            // PI(zeta) = sum pi_i * L_(g_i)(zeta) with L_j(zeta) = w^j * Z_H(zeta) / (n * (zeta - w^j))
+            {
+                let zeta := mload(%ZETA%)
+                let vanish := addmod(expmod(zeta, %N%), sub(%R%, 1), %R%)
+                if iszero(vanish) {
+                    fail()
+                }
+                mstore(%VANISH%, vanish)
+                mstore(%L1%, mulmod(vanish, inverse(mulmod(%N%, addmod(zeta, sub(%R%, 1), %R%), %R%)), %R%))
+                let piEval := 0
+%PI_TERMS%                mstore(%PI_EVAL%, mulmod(piEval, vanish, %R%))
+            }
+
+            // prod (a_i + beta * k_i * zeta + gamma) and prod (a_i + beta * s_i(zeta) + gamma)
+            {
+                let beta := mload(%BETA%)
+                let gamma := mload(%GAMMA%)
+                let zeta := mload(%ZETA%)
+                let prodOrig := 1
+%PROD_ORIG_TERMS%                let prodPerm := 1
+%PROD_PERM_TERMS%                mstore(%PROD_ORIG%, prodOrig)
+                mstore(%PROD_PERM%, prodPerm)
+            }
+
+            // linearization commitment F, selector terms
+            mstore(%ACC%, 0)
+            mstore(add(%ACC%, 0x20), 0)
+%SELECTOR_TERMS%
+            // permutation terms, z(X) is also opened at zeta * w with challenge u
+            {
+                let alpha := mload(%ALPHA%)
+                let zw := proofEval(%ZW%)
+                let s := addmod(mulmod(alpha, mload(%PROD_ORIG%), %R%), mulmod(mulmod(alpha, alpha, %R%), mload(%L1%), %R%), %R%)
+                mulAccProof(%ACC%, %PROD%, addmod(s, mload(%U%), %R%))
+                s := mulmod(mulmod(mulmod(alpha, mload(%BETA%), %R%), zw, %R%), mload(%PROD_PERM%), %R%)
+                mulAcc(%ACC%, %LAST_PERM_X%, %LAST_PERM_Y%, neg(s))
+            }
+
+            // quotient terms -Z_H(zeta) * zeta^(i * chunk size)
+            {
+                let zetaChunk := expmod(mload(%ZETA%), %CHUNK_SIZE%)
+                let coeff := neg(mload(%VANISH%))
+                for { let i := 0 } lt(i, %WIRE_COUNT%) { i := add(i, 1) } {
+                    mulAccProof(%ACC%, add(%QUOT%, mul(i, 0x40)), coeff)
+                    coeff := mulmod(coeff, zetaChunk, %R%)
+                }
+            }
+
+            // wires and identify permutations opened at zeta with challenge v
+            {
+                let v := mload(%V%)
+                let vPow := 1
+                for { let i := 0 } lt(i, %WIRE_COUNT%) { i := add(i, 1) } {
+                    vPow := mulmod(vPow, v, %R%)
+                    mulAccProof(%ACC%, add(%WIRES%, mul(i, 0x40)), vPow)
+                }
+%PERM_TERMS%            }
+
+            // r0 = PI(zeta) - alpha^2 * L_1(zeta) - alpha * z(zeta * w) * (a_last + gamma) * prodPerm,
+            // F opens to E = -r0 + sum v^j * e_j at zeta, -(E + u * z(zeta * w)) * g is added to F
+            {
+                let alpha := mload(%ALPHA%)
+                let zw := proofEval(%ZW%)
+                let r0 := addmod(mload(%PI_EVAL%), neg(mulmod(mulmod(alpha, alpha, %R%), mload(%L1%), %R%)), %R%)
+                let perm := mulmod(mulmod(alpha, zw, %R%), addmod(proofEval(%LAST_WIRE%), mload(%GAMMA%), %R%), %R%)
+                r0 := addmod(r0, neg(mulmod(perm, mload(%PROD_PERM%), %R%)), %R%)
+                let v := mload(%V%)
+                let vPow := 1
+                let e := neg(r0)
+                for { let i := 0 } lt(i, %OPENED_COUNT%) { i := add(i, 1) } {
+                    vPow := mulmod(vPow, v, %R%)
+                    e := addmod(e, mulmod(vPow, proofEval(i), %R%), %R%)
+                }
+                e := addmod(e, mulmod(mload(%U%), zw, %R%), %R%)
+                mulAcc(%ACC%, %G_X%, %G_Y%, neg(e))
+            }
+
+            // right = F + zeta * W + u * zeta * w * W' and left = W + u * W'
+            {
+                let zeta := mload(%ZETA%)
+                let u := mload(%U%)
+                mulAccProof(%ACC%, %OPENING%, zeta)
+                mulAccProof(%ACC%, %SHIFTED_OPENING%, mulmod(mulmod(u, zeta, %R%), %OMEGA%, %R%))
+                mstore(%LEFT%, 0)
+                mstore(add(%LEFT%, 0x20), 0)
+                mulAccProof(%LEFT%, %OPENING%, 1)
+                mulAccProof(%LEFT%, %SHIFTED_OPENING%, u)
+            }
+
+            // e(left, [beta]_2) * e(-right, [1]_2) = 1
+            mstore(%PAIRING%, mload(%LEFT%))
+            mstore(add(%PAIRING%, 0x20), mload(add(%LEFT%, 0x20)))
+            mstore(add(%PAIRING%, 0x40), %BETA_H_X1%)
+            mstore(add(%PAIRING%, 0x60), %BETA_H_X0%)
+            mstore(add(%PAIRING%, 0x80), %BETA_H_Y1%)
+            mstore(add(%PAIRING%, 0xa0), %BETA_H_Y0%)
+            mstore(add(%PAIRING%, 0xc0), mload(%ACC%))
+            mstore(add(%PAIRING%, 0xe0), mod(sub(%Q%, mload(add(%ACC%, 0x20))), %Q%))
+            mstore(add(%PAIRING%, 0x100), %H_X1%)
+            mstore(add(%PAIRING%, 0x120), %H_X0%)
+            mstore(add(%PAIRING%, 0x140), %H_Y1%)
+            mstore(add(%PAIRING%, 0x160), %H_Y0%)
+            let ok := staticcall(gas(), 0x08, %PAIRING%, 0x180, 0x00, 0x20)
+            mstore(0x00, and(ok, mload(0x00)))
+            return(0x00, 0x20)
+        }
+    }
+}
+"#;
+
+fn hex_word(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+fn word<F: PrimeField>(f: &F) -> String {
+    hex_word(&f.into_repr().to_bytes_be())
+}
+
+fn modulus<F: PrimeField>() -> String {
+    hex_word(&F::Params::MODULUS.to_bytes_be())
+}
+
+//x and y of a G1 point, (0, 0) for the point at infinity as the precompiles expect
+fn g1_words(point: &G1Affine) -> [String; 2] {
+    if point.is_zero() {
+        ["0".to_string(), "0".to_string()]
+    } else {
+        [word(&point.x), word(&point.y)]
+    }
+}
+
+//G2 coordinates in the pairing precompile order, the imaginary part first
+fn g2_words(point: &G2Affine) -> [String; 4] {
+    [word(&point.x.c1), word(&point.x.c0), word(&point.y.c1), word(&point.y.c0)]
+}
+
+//Solidity verifier of the circuit of vk, accepting per point proofs of KeccakPlonk encoded by encode_calldata
+pub fn verifier_contract(vk: &Vk<KZG10<Bn254>>) -> String {
+    let domain = Radix2EvaluationDomain::<Fr>::new(vk.domain_size).unwrap();
+    let indent = " ".repeat(16);
+
+    //generated code may use placeholders, they are replaced together with the template's
+    let pi_terms: String = vk
+        .pi_gates
+        .iter()
+        .enumerate()
+        .map(|(i, &gate_id)| {
+            let omega_j = word(&domain.group_gen.pow([gate_id as u64]));
+            format!(
+                "{indent}piEval := addmod(piEval, mulmod(mulmod(calldataload(add(piPtr, {:#x})), {omega_j}, %R%), \
+                 inverse(mulmod(%N%, addmod(zeta, sub(%R%, {omega_j}), %R%), %R%)), %R%), %R%)\n",
+                0x20 * i
+            )
+        })
+        .collect();
+    let prod_orig_terms: String = vk
+        .k_reprs
+        .iter()
+        .enumerate()
+        .map(|(i, k)| {
+            format!(
+                "{indent}prodOrig := mulmod(prodOrig, addmod(addmod(proofEval({i}), mulmod(beta, mulmod({}, zeta, %R%), %R%), %R%), \
+                 gamma, %R%), %R%)\n",
+                word(k)
+            )
+        })
+        .collect();
+    let prod_perm_terms: String = (0..WIRE_COUNT - 1)
+        .map(|i| {
+            format!(
+                "{indent}prodPerm := mulmod(prodPerm, addmod(addmod(proofEval({i}), mulmod(beta, proofEval({}), %R%), %R%), \
+                 gamma, %R%), %R%)\n",
+                WIRE_COUNT + i
+            )
+        })
+        .collect();
+    let wire_evals: Vec<String> = (0..WIRE_COUNT).map(|i| format!("proofEval({})", i)).collect();
+    let scalars = selector_terms(
+        &wire_evals,
+        |a, b| format!("mulmod({}, {}, %R%)", a, b),
+        |a| format!("neg({})", a),
+        "1".to_string(),
+    );
+    let selector_terms: String = vk
+        .selector_commits
+        .iter()
+        .zip(scalars.iter())
+        .map(|(commit, s)| {
+            let [x, y] = g1_words(&commit.0);
+            format!("            mulAcc(%ACC%, {x}, {y}, {s})\n")
+        })
+        .collect();
+    let perm_terms: String = vk.identify_perm_commits[..WIRE_COUNT - 1]
+        .iter()
+        .map(|commit| {
+            let [x, y] = g1_words(&commit.0);
+            format!("{indent}vPow := mulmod(vPow, v, %R%)\n{indent}mulAcc(%ACC%, {x}, {y}, vPow)\n")
+        })
+        .collect();
+
+    let [last_perm_x, last_perm_y] = g1_words(&vk.identify_perm_commits[WIRE_COUNT - 1].0);
+    let [g_x, g_y] = g1_words(&vk.vk.g);
+    let [h_x1, h_x0, h_y1, h_y0] = g2_words(&vk.vk.h);
+    let [beta_h_x1, beta_h_x0, beta_h_y1, beta_h_y0] = g2_words(&vk.vk.beta_h);
+    let mut vars: Vec<(&str, String)> = vec![
+        ("PI_TERMS", pi_terms),
+        ("PROD_ORIG_TERMS", prod_orig_terms),
+        ("PROD_PERM_TERMS", prod_perm_terms),
+        ("SELECTOR_TERMS", selector_terms),
+        ("PERM_TERMS", perm_terms),
+        ("R", modulus::<Fr>()),
+        ("R_MINUS_2", word(&-Fr::from(2u64))),
+        ("Q", modulus::<Fq>()),
+        ("N", format!("{:#x}", vk.domain_size)),
+        ("OMEGA", word(&domain.group_gen)),
+        ("CHUNK_SIZE", format!("{:#x}", quot_chunk_size(vk.domain_size))),
+        ("PI_COUNT", format!("{:#x}", vk.pi_count)),
+        ("PI_BYTES", format!("{:#x}", 0x20 * vk.pi_count)),
+        ("LABEL_HASH", hex_word(&Keccak256::digest(TRANSCRIPT_LABEL.as_bytes()))),
+        ("VK_DIGEST", hex_word(&KeccakTranscript::<Fr>::vk_digest(vk))),
+        ("LAST_PERM_X", last_perm_x),
+        ("LAST_PERM_Y", last_perm_y),
+        ("G_X", g_x),
+        ("G_Y", g_y),
+        ("H_X1", h_x1),
+        ("H_X0", h_x0),
+        ("H_Y1", h_y1),
+        ("H_Y0", h_y0),
+        ("BETA_H_X1", beta_h_x1),
+        ("BETA_H_X0", beta_h_x0),
+        ("BETA_H_Y1", beta_h_y1),
+        ("BETA_H_Y0", beta_h_y0),
+        ("WIRE_COUNT", WIRE_COUNT.to_string()),
+        ("LAST_WIRE", (WIRE_COUNT - 1).to_string()),
+        ("OPENED_COUNT", (2 * WIRE_COUNT - 1).to_string()),
+        ("ZW", (EVAL_COUNT - 1).to_string()),
+        ("EVAL_COUNT", EVAL_COUNT.to_string()),
+        ("POINT_COUNT", (SHIFTED_OPENING / 0x40 + 1).to_string()),
+        ("PROOF_LEN", format!("{:#x}", PROOF_LEN)),
+        ("WIRES", format!("{:#x}", WIRES)),
+        ("PROD", format!("{:#x}", PROD)),
+        ("QUOT", format!("{:#x}", QUOT)),
+        ("QUOT_BYTES", format!("{:#x}", OPENING - QUOT)),
+        ("OPENING", format!("{:#x}", OPENING)),
+        ("SHIFTED_OPENING", format!("{:#x}", SHIFTED_OPENING)),
+        ("EVALS", format!("{:#x}", EVALS)),
+        ("EVAL_BYTES", format!("{:#x}", PROOF_LEN - EVALS)),
+    ];
+    vars.extend(MEMORY.iter().map(|&(name, addr)| (name, format!("{:#x}", addr))));
+    vars.iter().fold(CONTRACT.to_string(), |contract, (name, value)| {
+        contract.replace(&format!("%{}%", name), value)
+    })
+}
+
+//Contract of a versioned encoding of the verifying key
+pub fn verifier_contract_from_bytes(bytes: &[u8]) -> Result<String, Error> {
+    Ok(verifier_contract(&Vk::<KZG10<Bn254>>::from_bytes(bytes)?))
+}
+
+fn g1_bytes(point: &G1Affine) -> Vec<u8> {
+    if point.is_zero() {
+        vec![0; 0x40]
+    } else {
+        [point.x.into_repr().to_bytes_be(), point.y.into_repr().to_bytes_be()].concat()
+    }
+}
+
+//Proof in the layout the contract reads, only per point openings of a single circuit
+pub fn encode_proof(proof: &Proof<KZG10<Bn254>>) -> Result<Vec<u8>, Error> {
+    let (opening_proof, shifted_opening_proof) = match &proof.openings {
+        Openings::PerPoint {
+            opening_proof,
+            shifted_opening_proofs,
+        } if shifted_opening_proofs.len() == 1 => (opening_proof, &shifted_opening_proofs[0]),
+        _ => return Err(Error::MalformedEncoding("opening mode")),
+    };
+    let evals = &proof.poly_evals;
+    if proof.wire_poly_commits.len() != WIRE_COUNT
+        || proof.quot_poly_commits.len() != WIRE_COUNT
+        || evals.wire_evals.len() != WIRE_COUNT
+        || evals.identify_perm_evals.len() != WIRE_COUNT - 1
+    {
+        return Err(Error::MalformedEncoding("proof shape"));
+    }
+    let mut bytes = vec![];
+    for commit in proof
+        .wire_poly_commits
+        .iter()
+        .chain([&proof.prod_perm_poly_commit])
+        .chain(proof.quot_poly_commits.iter())
+        .chain([opening_proof, shifted_opening_proof])
+    {
+        bytes.extend(g1_bytes(&commit.0));
+    }
+    for eval in evals
+        .wire_evals
+        .iter()
+        .chain(evals.identify_perm_evals.iter())
+        .chain([&evals.prod_perm_eval])
+    {
+        bytes.extend(eval.into_repr().to_bytes_be());
+    }
+    debug_assert_eq!(bytes.len(), PROOF_LEN);
+    Ok(bytes)
+}
+
+//ABI encoded call of verifyProof(proof, pi)
+pub fn encode_calldata(proof: &Proof<KZG10<Bn254>>, pi: &[Fr]) -> Result<Vec<u8>, Error> {
+    let proof_bytes = encode_proof(proof)?;
+    let abi_word = |value: usize| {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+        word
+    };
+    let mut calldata = Keccak256::digest(VERIFY_SIGNATURE.as_bytes())[..4].to_vec();
+    calldata.extend(abi_word(0x40));
+    calldata.extend(abi_word(0x60 + PROOF_LEN));
+    calldata.extend(abi_word(PROOF_LEN));
+    calldata.extend(proof_bytes);
+    calldata.extend(abi_word(pi.len()));
+    for pi in pi.iter() {
+        calldata.extend(pi.into_repr().to_bytes_be());
+    }
+    Ok(calldata)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{encode_calldata, verifier_contract, verifier_contract_from_bytes, OPENING, PROOF_LEN, VERIFY_SIGNATURE};
+    use crate::curves::bn254::{KeccakPlonk, Plonk};
+    use crate::serialize::{Compression, Versioned};
+    use crate::snark::{Hiding, OpeningMode, Snark};
+    use crate::transcript::KeccakTranscript;
+    use crate::yul::{compile, deploy_and_call};
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::One;
+    use constraint::arithmetization::{Arithmetization, PlonkArithmetization};
+    use constraint::circuit::{Circuit, PlonkCircuit};
+    use pcs::Error;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sha3::{Digest, Keccak256};
+    use solang_parser::pt::{ContractPart, SourceUnitPart};
+
+    //(a * b + c) * b with public a and c
+    fn build_circuit() -> PlonkCircuit<Fr> {
+        let mut circuit: PlonkCircuit<Fr> = PlonkCircuit::new();
+        let a = circuit.create_var(Fr::from(5u32), true);
+        let b = circuit.create_var(Fr::from(3u32), false);
+        let c = circuit.create_var(Fr::from(7u32), true);
+        let d = circuit.mul(a, b);
+        let e = circuit.add(d, c);
+        circuit.mul(e, b);
+        circuit
+    }
+
+    //Signature and attributes of the external function of the contract as the parser reads them
+    fn abi_function(contract: &str) -> (String, Vec<String>) {
+        let (unit, _) = solang_parser::parse(contract, 0).expect("contract does not parse");
+        let functions: Vec<_> = unit
+            .0
+            .iter()
+            .filter_map(|part| match part {
+                SourceUnitPart::ContractDefinition(contract) => Some(contract),
+                _ => None,
+            })
+            .flat_map(|contract| contract.parts.iter())
+            .filter_map(|part| match part {
+                ContractPart::FunctionDefinition(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(functions.len(), 1);
+        let f = functions[0];
+        let types: Vec<String> = f
+            .params
+            .iter()
+            .map(|(_, param)| param.as_ref().unwrap().ty.to_string())
+            .collect();
+        let signature = format!("{}({})", f.name.as_ref().unwrap().name, types.join(","));
+        let mut attributes: Vec<String> = f.attributes.iter().map(|a| a.to_string()).collect();
+        attributes.extend(f.returns.iter().map(|(_, ret)| format!("returns {}", ret.as_ref().unwrap().ty)));
+        (signature, attributes)
+    }
+
+    //ABI head of verifyProof: selector, offsets of proof and inputs, proof length, input count
+    fn check_calldata(calldata: &[u8], pi_count: usize) {
+        let abi_word = |i: usize| {
+            let word = &calldata[4 + 0x20 * i..4 + 0x20 * (i + 1)];
+            assert!(word[..24].iter().all(|&b| b == 0));
+            u64::from_be_bytes(word[24..].try_into().unwrap()) as usize
+        };
+        assert_eq!(calldata[..4], Keccak256::digest(VERIFY_SIGNATURE.as_bytes())[..4]);
+        assert_eq!(calldata.len(), 4 + 0x60 + PROOF_LEN + 0x20 * (1 + pi_count));
+        assert_eq!(abi_word(0), 0x40);
+        assert_eq!(abi_word(1), 0x60 + PROOF_LEN);
+        assert_eq!(abi_word(2), PROOF_LEN);
+        assert_eq!(abi_word(3 + PROOF_LEN / 0x20), pi_count);
+    }
+
+    fn abi_bool(value: bool) -> Option<Vec<u8>> {
+        let mut word = vec![0u8; 32];
+        word[31] = value as u8;
+        Some(word)
+    }
+
+    //No solc is available offline, so the contract is compiled by the Yul compiler of crate::yul,
+    //deployed in revm and called with the calldata of honest and tampered proofs
+    #[test]
+    fn test_verifier_contract() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circuit = build_circuit();
+        let pi = circuit.pi_value();
+        assert_eq!(pi.len(), 2);
+        let arith = PlonkArithmetization::new(&circuit);
+        let srs = KeccakPlonk::setup(arith.domain_size() + 2, &mut rng);
+        let (pk, vk) = KeccakPlonk::preprocess(&srs, &arith);
+        let contract = verifier_contract(&vk);
+        assert_eq!(
+            verifier_contract_from_bytes(&vk.to_bytes(Compression::Compressed)).unwrap(),
+            contract
+        );
+        assert!(!contract.contains('%'));
+        //the inlined digest covers the public input rows of PI_TERMS
+        let mut moved_vk = vk.clone();
+        moved_vk.pi_gates[0] += 1;
+        assert_ne!(KeccakTranscript::<Fr>::vk_digest(&moved_vk), KeccakTranscript::<Fr>::vk_digest(&vk));
+
+        let (signature, attributes) = abi_function(&contract);
+        assert_eq!(signature, VERIFY_SIGNATURE);
+        assert_eq!(attributes, ["external", "view", "returns bool"]);
+
+        let proof = KeccakPlonk::prove(&arith, &mut rng, &pk);
+        assert!(KeccakPlonk::verify(&vk, &proof, &pi));
+        check_calldata(&encode_calldata(&proof, &pi).unwrap(), pi.len());
+
+        //wrong public input
+        let wrong_pi = [pi[0], pi[1] + Fr::one()];
+        assert!(!KeccakPlonk::verify(&vk, &proof, &wrong_pi));
+
+        //wrong evaluation, and a commitment moved to another point of the curve
+        let mut wrong_eval = proof.clone();
+        wrong_eval.poly_evals.wire_evals[0] += Fr::one();
+        assert!(!KeccakPlonk::verify(&vk, &wrong_eval, &pi));
+        let mut wrong_commit = proof.clone();
+        wrong_commit.wire_poly_commits[1].0 = (wrong_commit.wire_poly_commits[1].0 + G1Affine::prime_subgroup_generator())
+            .into_projective()
+            .into_affine();
+        assert!(!KeccakPlonk::verify(&vk, &wrong_commit, &pi));
+
+        //a proof with the default transcript is not accepted
+        let merlin_proof = Plonk::prove(&arith, &mut rng, &pk);
+        assert!(Plonk::verify(&vk, &merlin_proof, &pi));
+        assert!(!KeccakPlonk::verify(&vk, &merlin_proof, &pi));
+
+        let calldata = encode_calldata(&proof, &pi).unwrap();
+        //the opening proof at zeta moved off the curve
+        let mut off_curve = calldata.clone();
+        off_curve[4 + 0x60 + OPENING + 0x3f] ^= 1;
+        let outputs = deploy_and_call(
+            &compile(&contract, Keccak256::digest(VERIFY_SIGNATURE.as_bytes())[..4].try_into().unwrap()),
+            &[
+                calldata.clone(),
+                encode_calldata(&proof, &wrong_pi).unwrap(),
+                encode_calldata(&wrong_eval, &pi).unwrap(),
+                encode_calldata(&wrong_commit, &pi).unwrap(),
+                encode_calldata(&merlin_proof, &pi).unwrap(),
+                off_curve,
+                //a public input short
+                calldata[..calldata.len() - 0x20].to_vec(),
+                [[0u8; 4].to_vec(), calldata[4..].to_vec()].concat(),
+            ],
+        );
+        assert_eq!(outputs[0], abi_bool(true));
+        for output in outputs[1..7].iter() {
+            assert_eq!(*output, abi_bool(false));
+        }
+        //unknown selector
+        assert_eq!(outputs[7], None);
+
+        let multi_point_proof = KeccakPlonk::prove_with_mode(&arith, &mut rng, &pk, OpeningMode::MultiPoint, Hiding::Blinded);
+        assert_eq!(
+            encode_calldata(&multi_point_proof, &pi).unwrap_err(),
+            Error::MalformedEncoding("opening mode")
+        );
+    }
+}
@@ -1,98 +1,620 @@
+use crate::poseidon::PoseidonParams;
 use crate::prover::ProofEvaluations;
 use crate::snark::Vk;
 use crate::to_bytes;
-use ark_ec::{short_weierstrass_jacobian::GroupAffine, PairingEngine, SWModelParameters};
-use ark_ff::PrimeField;
-use ark_poly_commit::kzg10::Commitment;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use constraint::gate::{Q_MUL, Q_O};
 use merlin::Transcript as Script;
+use pcs::{Absorb, Element, PolynomialCommitment};
+use sha3::{Digest, Keccak256};
+use std::marker::PhantomData;
 
-pub trait Transcript<F> {
+//Label of selector commitment, following the order of Q_LC, Q_MUL, Q_O, Q_C
+fn selector_label(id: usize) -> &'static str {
+    match id {
+        id if id < Q_MUL => "linear combination selector commitment",
+        id if id < Q_O => "multiplication selector commitment",
+        Q_O => "output selector commitment",
+        _ => "constant selector commitment",
+    }
+}
+
+//Round/label discipline of a transcript.
+//A round is a run of absorbed messages followed by one or more challenges,
+//every label is recorded together with the round it belongs to.
+//Challenges are bound to the round number, so a prover and a verifier that absorb
+//different messages, or squeeze in different rounds, end up with different logs.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptLog {
+    round: usize,
+    absorbing: bool,
+    labels: Vec<(usize, &'static str)>,
+}
+
+impl TranscriptLog {
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn labels(&self) -> &[(usize, &'static str)] {
+        &self.labels
+    }
+
+    fn absorb(&mut self, label: &'static str) {
+        self.absorbing = true;
+        self.labels.push((self.round, label));
+    }
+
+    //returns the round the challenge belongs to
+    fn squeeze(&mut self, label: &'static str) -> usize {
+        if self.absorbing {
+            self.round += 1;
+            self.absorbing = false;
+        }
+        self.labels.push((self.round, label));
+        self.round
+    }
+}
+
+pub trait Transcript<F: PrimeField> {
     fn new(label: &'static str) -> Self;
 
-    fn append_message(&mut self, label: &'static str, msg: &[u8]);
+    //absorb raw bytes into the underlying hash
+    fn absorb_bytes(&mut self, label: &'static str, msg: &[u8]);
 
-    fn append_vk_and_pi<E, P>(&mut self, vk: &Vk<E>, pi: &[E::Fr])
+    //squeeze raw bytes from the underlying hash
+    fn squeeze_bytes(&mut self, label: &'static str, dest: &mut [u8]);
+
+    fn log(&self) -> &TranscriptLog;
+
+    fn log_mut(&mut self) -> &mut TranscriptLog;
+
+    fn round(&self) -> usize {
+        self.log().round()
+    }
+
+    fn append_message(&mut self, label: &'static str, msg: &[u8]) {
+        self.log_mut().absorb(label);
+        self.absorb_bytes(label, msg);
+    }
+
+    fn append_scalar(&mut self, label: &'static str, scalar: &F) {
+        self.append_message(label, &to_bytes!(scalar).unwrap());
+    }
+
+    fn append_evaluation(&mut self, label: &'static str, eval: &F) {
+        self.append_message(label, &to_bytes!(eval).unwrap());
+    }
+
+    fn append_commitment<C: CanonicalSerialize + Absorb<F>>(&mut self, label: &'static str, commit: &C) {
+        self.append_message(label, &to_bytes!(commit).unwrap());
+    }
+
+    fn append_commitments<C: CanonicalSerialize + Absorb<F>>(&mut self, label: &'static str, commits: &[C]) {
+        for commit in commits.iter() {
+            self.append_commitment(label, commit);
+        }
+    }
+
+    fn append_vk_and_pi<PC>(&mut self, vk: &Vk<PC>, pi: &[F])
     where
-        E: PairingEngine<Fq = F, G1Affine = GroupAffine<P>>,
-        P: SWModelParameters<BaseField = F>,
+        PC: PolynomialCommitment<Field = F>,
     {
-        self.append_message("field order", E::Fr::size_in_bits().to_le_bytes().as_ref());
+        self.append_message("field order", F::size_in_bits().to_le_bytes().as_ref());
         self.append_message("domain size", vk.domain_size.to_le_bytes().as_ref());
         self.append_message("input size", vk.pi_count.to_le_bytes().as_ref());
-        for ipc in vk.identify_perm_commits.iter() {
-            self.append_message("identify permutation commitments", &to_bytes!(ipc).unwrap());
+        self.append_message("pcs verifier key", &to_bytes!(&vk.vk).unwrap());
+        self.append_commitments("identify permutation commitments", &vk.identify_perm_commits);
+        for (id, sc) in vk.selector_commits.iter().enumerate() {
+            self.append_commitment(selector_label(id), sc);
+        }
+        for k in vk.k_reprs.iter() {
+            self.append_scalar("coset representative", k);
+        }
+        //rows the public inputs are placed at are part of the statement
+        for gate in vk.pi_gates.iter() {
+            self.append_message("pi gate", &(*gate as u64).to_le_bytes());
         }
+        for pi in pi.iter() {
+            self.append_scalar("public input", pi);
+        }
+    }
 
-        for sc in vk.selector_commits.iter() {
-            self.append_message("selector commitments", &to_bytes!(sc).unwrap());
+    fn append_proof_eval(&mut self, evals: &ProofEvaluations<F>) {
+        for we in &evals.wire_evals {
+            self.append_evaluation("wire evaluations", we);
+        }
+        for ipe in &evals.identify_perm_evals {
+            self.append_evaluation("identify permutation evaluations", ipe);
         }
+        self.append_evaluation("product permutation evaluation", &evals.prod_perm_eval);
+    }
+
+    //Squeeze a challenge bound to the current round, the challenge is absorbed afterwards
+    fn get_challenge(&mut self, label: &'static str) -> F {
+        let round = self.log_mut().squeeze(label);
+        self.absorb_bytes("round", &(round as u64).to_le_bytes());
+        let mut buf = [0u8; 64];
+        self.squeeze_bytes(label, &mut buf);
+        let challenge = F::from_le_bytes_mod_order(&buf);
+        self.absorb_bytes(label, &to_bytes!(&challenge).unwrap());
+        challenge
+    }
+}
 
-        for _pi in pi.iter() {
-            self.append_message("public input", &to_bytes!(_pi).unwrap());
+pub struct PlonkTranscript<F: PrimeField> {
+    script: Script,
+    log: TranscriptLog,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> Transcript<F> for PlonkTranscript<F> {
+    fn new(label: &'static str) -> Self {
+        Self {
+            script: Script::new(label.as_bytes()),
+            log: TranscriptLog::default(),
+            _field: PhantomData,
         }
     }
 
-    fn append_commitments<E, P>(&mut self, label: &'static str, commits: &[Commitment<E>])
-    where
-        E: PairingEngine<Fq = F, G1Affine = GroupAffine<P>>,
-        P: SWModelParameters<BaseField = F>,
-    {
-        for commit in commits.iter() {
-            self.append_commitment(label, commit);
+    fn absorb_bytes(&mut self, label: &'static str, msg: &[u8]) {
+        self.script.append_message(label.as_bytes(), msg);
+    }
+
+    fn squeeze_bytes(&mut self, label: &'static str, dest: &mut [u8]) {
+        self.script.challenge_bytes(label.as_bytes(), dest);
+    }
+
+    fn log(&self) -> &TranscriptLog {
+        &self.log
+    }
+
+    fn log_mut(&mut self) -> &mut TranscriptLog {
+        &mut self.log
+    }
+}
+
+//Keccak-256 transcript with a fixed byte layout, a contract or a verifier in another language
+//reproduces its challenges. Labels are not hashed, the protocol fixes the order of the messages.
+//  state = keccak256(transcript label)
+//  a message appends its bytes to the state:
+//    scalar       big endian word as wide as the field representation, 32 bytes for BN254 and BLS12-381
+//    point        big endian affine x || y, (0, 0) for the point at infinity
+//    digest       the 32 bytes as they are
+//    commitment   every element of Absorb::elements in order, one G1 point for KZG10
+//  challenge = keccak256(state) mod r, the hash becomes the new state and is not absorbed again
+//A PLONK proof absorbs, with the challenges after every line:
+//  vk digest (see vk_digest), public inputs, wire commitments           -> beta, gamma
+//  product permutation commitment                                      -> alpha
+//  quotient chunk commitments                                          -> zeta
+//  wire evaluations, identify permutation evaluations, z(zeta * omega) -> v
+//  per point openings: opening proof at zeta, then at zeta * omega     -> u
+//Batch proofs start from the label "plonk batch transcript", see PlonkSnark::batch_prove_iop.
+pub struct KeccakTranscript<F: PrimeField> {
+    state: Vec<u8>,
+    log: TranscriptLog,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakTranscript<F> {
+    fn scalar_bytes(scalar: &F) -> Vec<u8> {
+        scalar.into_repr().to_bytes_be()
+    }
+
+    fn commitment_bytes<C: Absorb<F>>(commit: &C) -> Vec<u8> {
+        let mut bytes = vec![];
+        for element in commit.elements() {
+            match element {
+                Element::Point(x, y) => {
+                    bytes.extend(x);
+                    bytes.extend(y);
+                }
+                Element::Scalar(s) => bytes.extend(Self::scalar_bytes(&s)),
+                Element::Digest(d) => bytes.extend(d),
+            }
         }
+        bytes
     }
 
-    fn append_commitment<E, P>(&mut self, label: &'static str, commit: &Commitment<E>)
+    //keccak256 of the field size in bits, domain size and input count as scalars, the compressed
+    //serialization of the pcs verifier key, identify permutation then selector commitments, the
+    //coset representatives, and the rows of the public inputs as scalars. A verifier contract
+    //inlines it as a constant.
+    pub fn vk_digest<PC>(vk: &Vk<PC>) -> [u8; 32]
     where
-        E: PairingEngine<Fq = F, G1Affine = GroupAffine<P>>,
-        P: SWModelParameters<BaseField = F>,
+        PC: PolynomialCommitment<Field = F>,
     {
-        self.append_message(label, &to_bytes!(commit).unwrap());
+        let mut hasher = Keccak256::new();
+        hasher.update(Self::scalar_bytes(&F::from(F::size_in_bits() as u64)));
+        hasher.update(Self::scalar_bytes(&F::from(vk.domain_size as u64)));
+        hasher.update(Self::scalar_bytes(&F::from(vk.pi_count as u64)));
+        hasher.update(to_bytes!(&vk.vk).unwrap());
+        for commit in vk.identify_perm_commits.iter().chain(vk.selector_commits.iter()) {
+            hasher.update(Self::commitment_bytes(commit));
+        }
+        for k in vk.k_reprs.iter() {
+            hasher.update(Self::scalar_bytes(k));
+        }
+        for gate in vk.pi_gates.iter() {
+            hasher.update(Self::scalar_bytes(&F::from(*gate as u64)));
+        }
+        hasher.finalize().into()
     }
+}
 
-    fn append_proof_eval<E: PairingEngine>(&mut self, evals: &ProofEvaluations<E::Fr>) {
-        for we in &evals.wire_evals {
-            self.append_message("wire evaluations", &to_bytes!(we).unwrap())
+impl<F: PrimeField> Transcript<F> for KeccakTranscript<F> {
+    fn new(label: &'static str) -> Self {
+        Self {
+            state: Keccak256::digest(label.as_bytes()).to_vec(),
+            log: TranscriptLog::default(),
+            _field: PhantomData,
         }
-        for ipe in &evals.identify_perm_evals {
-            self.append_message(
-                "indentify permutation evaluations",
-                &to_bytes!(ipe).unwrap(),
-            )
+    }
+
+    fn absorb_bytes(&mut self, _label: &'static str, msg: &[u8]) {
+        self.state.extend_from_slice(msg);
+    }
+
+    //every 32 bytes hash the state into the next state
+    fn squeeze_bytes(&mut self, _label: &'static str, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let hash = Keccak256::digest(&self.state);
+            chunk.copy_from_slice(&hash[..chunk.len()]);
+            self.state = hash.to_vec();
         }
     }
 
-    fn append_challenge<E>(&mut self, label: &'static str, challenge: &E::Fr)
+    fn log(&self) -> &TranscriptLog {
+        &self.log
+    }
+
+    fn log_mut(&mut self) -> &mut TranscriptLog {
+        &mut self.log
+    }
+
+    fn append_scalar(&mut self, label: &'static str, scalar: &F) {
+        self.append_message(label, &Self::scalar_bytes(scalar));
+    }
+
+    fn append_evaluation(&mut self, label: &'static str, eval: &F) {
+        self.append_message(label, &Self::scalar_bytes(eval));
+    }
+
+    fn append_commitment<C: CanonicalSerialize + Absorb<F>>(&mut self, label: &'static str, commit: &C) {
+        self.append_message(label, &Self::commitment_bytes(commit));
+    }
+
+    fn append_vk_and_pi<PC>(&mut self, vk: &Vk<PC>, pi: &[F])
     where
-        E: PairingEngine<Fq = F>,
+        PC: PolynomialCommitment<Field = F>,
     {
-        self.append_message(label, &to_bytes!(challenge).unwrap());
+        self.append_message("verifier key digest", &Self::vk_digest(vk));
+        for pi in pi.iter() {
+            self.append_scalar("public input", pi);
+        }
     }
 
-    fn get_challenge<E>(&mut self, label: &'static str)
-    where
-        E: PairingEngine;
+    //The round is bound by the chained state, the hash is not absorbed again
+    fn get_challenge(&mut self, label: &'static str) -> F {
+        self.log_mut().squeeze(label);
+        let mut buf = [0u8; 32];
+        self.squeeze_bytes(label, &mut buf);
+        F::from_be_bytes_mod_order(&buf)
+    }
 }
 
-pub struct PlonkTranscript(Script);
+//Poseidon sponge transcript over the scalar field, a circuit that verifies the proof recomputes
+//its challenges natively instead of emulating a byte hash. The sponge has width 3, rate 2 and
+//capacity 1 with the parameters of PoseidonParams::default.
+//  state = [0, 0, 0], the capacity is the first element, then the transcript label is absorbed as bytes
+//  absorbing an element adds it to the next rate element, permuting first when the rate is full
+//  challenge = the first rate element after a permutation, the next absorb starts over at it
+//A message is absorbed as:
+//  scalar       the element itself
+//  point        x then y, each coordinate of the base field cut into 128 bit limbs, most significant
+//               first: 2 limbs for BN254 and Pallas, 3 for BLS12-381, (0, 0) for the point at infinity
+//  digest       2 limbs of 128 bits
+//  bytes        the byte length, then the bytes as 128 bit big endian limbs, the pcs verifier key
+//               and the sizes of append_vk_and_pi
+pub struct PoseidonTranscript<F: PrimeField> {
+    params: PoseidonParams<F>,
+    state: Vec<F>,
+    absorbed: usize,
+    log: TranscriptLog,
+}
 
-impl<F> Transcript<F> for PlonkTranscript {
+impl<F: PrimeField> PoseidonTranscript<F> {
+    pub const LIMB_BYTES: usize = 16;
+
+    fn rate(&self) -> usize {
+        self.params.width - 1
+    }
+
+    fn absorb(&mut self, element: F) {
+        if self.absorbed == self.rate() {
+            self.params.permute(&mut self.state);
+            self.absorbed = 0;
+        }
+        self.state[1 + self.absorbed] += element;
+        self.absorbed += 1;
+    }
+
+    fn squeeze(&mut self) -> F {
+        self.params.permute(&mut self.state);
+        self.absorbed = 0;
+        self.state[1]
+    }
+
+    //big endian bytes left padded to whole limbs, each limb is below the field order
+    fn absorb_limbs(&mut self, bytes: &[u8]) {
+        let pad = (Self::LIMB_BYTES - bytes.len() % Self::LIMB_BYTES) % Self::LIMB_BYTES;
+        let padded = [&vec![0u8; pad], bytes].concat();
+        for limb in padded.chunks(Self::LIMB_BYTES) {
+            self.absorb(F::from_be_bytes_mod_order(limb));
+        }
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for PoseidonTranscript<F> {
     fn new(label: &'static str) -> Self {
-        Self(Script::new(label.as_bytes()))
+        let params = PoseidonParams::default();
+        let mut transcript = Self {
+            state: vec![F::zero(); params.width],
+            params,
+            absorbed: 0,
+            log: TranscriptLog::default(),
+        };
+        transcript.absorb_bytes(label, label.as_bytes());
+        transcript
     }
 
-    fn append_message(&mut self, label: &'static str, msg: &[u8]) {
-        self.0.append_message(label.as_bytes(), msg);
+    fn absorb_bytes(&mut self, _label: &'static str, msg: &[u8]) {
+        self.absorb(F::from(msg.len() as u64));
+        self.absorb_limbs(msg);
     }
 
-    fn get_challenge<E>(&mut self, label: &'static str)
-    where
-        E: PairingEngine,
-    {
-        let mut buf = [0u8; 64];
-        self.0.challenge_bytes(label.as_bytes(), &mut buf);
-        let challenge = E::Fr::from_le_bytes_mod_order(&buf);
-        self.0
-            .append_message(label.as_bytes(), &to_bytes!(&challenge).unwrap());
+    //every 16 bytes are the low bytes of a squeezed element
+    fn squeeze_bytes(&mut self, _label: &'static str, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(Self::LIMB_BYTES) {
+            let bytes = self.squeeze().into_repr().to_bytes_be();
+            chunk.copy_from_slice(&bytes[bytes.len() - chunk.len()..]);
+        }
+    }
+
+    fn log(&self) -> &TranscriptLog {
+        &self.log
+    }
+
+    fn log_mut(&mut self) -> &mut TranscriptLog {
+        &mut self.log
+    }
+
+    fn append_scalar(&mut self, label: &'static str, scalar: &F) {
+        self.log_mut().absorb(label);
+        self.absorb(*scalar);
+    }
+
+    fn append_evaluation(&mut self, label: &'static str, eval: &F) {
+        self.append_scalar(label, eval);
+    }
+
+    fn append_commitment<C: CanonicalSerialize + Absorb<F>>(&mut self, label: &'static str, commit: &C) {
+        self.log_mut().absorb(label);
+        for element in commit.elements() {
+            match element {
+                Element::Point(x, y) => {
+                    self.absorb_limbs(&x);
+                    self.absorb_limbs(&y);
+                }
+                Element::Scalar(s) => self.absorb(s),
+                Element::Digest(d) => self.absorb_limbs(&d),
+            }
+        }
+    }
+
+    //The round is bound by the sponge state, the challenge is not absorbed again
+    fn get_challenge(&mut self, label: &'static str) -> F {
+        self.log_mut().squeeze(label);
+        self.squeeze()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{PlonkTranscript, Transcript};
+    use crate::prover::ProofEvaluations;
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine};
+    use ark_ec::AffineCurve;
+    use ark_ff::{BigInteger, PrimeField};
+    use constraint::circuit::WIRE_COUNT;
+    use pcs::kzg10::Commitment;
+
+    fn to_hex(f: Fr) -> String {
+        f.into_repr()
+            .to_bytes_le()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn absorb_messages(transcript: &mut PlonkTranscript<Fr>) {
+        transcript.append_scalar("scalar", &Fr::from(1u64));
+        transcript.append_evaluation("evaluation", &Fr::from(2u64));
+        let commit = Commitment::<Bls12_381>(G1Affine::prime_subgroup_generator());
+        transcript.append_commitment("commitment", &commit);
+    }
+
+    #[test]
+    fn test_known_answer() {
+        let mut transcript = PlonkTranscript::<Fr>::new("plonk transcript");
+        let c0 = transcript.get_challenge("c0");
+        assert_eq!(
+            to_hex(c0),
+            "d895988b8347ebd6395544796819f56d1a72ec62707d8c011e0b70d21af67925"
+        );
+
+        absorb_messages(&mut transcript);
+        let c1 = transcript.get_challenge("c1");
+        let c2 = transcript.get_challenge("c2");
+        assert_eq!(
+            to_hex(c1),
+            "7a961cee10e44e51c9b87edf480412527ee024b91b8d5eeffa30d40fca64de5a"
+        );
+        assert_eq!(
+            to_hex(c2),
+            "af4ddc2acea1660605e40a3bb35729ee72ddca4d4691e381bc8b3a6349ed4c6e"
+        );
+    }
+
+    //The labels of the proof evaluations are part of the challenge v
+    #[test]
+    fn test_proof_eval_known_answer() {
+        let evals = ProofEvaluations {
+            wire_evals: (1..=WIRE_COUNT as u64).map(Fr::from).collect(),
+            identify_perm_evals: (11..10 + WIRE_COUNT as u64).map(Fr::from).collect(),
+            prod_perm_eval: Fr::from(20u64),
+        };
+        let mut transcript = PlonkTranscript::<Fr>::new("plonk transcript");
+        transcript.append_proof_eval(&evals);
+        let v = transcript.get_challenge("v");
+        assert_eq!(
+            to_hex(v),
+            "7080283a6231164f3f0c436a0a0daa1487436736c11af7184079c470ad414f55"
+        );
+        let labels: Vec<&str> = transcript.log().labels().iter().map(|(_, label)| *label).collect();
+        assert_eq!(labels[WIRE_COUNT], "identify permutation evaluations");
+        assert_eq!(labels[2 * WIRE_COUNT - 1], "product permutation evaluation");
+    }
+
+    #[test]
+    fn test_round_discipline() {
+        let mut transcript = PlonkTranscript::<Fr>::new("plonk transcript");
+        absorb_messages(&mut transcript);
+        transcript.get_challenge("beta");
+        transcript.get_challenge("gamma");
+        assert_eq!(transcript.round(), 1);
+        transcript.append_scalar("scalar", &Fr::from(3u64));
+        transcript.get_challenge("alpha");
+        assert_eq!(transcript.round(), 2);
+        assert_eq!(
+            transcript.log().labels(),
+            &[
+                (0, "scalar"),
+                (0, "evaluation"),
+                (0, "commitment"),
+                (1, "beta"),
+                (1, "gamma"),
+                (1, "scalar"),
+                (2, "alpha"),
+            ]
+        );
+
+        //a transcript that squeezes in a different round drifts apart
+        let mut expected = PlonkTranscript::<Fr>::new("plonk transcript");
+        absorb_messages(&mut expected);
+        let beta = expected.get_challenge("beta");
+        let mut drifted = PlonkTranscript::<Fr>::new("plonk transcript");
+        absorb_messages(&mut drifted);
+        drifted.get_challenge("gamma");
+        drifted.append_scalar("scalar", &Fr::from(3u64));
+        assert_ne!(drifted.get_challenge("beta"), beta);
+        assert_ne!(drifted.log(), expected.log());
+    }
+
+    //Challenges of the keccak transcript and the same challenges from the specified byte layout
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_keccak_known_answer() {
+        use super::KeccakTranscript;
+        use ark_bn254::{Bn254, Fr, G1Affine};
+        use ark_ff::Zero;
+        use sha3::{Digest, Keccak256};
+
+        let be_hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+
+        //Keccak-256 as the EVM has it, not SHA3-256
+        assert_eq!(
+            be_hex(&Keccak256::digest(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+
+        let mut transcript = KeccakTranscript::<Fr>::new("plonk transcript");
+        let c0 = transcript.get_challenge("c0");
+        transcript.append_scalar("scalar", &Fr::from(1u64));
+        transcript.append_evaluation("evaluation", &Fr::from(2u64));
+        //the BN254 generator is (1, 2)
+        transcript.append_commitment("commitment", &Commitment::<Bn254>(G1Affine::prime_subgroup_generator()));
+        transcript.append_commitment("infinity", &Commitment::<Bn254>(G1Affine::zero()));
+        let c1 = transcript.get_challenge("c1");
+        let c2 = transcript.get_challenge("c2");
+        assert_eq!(transcript.round(), 1);
+        let to_hex = |c: Fr| be_hex(&c.into_repr().to_bytes_be());
+        assert_eq!(
+            to_hex(c0),
+            "2ba5583b3c604bc380de3f784fa5eda51eda5d9dc756482b7ee68912cf06fa3c"
+        );
+        assert_eq!(
+            to_hex(c1),
+            "17037b225ce1ad88e17e7419bfb70ee2ca95c5e8dca79d29fa23f511cb2dc958"
+        );
+        assert_eq!(
+            to_hex(c2),
+            "1352676e7ac3dd5a39e6ea4689c689b5fd7a7b908aaf6df1277388c9ddf617b1"
+        );
+
+        let word = |v: u8| {
+            let mut word = [0u8; 32];
+            word[31] = v;
+            word
+        };
+        let state = Keccak256::digest(b"plonk transcript");
+        let h0 = Keccak256::digest(state);
+        assert_eq!(c0, Fr::from_be_bytes_mod_order(&h0));
+        let h1 = Keccak256::digest([&h0[..], &word(1), &word(2), &word(1), &word(2), &[0u8; 64]].concat());
+        assert_eq!(c1, Fr::from_be_bytes_mod_order(&h1));
+        let h2 = Keccak256::digest(h1);
+        assert_eq!(c2, Fr::from_be_bytes_mod_order(&h2));
+    }
+
+    //Challenges of the poseidon transcript and the same challenges from the specified sponge layout
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_poseidon_layout() {
+        use super::PoseidonTranscript;
+        use crate::poseidon::PoseidonParams;
+        use ark_bn254::{Bn254, Fr, G1Affine};
+        use ark_ff::Zero;
+
+        let mut transcript = PoseidonTranscript::<Fr>::new("plonk transcript");
+        let c0 = transcript.get_challenge("c0");
+        transcript.append_scalar("scalar", &Fr::from(1u64));
+        transcript.append_evaluation("evaluation", &Fr::from(2u64));
+        transcript.append_commitment("commitment", &Commitment::<Bn254>(G1Affine::prime_subgroup_generator()));
+        transcript.append_commitment("infinity", &Commitment::<Bn254>(G1Affine::zero()));
+        let c1 = transcript.get_challenge("c1");
+        let c2 = transcript.get_challenge("c2");
+        assert_eq!(transcript.round(), 1);
+
+        let params = PoseidonParams::<Fr>::default();
+        let mut state = [Fr::zero(); 3];
+        let absorb = |state: &mut [Fr; 3], rate: [Fr; 2]| {
+            state[1] += rate[0];
+            state[2] += rate[1];
+        };
+        //the label is 16 bytes, one limb
+        absorb(&mut state, [Fr::from(16u64), Fr::from_be_bytes_mod_order(b"plonk transcript")]);
+        params.permute(&mut state);
+        assert_eq!(c0, state[1]);
+        absorb(&mut state, [Fr::from(1u64), Fr::from(2u64)]);
+        //the generator (1, 2) and the point at infinity, 2 limbs per coordinate
+        for rate in [[0u64, 1], [0, 2], [0, 0], [0, 0]] {
+            params.permute(&mut state);
+            absorb(&mut state, rate.map(Fr::from));
+        }
+        params.permute(&mut state);
+        assert_eq!(c1, state[1]);
+        params.permute(&mut state);
+        assert_eq!(c2, state[1]);
     }
 }
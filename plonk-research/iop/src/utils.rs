@@ -1,3 +1,6 @@
+use constraint::circuit::{INPUT1, INPUT2, INPUT3, INPUT4, OUTPUT};
+use constraint::gate::{INPUT_COUNT, Q_LC, Q_MUL, Q_O, SELECTOR_COUNT};
+
 #[macro_export]
 macro_rules! to_bytes {
     ($x:expr) => {{
@@ -5,3 +8,30 @@ macro_rules! to_bytes {
         ark_serialize::CanonicalSerialize::serialize($x, &mut buf).map(|_| buf)
     }};
 }
+
+//Terms multiplied by each selector in the gate constraint
+//sum(q_lc[i] * w[i]) + q_mul[0] * w0 * w1 + q_mul[1] * w2 * w3 - q_o * w4 + q_c,
+//ordered as selector polys (Q_LC, Q_MUL, Q_O, Q_C)
+pub(crate) fn selector_terms<T, M, N>(wires: &[T], mul: M, neg: N, one: T) -> Vec<T>
+where
+    T: Clone,
+    M: Fn(&T, &T) -> T,
+    N: Fn(&T) -> T,
+{
+    let mut terms = vec![one; SELECTOR_COUNT];
+    write_selector_terms(wires, &mut terms, mul, neg);
+    terms
+}
+
+//selector_terms into a buffer of SELECTOR_COUNT terms whose constant term is already one
+pub(crate) fn write_selector_terms<T, M, N>(wires: &[T], terms: &mut [T], mul: M, neg: N)
+where
+    T: Clone,
+    M: Fn(&T, &T) -> T,
+    N: Fn(&T) -> T,
+{
+    terms[Q_LC..Q_MUL].clone_from_slice(&wires[INPUT1..INPUT1 + INPUT_COUNT]);
+    terms[Q_MUL] = mul(&wires[INPUT1], &wires[INPUT2]);
+    terms[Q_MUL + 1] = mul(&wires[INPUT3], &wires[INPUT4]);
+    terms[Q_O] = neg(&wires[OUTPUT]);
+}
@@ -0,0 +1,388 @@
+use crate::prover::{quot_chunk_size, ProofEvaluations};
+use crate::snark::{BatchProof, Challenge, Openings, Proof, Vk};
+use crate::utils::selector_terms;
+use ark_ff::{Field, One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use constraint::circuit::WIRE_COUNT;
+use constraint::gate::SELECTOR_COUNT;
+use pcs::shplonk::SHPLONK;
+use pcs::PolynomialCommitment;
+
+pub struct Verifier<PC: PolynomialCommitment> {
+    domain: Radix2EvaluationDomain<PC::Field>,
+}
+
+impl<PC> Verifier<PC>
+where
+    PC: PolynomialCommitment,
+{
+    //None if the field has no domain of that size
+    pub(crate) fn new(domain_size: usize) -> Option<Self> {
+        let domain = Radix2EvaluationDomain::<PC::Field>::new(domain_size)?;
+        Some(Self { domain })
+    }
+
+    //Check the shape of the proof, and every commitment and opening proof must be well formed
+    pub(crate) fn validate_proof(&self, vk: &Vk<PC>, proof: &Proof<PC>, pi: &[PC::Field]) -> bool {
+        self.validate_instance(vk, &proof.wire_poly_commits, &proof.poly_evals, pi)
+            && proof.quot_poly_commits.len() == WIRE_COUNT
+            && proof
+                .wire_poly_commits
+                .iter()
+                .chain(proof.quot_poly_commits.iter())
+                .chain([&proof.prod_perm_poly_commit])
+                .all(PC::check_commitment)
+            && Self::validate_openings(&proof.openings, 1)
+    }
+
+    //Batch proof shares quotient and opening proofs, one shifted opening proof
+    //for every distinct domain size, all instances must come from the same srs
+    pub(crate) fn validate_batch_proof(
+        verifiers: &[Self],
+        vks: &[&Vk<PC>],
+        proof: &BatchProof<PC>,
+        pi: &[&[PC::Field]],
+    ) -> bool {
+        if verifiers.is_empty()
+            || vks.len() != verifiers.len()
+            || pi.len() != verifiers.len()
+            || proof.wire_poly_commits_vec.len() != verifiers.len()
+            || proof.prod_perm_poly_commit_vec.len() != verifiers.len()
+            || proof.poly_evals_vec.len() != verifiers.len()
+            || proof.quot_poly_commits.len() != WIRE_COUNT
+        {
+            return false;
+        }
+        let max_vk = &vks[batch_max_domain(verifiers)].vk;
+        let same_srs = vks.iter().all(|vk| PC::same_params(&vk.vk, max_vk));
+        same_srs
+            && verifiers.iter().enumerate().all(|(i, verifier)| {
+                verifier.validate_instance(
+                    vks[i],
+                    &proof.wire_poly_commits_vec[i],
+                    &proof.poly_evals_vec[i],
+                    pi[i],
+                )
+            })
+            && proof
+                .wire_poly_commits_vec
+                .iter()
+                .flatten()
+                .chain(proof.prod_perm_poly_commit_vec.iter())
+                .chain(proof.quot_poly_commits.iter())
+                .all(PC::check_commitment)
+            && Self::validate_openings(&proof.openings, batch_domain_sizes(vks).len())
+    }
+
+    //Per point openings have one shifted opening proof for every shifted point
+    fn validate_openings(openings: &Openings<PC>, shifted_count: usize) -> bool {
+        match openings {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => {
+                shifted_opening_proofs.len() == shifted_count
+                    && shifted_opening_proofs
+                        .iter()
+                        .chain([opening_proof])
+                        .all(PC::check_proof)
+            }
+            Openings::MultiPoint(proof) => {
+                PC::check_commitment(&proof.quot_commit) && PC::check_proof(&proof.opening_proof)
+            }
+        }
+    }
+
+    fn validate_instance(
+        &self,
+        vk: &Vk<PC>,
+        wire_poly_commits: &[PC::Commitment],
+        evals: &ProofEvaluations<PC::Field>,
+        pi: &[PC::Field],
+    ) -> bool {
+        self.domain.size() == vk.domain_size
+            && pi.len() == vk.pi_count
+            && vk.selector_commits.len() == SELECTOR_COUNT
+            && vk.identify_perm_commits.len() == WIRE_COUNT
+            && vk.k_reprs.len() == WIRE_COUNT
+            && vk.pi_gates.len() == vk.pi_count
+            && wire_poly_commits.len() == WIRE_COUNT
+            && evals.wire_evals.len() == WIRE_COUNT
+            && evals.identify_perm_evals.len() == WIRE_COUNT - 1
+    }
+
+    //PI(zeta) = sum pi_i * L_{g_i}(zeta), g_i is the row of i-th pi gate
+    fn compute_pi_eval(&self, vk: &Vk<PC>, pi: &[PC::Field], zeta: PC::Field, vanish_eval: PC::Field) -> PC::Field {
+        let n = self.domain.size_as_field_element;
+        vk.pi_gates
+            .iter()
+            .zip(pi.iter())
+            .fold(PC::Field::zero(), |acc, (&gate_id, &val)| {
+                //L_j(zeta) = w^j * Z_H(zeta) / (n * (zeta - w^j))
+                let omega_j = self.domain.group_gen.pow([gate_id as u64]);
+                acc + val * omega_j * vanish_eval / (n * (zeta - omega_j))
+            })
+    }
+
+    //Constant term r0 and the commitment terms of linearization poly without quotient part
+    fn compute_lin_terms(
+        &self,
+        vk: &Vk<PC>,
+        evals: &ProofEvaluations<PC::Field>,
+        prod_perm_poly_commit: &PC::Commitment,
+        pi: &[PC::Field],
+        challenge: &Challenge<PC::Field>,
+    ) -> (PC::Field, Vec<PC::Commitment>, Vec<PC::Field>) {
+        let alpha = challenge.alpha;
+        let beta = challenge.beta;
+        let gamma = challenge.gamma;
+        let zeta = challenge.zeta;
+        let wire_evals = &evals.wire_evals;
+
+        let vanish_eval = self.domain.evaluate_vanishing_polynomial(zeta);
+        let l1_eval = vanish_eval / (self.domain.size_as_field_element * (zeta - PC::Field::one()));
+        let pi_eval = self.compute_pi_eval(vk, pi, zeta, vanish_eval);
+
+        let prod_orig = wire_evals
+            .iter()
+            .zip(vk.k_reprs.iter())
+            .fold(PC::Field::one(), |acc, (&a, &k)| acc * (a + beta * k * zeta + gamma));
+        let prod_perm = wire_evals
+            .iter()
+            .zip(evals.identify_perm_evals.iter())
+            .fold(PC::Field::one(), |acc, (&a, &s)| acc * (a + beta * s + gamma));
+        //constant term of linearization poly, r(zeta) = -r0
+        let r0 = pi_eval
+            - alpha * alpha * l1_eval
+            - alpha * evals.prod_perm_eval * (wire_evals[WIRE_COUNT - 1] + gamma) * prod_perm;
+
+        let mut commits: Vec<PC::Commitment> = vk.selector_commits.clone();
+        let mut scalars = selector_terms(wire_evals, |a, b| *a * b, |a| -*a, PC::Field::one());
+        commits.push(prod_perm_poly_commit.clone());
+        scalars.push(alpha * prod_orig + alpha * alpha * l1_eval);
+        commits.push(vk.identify_perm_commits[WIRE_COUNT - 1].clone());
+        scalars.push(-alpha * beta * evals.prod_perm_eval * prod_perm);
+        (r0, commits, scalars)
+    }
+
+    //Quotient commitment terms of linearization poly, -Z_H(zeta) * zeta^(i * chunk_size)
+    fn push_quot_terms(
+        &self,
+        quot_poly_commits: &[PC::Commitment],
+        zeta: PC::Field,
+        vanish_eval: PC::Field,
+        commits: &mut Vec<PC::Commitment>,
+        scalars: &mut Vec<PC::Field>,
+    ) {
+        let zeta_n = zeta.pow([quot_chunk_size(self.domain.size()) as u64]);
+        let mut coeff = -vanish_eval;
+        for commit in quot_poly_commits.iter() {
+            commits.push(commit.clone());
+            scalars.push(coeff);
+            coeff *= zeta_n;
+        }
+    }
+
+    //Batch polys opened at zeta with challenge v, continuing from v_pow
+    fn compute_opening_terms(
+        vk: &Vk<PC>,
+        wire_poly_commits: &[PC::Commitment],
+        evals: &ProofEvaluations<PC::Field>,
+        v: PC::Field,
+        v_pow: &mut PC::Field,
+        eval: &mut PC::Field,
+    ) -> (Vec<PC::Commitment>, Vec<PC::Field>) {
+        let mut commits: Vec<PC::Commitment> = vec![];
+        let mut scalars: Vec<PC::Field> = vec![];
+        for (commit, &e) in wire_poly_commits
+            .iter()
+            .chain(vk.identify_perm_commits[..WIRE_COUNT - 1].iter())
+            .zip(evals.wire_evals.iter().chain(evals.identify_perm_evals.iter()))
+        {
+            *v_pow *= v;
+            commits.push(commit.clone());
+            scalars.push(*v_pow);
+            *eval += *v_pow * e;
+        }
+        (commits, scalars)
+    }
+
+    //Check all openings at once with challenge u: the combined commitment F opens to E at zeta,
+    //product permutation poly opens to z(zeta * omega) at zeta * omega
+    pub(crate) fn batch_check(
+        &self,
+        vk: &Vk<PC>,
+        proof: &Proof<PC>,
+        pi: &[PC::Field],
+        challenge: &Challenge<PC::Field>,
+    ) -> bool {
+        let zeta = challenge.zeta;
+        let omega = self.domain.group_gen;
+        let evals = &proof.poly_evals;
+
+        let vanish_eval = self.domain.evaluate_vanishing_polynomial(zeta);
+        if vanish_eval.is_zero() {
+            return false;
+        }
+
+        //linearization poly commitment
+        let (r0, mut commits, mut scalars) =
+            self.compute_lin_terms(vk, evals, &proof.prod_perm_poly_commit, pi, challenge);
+        self.push_quot_terms(&proof.quot_poly_commits, zeta, vanish_eval, &mut commits, &mut scalars);
+
+        //batch polys opened at zeta with challenge v
+        let mut eval = -r0;
+        let mut v_pow = PC::Field::one();
+        let (c, s) = Self::compute_opening_terms(
+            vk,
+            &proof.wire_poly_commits,
+            evals,
+            challenge.v,
+            &mut v_pow,
+            &mut eval,
+        );
+        commits.extend(c);
+        scalars.extend(s);
+
+        Self::check_openings(
+            &vk.vk,
+            &[
+                PC::combine_commitments(&commits, &scalars),
+                proof.prod_perm_poly_commit.clone(),
+            ],
+            &[zeta, zeta * omega],
+            &[eval, evals.prod_perm_eval],
+            &proof.openings,
+            challenge.u,
+        )
+    }
+
+    //Batch check a proof of many circuits. Circuit i is scaled by alpha^(3i) * Z_N(zeta) / Z_i(zeta),
+    //N is the largest domain, so the combined quotient is divided by Z_N(zeta). Product permutation
+    //polys with the same domain size share one shifted opening, combined with powers of v.
+    //All openings are checked at once with challenge u.
+    pub(crate) fn batch_check_proofs(
+        verifiers: &[Self],
+        vks: &[&Vk<PC>],
+        proof: &BatchProof<PC>,
+        pi: &[&[PC::Field]],
+        challenge: &Challenge<PC::Field>,
+    ) -> bool {
+        let zeta = challenge.zeta;
+        let v = challenge.v;
+        let max_id = batch_max_domain(verifiers);
+        let vanish_eval = verifiers[max_id].domain.evaluate_vanishing_polynomial(zeta);
+        if vanish_eval.is_zero() {
+            return false;
+        }
+
+        //linearization poly commitment
+        let mut r0 = PC::Field::zero();
+        let mut commits: Vec<PC::Commitment> = vec![];
+        let mut scalars: Vec<PC::Field> = vec![];
+        let alpha_base = challenge.alpha.pow([3u64]);
+        let mut alpha_pow = PC::Field::one();
+        for (i, verifier) in verifiers.iter().enumerate() {
+            let lambda = vanish_eval / verifier.domain.evaluate_vanishing_polynomial(zeta);
+            let (r, c, s) = verifier.compute_lin_terms(
+                vks[i],
+                &proof.poly_evals_vec[i],
+                &proof.prod_perm_poly_commit_vec[i],
+                pi[i],
+                challenge,
+            );
+            r0 += alpha_pow * lambda * r;
+            commits.extend(c);
+            scalars.extend(s.iter().map(|s| alpha_pow * lambda * s));
+            alpha_pow *= alpha_base;
+        }
+        verifiers[max_id].push_quot_terms(&proof.quot_poly_commits, zeta, vanish_eval, &mut commits, &mut scalars);
+
+        //batch polys opened at zeta with challenge v
+        let mut eval = -r0;
+        let mut v_pow = PC::Field::one();
+        for (i, vk) in vks.iter().enumerate() {
+            let (c, s) = Self::compute_opening_terms(
+                vk,
+                &proof.wire_poly_commits_vec[i],
+                &proof.poly_evals_vec[i],
+                v,
+                &mut v_pow,
+                &mut eval,
+            );
+            commits.extend(c);
+            scalars.extend(s);
+        }
+        let mut open_commits = vec![PC::combine_commitments(&commits, &scalars)];
+        let mut points = vec![zeta];
+        let mut values = vec![eval];
+
+        //product permutation polys opened at zeta * omega_j
+        for domain_size in batch_domain_sizes(vks) {
+            let mut group_commits = vec![];
+            let mut group_scalars = vec![];
+            let mut group_eval = PC::Field::zero();
+            let mut v_pow = PC::Field::one();
+            let mut omega = PC::Field::one();
+            for (i, verifier) in verifiers.iter().enumerate() {
+                if verifier.domain.size() == domain_size {
+                    group_commits.push(proof.prod_perm_poly_commit_vec[i].clone());
+                    group_scalars.push(v_pow);
+                    group_eval += v_pow * proof.poly_evals_vec[i].prod_perm_eval;
+                    v_pow *= v;
+                    omega = verifier.domain.group_gen;
+                }
+            }
+            open_commits.push(PC::combine_commitments(&group_commits, &group_scalars));
+            points.push(zeta * omega);
+            values.push(group_eval);
+        }
+        //openings use the key of the largest domain
+        Self::check_openings(&vks[max_id].vk, &open_commits, &points, &values, &proof.openings, challenge.u)
+    }
+
+    //commits[i] opens to values[i] at points[i], per point proofs are merged with challenge u,
+    //a multi point opening combines the claims with u
+    fn check_openings(
+        vk: &PC::VerifierKey,
+        commits: &[PC::Commitment],
+        points: &[PC::Field],
+        values: &[PC::Field],
+        openings: &Openings<PC>,
+        u: PC::Field,
+    ) -> bool {
+        match openings {
+            Openings::PerPoint {
+                opening_proof,
+                shifted_opening_proofs,
+            } => {
+                let proofs: Vec<PC::Proof> = [opening_proof]
+                    .into_iter()
+                    .chain(shifted_opening_proofs.iter())
+                    .cloned()
+                    .collect();
+                PC::multi_point_verify(vk, commits, points, values, &proofs, u)
+            }
+            Openings::MultiPoint(proof) => {
+                let point_sets: Vec<Vec<PC::Field>> = points.iter().map(|p| vec![*p]).collect();
+                let value_sets: Vec<Vec<PC::Field>> = values.iter().map(|v| vec![*v]).collect();
+                SHPLONK::verify(vk, commits, &point_sets, &value_sets, proof, u)
+            }
+        }
+    }
+}
+
+//Distinct domain sizes of a batch in ascending order, each has its own shifted opening point
+pub(crate) fn batch_domain_sizes<PC: PolynomialCommitment>(vks: &[&Vk<PC>]) -> Vec<usize> {
+    let mut sizes: Vec<usize> = vks.iter().map(|vk| vk.domain_size).collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+    sizes
+}
+
+//Index of the instance with the largest domain
+pub(crate) fn batch_max_domain<PC: PolynomialCommitment>(verifiers: &[Verifier<PC>]) -> usize {
+    (0..verifiers.len())
+        .max_by_key(|&i| verifiers[i].domain.size())
+        .unwrap()
+}
@@ -0,0 +1,544 @@
+//Compiler of the Yul subset the generated verifier is written in, and a local EVM to run it: the
+//Solidity contract is turned into bytecode without solc, deployed in revm and called with calldata.
+//Variables live in static memory slots from VARIABLES on, so functions may not recurse. The
+//dispatcher around the assembly block is the one solc emits for a single non payable function:
+//revert on an unknown selector or on value, and return the zero word when the block falls through.
+use revm::primitives::{Address, ExecutionResult, Output, TransactTo, U256};
+use revm::{Evm, InMemoryDB};
+use solang_parser::pt::{
+    ContractPart, SourceUnitPart, Statement, YulBlock, YulExpression, YulFunctionDefinition, YulStatement,
+};
+use std::collections::HashMap;
+
+//Above the memory the verifier uses for its transcript buffer
+pub const VARIABLES: usize = 0x8000;
+
+const JUMPDEST: u8 = 0x5b;
+const JUMP: u8 = 0x56;
+const JUMPI: u8 = 0x57;
+const PUSH1: u8 = 0x60;
+const PUSH2: u8 = 0x61;
+const MLOAD: u8 = 0x51;
+const MSTORE: u8 = 0x52;
+const ISZERO: u8 = 0x15;
+
+enum Op {
+    Code(u8),
+    Push(U256),
+    Label(usize),
+    PushLabel(usize),
+}
+
+//(opcode, inputs, outputs) of the builtins
+fn builtin(name: &str) -> Option<(u8, usize, usize)> {
+    Some(match name {
+        "stop" => (0x00, 0, 0),
+        "add" => (0x01, 2, 1),
+        "mul" => (0x02, 2, 1),
+        "sub" => (0x03, 2, 1),
+        "div" => (0x04, 2, 1),
+        "mod" => (0x06, 2, 1),
+        "addmod" => (0x08, 3, 1),
+        "mulmod" => (0x09, 3, 1),
+        "exp" => (0x0a, 2, 1),
+        "lt" => (0x10, 2, 1),
+        "gt" => (0x11, 2, 1),
+        "slt" => (0x12, 2, 1),
+        "sgt" => (0x13, 2, 1),
+        "eq" => (0x14, 2, 1),
+        "iszero" => (ISZERO, 1, 1),
+        "and" => (0x16, 2, 1),
+        "or" => (0x17, 2, 1),
+        "xor" => (0x18, 2, 1),
+        "not" => (0x19, 1, 1),
+        "byte" => (0x1a, 2, 1),
+        "shl" => (0x1b, 2, 1),
+        "shr" => (0x1c, 2, 1),
+        "keccak256" => (0x20, 2, 1),
+        "address" => (0x30, 0, 1),
+        "caller" => (0x33, 0, 1),
+        "callvalue" => (0x34, 0, 1),
+        "calldataload" => (0x35, 1, 1),
+        "calldatasize" => (0x36, 0, 1),
+        "calldatacopy" => (0x37, 3, 0),
+        "returndatasize" => (0x3d, 0, 1),
+        "returndatacopy" => (0x3e, 3, 0),
+        "pop" => (0x50, 1, 0),
+        "mload" => (MLOAD, 1, 1),
+        "mstore" => (MSTORE, 2, 0),
+        "mstore8" => (0x53, 2, 0),
+        "msize" => (0x59, 0, 1),
+        "gas" => (0x5a, 0, 1),
+        "staticcall" => (0xfa, 6, 1),
+        "return" => (0xf3, 2, 0),
+        "revert" => (0xfd, 2, 0),
+        "invalid" => (0xfe, 0, 0),
+        _ => return None,
+    })
+}
+
+struct Function {
+    entry: usize,
+    params: usize,
+    returns: usize,
+}
+
+#[derive(Default)]
+struct Compiler<'a> {
+    ops: Vec<Op>,
+    labels: usize,
+    slots: usize,
+    functions: HashMap<String, Function>,
+    pending: Vec<(usize, &'a YulFunctionDefinition)>,
+    //variable slots of the enclosing blocks of the current function
+    scopes: Vec<HashMap<String, usize>>,
+    //continue and break labels of the enclosing loops
+    loops: Vec<(usize, usize)>,
+    exit: Option<usize>,
+}
+
+impl<'a> Compiler<'a> {
+    fn label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels - 1
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        let slot = VARIABLES + 0x20 * self.slots;
+        self.slots += 1;
+        self.scopes.last_mut().unwrap().insert(name.to_string(), slot);
+        slot
+    }
+
+    fn lookup(&self, name: &str) -> usize {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .unwrap_or_else(|| panic!("undeclared variable {}", name))
+    }
+
+    fn push(&mut self, value: usize) {
+        self.ops.push(Op::Push(U256::from(value)));
+    }
+
+    fn jump(&mut self, label: usize) {
+        self.ops.push(Op::PushLabel(label));
+        self.ops.push(Op::Code(JUMP));
+    }
+
+    //Stores the values on the stack, the first one on top
+    fn store(&mut self, slots: &[usize]) {
+        for &slot in slots {
+            self.push(slot);
+            self.ops.push(Op::Code(MSTORE));
+        }
+    }
+
+    fn literal(&mut self, value: U256) {
+        self.ops.push(Op::Push(value));
+    }
+
+    //Pushes the outputs of the call, the first one on top, arguments are evaluated right to left
+    fn call(&mut self, name: &str, args: &'a [YulExpression]) -> usize {
+        if let Some((opcode, inputs, outputs)) = builtin(name) {
+            assert_eq!(args.len(), inputs, "{} takes {} arguments", name, inputs);
+            for arg in args.iter().rev() {
+                self.expression(arg);
+            }
+            self.ops.push(Op::Code(opcode));
+            return outputs;
+        }
+        let (entry, params, returns) = match self.functions.get(name) {
+            Some(f) => (f.entry, f.params, f.returns),
+            None => panic!("unknown function {}", name),
+        };
+        assert_eq!(args.len(), params, "{} takes {} arguments", name, params);
+        let back = self.label();
+        self.ops.push(Op::PushLabel(back));
+        for arg in args.iter().rev() {
+            self.expression(arg);
+        }
+        self.jump(entry);
+        self.ops.push(Op::Label(back));
+        returns
+    }
+
+    fn expression(&mut self, expr: &'a YulExpression) {
+        match expr {
+            YulExpression::BoolLiteral(_, value, _) => self.literal(U256::from(*value as u8)),
+            YulExpression::NumberLiteral(_, digits, exponent, _) => {
+                assert!(exponent.is_empty(), "number literal with an exponent");
+                self.literal(U256::from_str_radix(digits, 10).unwrap());
+            }
+            YulExpression::HexNumberLiteral(_, hex, _) => {
+                self.literal(U256::from_str_radix(hex.trim_start_matches("0x"), 16).unwrap());
+            }
+            YulExpression::Variable(id) => {
+                let slot = self.lookup(&id.name);
+                self.push(slot);
+                self.ops.push(Op::Code(MLOAD));
+            }
+            YulExpression::FunctionCall(call) => {
+                assert_eq!(self.call(&call.id.name, &call.arguments), 1, "{} is not one value", call.id.name);
+            }
+            _ => panic!("unsupported expression {:?}", expr),
+        }
+    }
+
+    //Functions are visible in their whole block, they are compiled after the code of the contract
+    fn block(&mut self, block: &'a YulBlock) {
+        for statement in block.statements.iter() {
+            if let YulStatement::FunctionDefinition(f) = statement {
+                let f = f.as_ref();
+                let entry = self.label();
+                let function = Function {
+                    entry,
+                    params: f.params.len(),
+                    returns: f.returns.len(),
+                };
+                assert!(self.functions.insert(f.id.name.clone(), function).is_none(), "{} is defined twice", f.id.name);
+                self.pending.push((entry, f));
+            }
+        }
+        self.scopes.push(HashMap::new());
+        for statement in block.statements.iter() {
+            self.statement(statement);
+        }
+        self.scopes.pop();
+    }
+
+    fn statement(&mut self, statement: &'a YulStatement) {
+        match statement {
+            YulStatement::VariableDeclaration(_, ids, value) => {
+                match value {
+                    Some(value) => self.values(value, ids.len()),
+                    None => (0..ids.len()).for_each(|_| self.literal(U256::ZERO)),
+                }
+                let slots: Vec<usize> = ids.iter().map(|id| self.slot(&id.id.name)).collect();
+                self.store(&slots);
+            }
+            YulStatement::Assign(_, vars, value) => {
+                self.values(value, vars.len());
+                let slots: Vec<usize> = vars
+                    .iter()
+                    .map(|var| match var {
+                        YulExpression::Variable(id) => self.lookup(&id.name),
+                        _ => panic!("assignment to {:?}", var),
+                    })
+                    .collect();
+                self.store(&slots);
+            }
+            YulStatement::If(_, condition, body) => {
+                let end = self.label();
+                self.expression(condition);
+                self.ops.push(Op::Code(ISZERO));
+                self.ops.push(Op::PushLabel(end));
+                self.ops.push(Op::Code(JUMPI));
+                self.block(body);
+                self.ops.push(Op::Label(end));
+            }
+            YulStatement::For(f) => {
+                let (start, next, end) = (self.label(), self.label(), self.label());
+                //variables of the init block are visible in the whole loop
+                self.scopes.push(HashMap::new());
+                for statement in f.init_block.statements.iter() {
+                    self.statement(statement);
+                }
+                self.ops.push(Op::Label(start));
+                self.expression(&f.condition);
+                self.ops.push(Op::Code(ISZERO));
+                self.ops.push(Op::PushLabel(end));
+                self.ops.push(Op::Code(JUMPI));
+                self.loops.push((next, end));
+                self.block(&f.execution_block);
+                self.loops.pop();
+                self.ops.push(Op::Label(next));
+                self.block(&f.post_block);
+                self.jump(start);
+                self.ops.push(Op::Label(end));
+                self.scopes.pop();
+            }
+            YulStatement::Break(_) => {
+                let (_, end) = *self.loops.last().expect("break outside of a loop");
+                self.jump(end);
+            }
+            YulStatement::Continue(_) => {
+                let (next, _) = *self.loops.last().expect("continue outside of a loop");
+                self.jump(next);
+            }
+            YulStatement::Leave(_) => {
+                let exit = self.exit.expect("leave outside of a function");
+                self.jump(exit);
+            }
+            YulStatement::Block(block) => self.block(block),
+            YulStatement::FunctionDefinition(_) => {}
+            YulStatement::FunctionCall(call) => {
+                assert_eq!(self.call(&call.id.name, &call.arguments), 0, "{} returns values", call.id.name);
+            }
+            _ => panic!("unsupported statement {:?}", statement),
+        }
+    }
+
+    fn values(&mut self, value: &'a YulExpression, count: usize) {
+        match value {
+            YulExpression::FunctionCall(call) => {
+                assert_eq!(self.call(&call.id.name, &call.arguments), count, "{} values expected", count);
+            }
+            _ => {
+                assert_eq!(count, 1, "{} values expected", count);
+                self.expression(value);
+            }
+        }
+    }
+
+    //On entry the stack holds the return address then the arguments, the first one on top
+    fn function(&mut self, entry: usize, f: &'a YulFunctionDefinition) {
+        let loops = std::mem::take(&mut self.loops);
+        let scopes = std::mem::replace(&mut self.scopes, vec![HashMap::new()]);
+        let exit = self.label();
+        let outer_exit = self.exit.replace(exit);
+
+        self.ops.push(Op::Label(entry));
+        let params: Vec<usize> = f.params.iter().map(|id| self.slot(&id.id.name)).collect();
+        self.store(&params);
+        let back = VARIABLES + 0x20 * self.slots;
+        self.slots += 1;
+        self.store(&[back]);
+        let returns: Vec<usize> = f.returns.iter().map(|id| self.slot(&id.id.name)).collect();
+        for &slot in returns.iter() {
+            self.literal(U256::ZERO);
+            self.store(&[slot]);
+        }
+        self.block(&f.body);
+
+        self.ops.push(Op::Label(exit));
+        for &slot in returns.iter().rev() {
+            self.push(slot);
+            self.ops.push(Op::Code(MLOAD));
+        }
+        self.push(back);
+        self.ops.push(Op::Code(MLOAD));
+        self.ops.push(Op::Code(JUMP));
+
+        self.exit = outer_exit;
+        self.scopes = scopes;
+        self.loops = loops;
+    }
+
+    //Labels are pushed with PUSH2, so their offsets are known before the code is laid out
+    fn assemble(&self) -> Vec<u8> {
+        let push_bytes = |value: &U256| {
+            let bytes: [u8; 32] = value.to_be_bytes();
+            let skip = bytes.iter().take_while(|&&b| b == 0).count().min(31);
+            bytes[skip..].to_vec()
+        };
+        let mut offsets = vec![0; self.labels];
+        let mut len = 0;
+        for op in self.ops.iter() {
+            len += match op {
+                Op::Code(_) => 1,
+                Op::Push(value) => 1 + push_bytes(value).len(),
+                Op::Label(label) => {
+                    offsets[*label] = len;
+                    1
+                }
+                Op::PushLabel(_) => 3,
+            };
+        }
+        assert!(len <= u16::MAX as usize, "code too large for PUSH2 labels");
+        let mut code = Vec::with_capacity(len);
+        for op in self.ops.iter() {
+            match op {
+                Op::Code(opcode) => code.push(*opcode),
+                Op::Push(value) => {
+                    let bytes = push_bytes(value);
+                    code.push(PUSH1 - 1 + bytes.len() as u8);
+                    code.extend(bytes);
+                }
+                Op::Label(_) => code.push(JUMPDEST),
+                Op::PushLabel(label) => {
+                    code.push(PUSH2);
+                    code.extend((offsets[*label] as u16).to_be_bytes());
+                }
+            }
+        }
+        code
+    }
+}
+
+//Runtime code of the contract in source, a single function whose body is one assembly block
+pub fn compile(source: &str, selector: [u8; 4]) -> Vec<u8> {
+    let (unit, _) = solang_parser::parse(source, 0).expect("contract does not parse");
+    let mut bodies = unit
+        .0
+        .iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ContractDefinition(contract) => Some(contract),
+            _ => None,
+        })
+        .flat_map(|contract| contract.parts.iter())
+        .filter_map(|part| match part {
+            ContractPart::FunctionDefinition(f) => f.body.as_ref(),
+            _ => None,
+        });
+    let block = match (bodies.next(), bodies.next()) {
+        (Some(Statement::Block { statements, .. }), None) => match &statements[..] {
+            [Statement::Assembly { block, .. }] => block,
+            _ => panic!("function body is not one assembly block"),
+        },
+        _ => panic!("contract has not one function"),
+    };
+
+    let mut compiler = Compiler::default();
+    let revert = compiler.label();
+    //selector of the function then no value
+    compiler.literal(U256::from(4));
+    compiler.ops.extend([Op::Code(0x36), Op::Code(0x10), Op::PushLabel(revert), Op::Code(JUMPI)]);
+    compiler.literal(U256::ZERO);
+    compiler.ops.push(Op::Code(0x35));
+    compiler.literal(U256::from(0xe0));
+    compiler.ops.push(Op::Code(0x1c));
+    compiler.literal(U256::from(u32::from_be_bytes(selector)));
+    compiler.ops.extend([Op::Code(0x14), Op::Code(ISZERO), Op::PushLabel(revert), Op::Code(JUMPI)]);
+    compiler.ops.extend([Op::Code(0x34), Op::PushLabel(revert), Op::Code(JUMPI)]);
+
+    compiler.block(block);
+
+    //false when the block falls through
+    compiler.literal(U256::ZERO);
+    compiler.literal(U256::ZERO);
+    compiler.ops.push(Op::Code(MSTORE));
+    compiler.literal(U256::from(0x20));
+    compiler.literal(U256::ZERO);
+    compiler.ops.push(Op::Code(0xf3));
+    compiler.ops.push(Op::Label(revert));
+    compiler.literal(U256::ZERO);
+    compiler.literal(U256::ZERO);
+    compiler.ops.push(Op::Code(0xfd));
+    while let Some((entry, f)) = compiler.pending.pop() {
+        compiler.function(entry, f);
+    }
+    compiler.assemble()
+}
+
+//Creation code returning runtime: codecopy(0, 13, len) then return(0, len)
+fn init_code(runtime: &[u8]) -> Vec<u8> {
+    let len = (runtime.len() as u16).to_be_bytes();
+    let mut code = vec![PUSH2, len[0], len[1], 0x80, PUSH2, 0x00, 13, PUSH1, 0x00, 0x39, PUSH1, 0x00, 0xf3];
+    code.extend(runtime);
+    code
+}
+
+//Deploys runtime with a create transaction then calls it with each calldata, the output of a
+//call is None when it reverts or halts
+pub fn deploy_and_call(runtime: &[u8], calls: &[Vec<u8>]) -> Vec<Option<Vec<u8>>> {
+    let mut evm = Evm::builder().with_db(InMemoryDB::default()).build();
+    evm.tx_mut().caller = Address::with_last_byte(1);
+    evm.tx_mut().gas_limit = 30_000_000;
+    evm.tx_mut().transact_to = TransactTo::create();
+    evm.tx_mut().data = init_code(runtime).into();
+    let address = match evm.transact_commit().expect("deployment is not valid") {
+        ExecutionResult::Success {
+            output: Output::Create(code, Some(address)),
+            ..
+        } => {
+            assert_eq!(code.as_ref(), runtime);
+            address
+        }
+        result => panic!("deployment failed: {:?}", result),
+    };
+    calls
+        .iter()
+        .map(|calldata| {
+            evm.tx_mut().transact_to = TransactTo::Call(address);
+            evm.tx_mut().data = calldata.clone().into();
+            match evm.transact_commit().expect("call is not valid") {
+                ExecutionResult::Success { output, .. } => Some(output.into_data().to_vec()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{compile, deploy_and_call};
+
+    const CONTRACT: &str = r#"
+contract Sum {
+    function sum(uint256) external pure returns (uint256) {
+        assembly {
+            function split(x) -> low, high {
+                low := and(x, 0xff)
+                high := shr(8, x)
+            }
+
+            // sum of the odd bytes of x below the first zero byte, recursion free
+            function oddSum(x) -> s {
+                for { let rest := x } 1 { } {
+                    let low, high := split(rest)
+                    if iszero(low) {
+                        leave
+                    }
+                    rest := high
+                    if iszero(mod(low, 2)) {
+                        continue
+                    }
+                    s := add(s, low)
+                }
+            }
+
+            let n := calldataload(4)
+            if eq(n, 7) {
+                revert(0, 0)
+            }
+            mstore(0x00, oddSum(n))
+            if gt(n, 0xffff) {
+                return(0x00, 0x20)
+            }
+            for { let i := 0 } 1 { i := add(i, 1) } {
+                if eq(i, 3) {
+                    break
+                }
+                mstore(0x00, add(mload(0x00), oddSum(i)))
+            }
+            return(0x00, 0x20)
+        }
+    }
+}
+"#;
+
+    fn word(value: u64) -> Vec<u8> {
+        let mut word = vec![0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_compile() {
+        let selector = [1, 2, 3, 4];
+        let runtime = compile(CONTRACT, selector);
+        let call = |n: u64| [selector.to_vec(), word(n)].concat();
+        let outputs = deploy_and_call(
+            &runtime,
+            &[
+                call(0x0305),
+                call(0x00_03_04_05_0b_01_00_00),
+                call(0x0300_0105),
+                call(7),
+                [[1, 2, 3, 5].to_vec(), word(0x0305)].concat(),
+                selector.to_vec(),
+            ],
+        );
+        //oddSum(1) + oddSum(2) = 1 is added below 0x10000
+        assert_eq!(outputs[0], Some(word(0x03 + 0x05 + 1)));
+        assert_eq!(outputs[1], Some(word(0)));
+        assert_eq!(outputs[2], Some(word(0x05 + 0x01)));
+        assert_eq!(outputs[3], None);
+        assert_eq!(outputs[4], None);
+        //calldataload reads zeros past the end
+        assert_eq!(outputs[5], Some(word(1)));
+    }
+}
@@ -0,0 +1,43 @@
+//Times the arkworks msm, the Pippenger msm and a fixed base table msm on BLS12-381 G1 for 2^k
+//random points and scalars, k defaults to 12 and 16: cargo bench -p pcs [--features parallel] -- 12 16
+use ark_bls12_381::{Fr, G1Projective};
+use ark_ec::msm::VariableBaseMSM;
+use ark_ec::ProjectiveCurve;
+use ark_ff::{PrimeField, UniformRand};
+use pcs::pippenger::{self, FixedBaseTable};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+
+fn bench(log_size: usize) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let n = 1 << log_size;
+    let bases: Vec<_> = (0..n).map(|_| G1Projective::rand(&mut rng)).collect();
+    let bases = G1Projective::batch_normalization_into_affine(&bases);
+    let scalars: Vec<_> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+    println!("2^{} points", log_size);
+
+    let start = Instant::now();
+    let reprs: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+    let expected = VariableBaseMSM::multi_scalar_mul(&bases, &reprs);
+    println!("  arkworks   {:>10.2?}", start.elapsed());
+    let start = Instant::now();
+    assert_eq!(pippenger::msm(&bases, &scalars), expected);
+    println!("  pippenger  {:>10.2?}", start.elapsed());
+    let start = Instant::now();
+    let table = FixedBaseTable::new(&bases, 16);
+    println!("  table      {:>10.2?}", start.elapsed());
+    let start = Instant::now();
+    assert_eq!(table.msm(&scalars), expected);
+    println!("  table msm  {:>10.2?}", start.elapsed());
+}
+
+fn main() {
+    let mut sizes: Vec<usize> = std::env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+    if sizes.is_empty() {
+        sizes = vec![12, 16];
+    }
+    for log_size in sizes {
+        bench(log_size);
+    }
+}
@@ -0,0 +1,18 @@
+use ark_ff::PrimeField;
+
+//One value of a commitment or an opening proof as a transcript absorbs it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Element<F: PrimeField> {
+    //big endian affine coordinates x and y, see CurveAffine::coordinates
+    Point(Vec<u8>, Vec<u8>),
+
+    Scalar(F),
+
+    Digest([u8; 32]),
+}
+
+//Commitments and opening proofs as a sequence of elements. Transcripts that a verifier in another
+//language, a contract or a circuit reproduces absorb these instead of the arkworks serialization.
+pub trait Absorb<F: PrimeField> {
+    fn elements(&self) -> Vec<Element<F>>;
+}
@@ -0,0 +1,248 @@
+use crate::error::Error;
+use crate::kzg10::UniversalParams;
+use crate::{powers, CurveAffine};
+use ark_ec::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve, SWModelParameters};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2b, Digest};
+use rand::RngCore;
+
+const PROTOCOL_NAME: &[u8] = b"plonk srs contribution";
+
+//Contribution of a secret s to an updatable KZG10 ceremony, beta becomes beta * s.
+//s_g and s_h are g^s and h^s, (pok_commit, pok_response) is a Schnorr proof of knowledge
+//of s bound to the powers g^beta before and after the update.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContributionProof<E: PairingEngine> {
+    pub beta_g: E::G1Affine,
+
+    pub s_g: E::G1Affine,
+
+    pub s_h: E::G2Affine,
+
+    pub pok_commit: E::G1Affine,
+
+    pub pok_response: E::Fr,
+}
+
+//Srs with beta = 1 on the generators, the first contribution makes the trapdoor unknown
+pub fn initial_params<E, P1, P2>(max_degree: usize) -> Result<UniversalParams<E>, Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+{
+    if max_degree < 1 {
+        return Err(Error::DegreeIsZero);
+    }
+    Ok(UniversalParams {
+        powers_of_g: vec![GroupAffine::prime_subgroup_generator(); max_degree + 1],
+        h: GroupAffine::prime_subgroup_generator(),
+        beta_h: GroupAffine::prime_subgroup_generator(),
+    })
+}
+
+//Mixes a secret sampled from rng into params, the secret is dropped when it returns
+pub fn contribute<E, P1, P2, R>(
+    params: &UniversalParams<E>,
+    rng: &mut R,
+) -> Result<(UniversalParams<E>, ContributionProof<E>), Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+    R: RngCore,
+{
+    if params.max_degree()? < 1 {
+        return Err(Error::DegreeIsZero);
+    }
+    let mut s = E::Fr::rand(rng);
+    while s.is_zero() {
+        s = E::Fr::rand(rng);
+    }
+    let powers_of_g: Vec<_> = params
+        .powers_of_g
+        .iter()
+        .zip(powers(s, params.powers_of_g.len()))
+        .map(|(g, pow)| g.mul(pow))
+        .collect();
+    let updated = UniversalParams {
+        powers_of_g: GroupProjective::batch_normalization_into_affine(&powers_of_g),
+        h: params.h,
+        beta_h: params.beta_h.mul(s).into_affine(),
+    };
+
+    let g = params.powers_of_g[0];
+    let r = E::Fr::rand(rng);
+    let mut proof = ContributionProof {
+        beta_g: updated.powers_of_g[1],
+        s_g: g.mul(s).into_affine(),
+        s_h: params.h.mul(s).into_affine(),
+        pok_commit: g.mul(r).into_affine(),
+        pok_response: E::Fr::zero(),
+    };
+    proof.pok_response = r + pok_challenge(&params.powers_of_g[1], &proof) * s;
+    Ok((updated, proof))
+}
+
+//Checks that next is params updated by the contribution proof was made for
+pub fn verify_contribution<E, P1, P2>(
+    params: &UniversalParams<E>,
+    next: &UniversalParams<E>,
+    proof: &ContributionProof<E>,
+) -> Result<(), Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+{
+    verify_chain(params, &[*proof], next)
+}
+
+//Checks that every contribution of proofs updates the powers g^beta left by the one before,
+//starting from initial and ending at last. Only the end points of the chain need the full params.
+pub fn verify_chain<E, P1, P2>(
+    initial: &UniversalParams<E>,
+    proofs: &[ContributionProof<E>],
+    last: &UniversalParams<E>,
+) -> Result<(), Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+{
+    initial.check()?;
+    last.check()?;
+    if initial.max_degree()? < 1 {
+        return Err(Error::DegreeIsZero);
+    }
+    if initial.max_degree() != last.max_degree()
+        || initial.powers_of_g[0] != last.powers_of_g[0]
+        || initial.h != last.h
+    {
+        return Err(Error::InconsistentSrs);
+    }
+    let (g, h) = (initial.powers_of_g[0], initial.h);
+    let mut beta_g = initial.powers_of_g[1];
+    for (i, proof) in proofs.iter().enumerate() {
+        let points_valid = [proof.beta_g, proof.s_g, proof.pok_commit]
+            .iter()
+            .all(|p| !p.is_zero() && p.is_valid())
+            && !proof.s_h.is_zero()
+            && proof.s_h.is_valid();
+        //s_g and s_h share s, beta_g is the previous beta_g raised to it
+        let valid = points_valid
+            && same_ratio::<E>((g, proof.s_g), (h, proof.s_h))
+            && same_ratio::<E>((beta_g, proof.beta_g), (h, proof.s_h))
+            && g.mul(proof.pok_response)
+                == proof.pok_commit.into_projective() + proof.s_g.mul(pok_challenge(&beta_g, proof));
+        if !valid {
+            return Err(Error::InvalidContribution(i));
+        }
+        beta_g = proof.beta_g;
+    }
+    if beta_g != last.powers_of_g[1] {
+        return Err(Error::InvalidContribution(proofs.len()));
+    }
+    Ok(())
+}
+
+//e(a.0, b.1) = e(a.1, b.0), i.e. a.1 / a.0 and b.1 / b.0 have the same discrete log
+fn same_ratio<E: PairingEngine>(a: (E::G1Affine, E::G1Affine), b: (E::G2Affine, E::G2Affine)) -> bool {
+    E::product_of_pairings(&[(a.0.into(), b.1.into()), ((-a.1).into(), b.0.into())]).is_one()
+}
+
+//Fiat-Shamir challenge of the Schnorr proof, bound to the powers g^beta it updates
+fn pok_challenge<E: PairingEngine>(prev_beta_g: &E::G1Affine, proof: &ContributionProof<E>) -> E::Fr {
+    let mut bytes = PROTOCOL_NAME.to_vec();
+    prev_beta_g.serialize(&mut bytes).unwrap();
+    proof.beta_g.serialize(&mut bytes).unwrap();
+    proof.s_g.serialize(&mut bytes).unwrap();
+    proof.s_h.serialize(&mut bytes).unwrap();
+    proof.pok_commit.serialize(&mut bytes).unwrap();
+    E::Fr::from_le_bytes_mod_order(&Blake2b::digest(&bytes))
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::ceremony::{contribute, initial_params, verify_chain, verify_contribution, ContributionProof};
+    use crate::error::Error;
+    use crate::kzg10::{UniversalParams, KZG10};
+    use crate::PolynomialCommitment;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{Polynomial, UVPolynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::fs::{self, File};
+
+    #[test]
+    fn test_ceremony() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let dir = std::env::temp_dir().join(format!("plonk-ceremony-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        //every participant reads the latest srs file and writes the next srs and its proof
+        let initial = initial_params::<Bls12_381, _, _>(8).unwrap();
+        initial.serialize(File::create(dir.join("srs_0")).unwrap()).unwrap();
+        for i in 0..3 {
+            let params = UniversalParams::<Bls12_381>::deserialize(File::open(dir.join(format!("srs_{}", i))).unwrap())
+                .unwrap();
+            let (next, proof) = contribute(&params, &mut rng).unwrap();
+            assert_eq!(verify_contribution(&params, &next, &proof), Ok(()));
+            next.serialize(File::create(dir.join(format!("srs_{}", i + 1))).unwrap()).unwrap();
+            proof.serialize(File::create(dir.join(format!("proof_{}", i))).unwrap()).unwrap();
+        }
+
+        let read_params = |i: usize| {
+            UniversalParams::<Bls12_381>::deserialize(File::open(dir.join(format!("srs_{}", i))).unwrap()).unwrap()
+        };
+        let proofs: Vec<_> = (0..3)
+            .map(|i| {
+                ContributionProof::<Bls12_381>::deserialize(File::open(dir.join(format!("proof_{}", i))).unwrap())
+                    .unwrap()
+            })
+            .collect();
+        let (initial, last) = (read_params(0), read_params(3));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(verify_chain(&initial, &proofs, &last), Ok(()));
+        assert_eq!(verify_chain(&initial, &[], &initial), Ok(()));
+
+        //the final srs is a working KZG10 setup
+        let (ck, vk) = KZG10::<Bls12_381>::trim(&last, 8).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(8, &mut rng);
+        let commit = KZG10::commit(&ck, &poly).unwrap();
+        let point = Fr::from(5u32);
+        let proof = KZG10::open(&ck, &poly, point).unwrap();
+        assert!(KZG10::verify(&vk, &commit, point, poly.evaluate(&point), &proof));
+
+        let mut swapped = proofs.clone();
+        swapped.swap(1, 2);
+        assert_eq!(verify_chain(&initial, &swapped, &last), Err(Error::InvalidContribution(1)));
+        assert_eq!(verify_chain(&initial, &proofs[..2], &last), Err(Error::InvalidContribution(2)));
+        let mut forged = proofs.clone();
+        forged[0].s_h = proofs[1].s_h;
+        assert_eq!(verify_chain(&initial, &forged, &last), Err(Error::InvalidContribution(0)));
+        let mut forged = proofs.clone();
+        forged[2].pok_response += Fr::from(1u32);
+        assert_eq!(verify_chain(&initial, &forged, &last), Err(Error::InvalidContribution(2)));
+
+        let mut bad_last = last.clone();
+        bad_last.powers_of_g[4] = bad_last.powers_of_g[3];
+        assert_eq!(verify_chain(&initial, &proofs, &bad_last), Err(Error::InconsistentSrs));
+        let (other, _) = contribute(&initial, &mut rng).unwrap();
+        assert_eq!(verify_chain(&initial, &proofs, &other), Err(Error::InvalidContribution(3)));
+        assert_eq!(verify_chain(&initial, &proofs, &last.truncate(4).unwrap()), Err(Error::InconsistentSrs));
+
+        //caller supplied params without powers or with g only are errors, not panics
+        let mut empty = initial.clone();
+        empty.powers_of_g.clear();
+        assert_eq!(verify_chain(&empty, &[], &last), Err(Error::InconsistentSrs));
+        assert_eq!(verify_chain(&initial, &proofs, &empty), Err(Error::InconsistentSrs));
+        assert_eq!(contribute(&empty, &mut rng).unwrap_err(), Error::InconsistentSrs);
+        let g_only = initial.truncate(0).unwrap();
+        assert_eq!(verify_chain(&g_only, &[], &g_only), Err(Error::DegreeIsZero));
+    }
+}
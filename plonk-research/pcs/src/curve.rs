@@ -0,0 +1,84 @@
+use ark_ec::short_weierstrass_jacobian::GroupAffine;
+use ark_ec::{AffineCurve, SWModelParameters};
+use ark_ff::{batch_inversion, Field, One, Zero};
+use ark_serialize::CanonicalSerialize;
+
+//Affine points the schemes commit with. Checking untrusted points and adding points in affine
+//coordinates depend on the curve model, any model implementing this can be committed with.
+pub trait CurveAffine: AffineCurve {
+    //On the curve and in the prime order subgroup
+    fn is_valid(&self) -> bool;
+
+    //points[k] + points[k + 1] for every k of pairs, the additions share one inversion
+    fn batch_add(points: &[Self], pairs: &[usize]) -> Vec<Self>;
+
+    //Big endian affine x and y, (0, 0) for the point at infinity, which is not on a curve with b != 0.
+    //Coordinates in an extension field list the highest coefficient first as the EVM does.
+    fn coordinates(&self) -> (Vec<u8>, Vec<u8>);
+}
+
+impl<P: SWModelParameters> CurveAffine for GroupAffine<P> {
+    //the subgroup check is free with cofactor one
+    fn is_valid(&self) -> bool {
+        let cofactor_one = P::COFACTOR[0] == 1 && P::COFACTOR[1..].iter().all(|c| *c == 0);
+        self.is_on_curve() && (cofactor_one || self.is_in_correct_subgroup_assuming_on_curve())
+    }
+
+    fn batch_add(points: &[Self], pairs: &[usize]) -> Vec<Self> {
+        let two = P::BaseField::one().double();
+        let mut denominators: Vec<_> = pairs
+            .iter()
+            .map(|&k| {
+                let (p, q) = (&points[k], &points[k + 1]);
+                if p.is_zero() || q.is_zero() {
+                    P::BaseField::one()
+                } else if p.x != q.x {
+                    q.x - p.x
+                } else if p.y == q.y && !p.y.is_zero() {
+                    p.y * two
+                } else {
+                    //p = -q, the sum is zero
+                    P::BaseField::one()
+                }
+            })
+            .collect();
+        batch_inversion(&mut denominators);
+        pairs
+            .iter()
+            .zip(denominators)
+            .map(|(&k, inv)| {
+                let (p, q) = (&points[k], &points[k + 1]);
+                let lambda = if p.is_zero() {
+                    return *q;
+                } else if q.is_zero() {
+                    return *p;
+                } else if p.x != q.x {
+                    (q.y - p.y) * inv
+                } else if p.y == q.y && !p.y.is_zero() {
+                    //tangent slope (3x^2 + a) / 2y
+                    (p.x.square() * (two + P::BaseField::one()) + P::COEFF_A) * inv
+                } else {
+                    return GroupAffine::zero();
+                };
+                let x = lambda.square() - p.x - q.x;
+                let y = lambda * (p.x - x) - p.y;
+                GroupAffine::new(x, y, false)
+            })
+            .collect()
+    }
+
+    //field elements serialize little endian, coefficients in ascending order
+    fn coordinates(&self) -> (Vec<u8>, Vec<u8>) {
+        if self.is_zero() {
+            let len = P::BaseField::zero().serialized_size();
+            return (vec![0; len], vec![0; len]);
+        }
+        let big_endian = |c: &P::BaseField| {
+            let mut bytes = vec![];
+            c.serialize(&mut bytes).unwrap();
+            bytes.reverse();
+            bytes
+        };
+        (big_endian(&self.x), big_endian(&self.y))
+    }
+}
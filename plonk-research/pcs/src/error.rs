@@ -0,0 +1,113 @@
+use ark_serialize::SerializationError;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    //setup needs at least one power besides g
+    DegreeIsZero,
+
+    //trim to a degree the universal params do not support
+    TrimmingDegreeTooLarge { degree: usize, max_degree: usize },
+
+    //polynomial has more coefficients than the committer key has powers
+    TooManyCoefficients { num_coefficients: usize, num_powers: usize },
+
+    //blowup factor and folding arity must be powers of two at least 2, with at least one query
+    InvalidFriConfig {
+        blowup_factor: usize,
+        num_queries: usize,
+        folding_arity: usize,
+    },
+
+    //no radix 2 domain of this size in the field
+    InvalidDomainSize(usize),
+
+    //committing evaluations needs one per element of the domain
+    WrongEvaluationCount { num_evals: usize, domain_size: usize },
+
+    //ceremony file is truncated or does not follow its format
+    MalformedSrsFile(&'static str),
+
+    //ceremony point is off the curve or outside the prime order subgroup
+    InvalidSrsPoint,
+
+    //consecutive powers of the srs do not have the same ratio
+    InconsistentSrs,
+
+    //contribution at this position of a ceremony does not update the srs before it,
+    //the position after the last contribution means the final srs does not match the chain
+    InvalidContribution(usize),
+
+    //encoded key or proof has a wrong header, is truncated or has bytes left over
+    MalformedEncoding(&'static str),
+
+    //encoded with a format version this build does not read
+    UnsupportedVersion(u8),
+
+    //decoded point is off the curve or outside the prime order subgroup,
+    //or the decoded key or proof is not well formed
+    InvalidEncodedData,
+
+    //multi point opening of a claim at a point set that repeats a point
+    DuplicateOpeningPoint,
+
+    //batch to prove is empty, or its circuits and proving keys do not pair up
+    InvalidBatch(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DegreeIsZero => write!(f, "degree of setup must be positive"),
+            Error::TrimmingDegreeTooLarge { degree, max_degree } => write!(
+                f,
+                "trim degree {} is larger than the max degree {} of setup",
+                degree, max_degree
+            ),
+            Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            } => write!(
+                f,
+                "polynomial has {} coefficients, but committer key only has {} powers",
+                num_coefficients, num_powers
+            ),
+            Error::InvalidFriConfig {
+                blowup_factor,
+                num_queries,
+                folding_arity,
+            } => write!(
+                f,
+                "invalid fri config with blowup factor {}, {} queries and folding arity {}",
+                blowup_factor, num_queries, folding_arity
+            ),
+            Error::InvalidDomainSize(size) => write!(f, "no evaluation domain of size {}", size),
+            Error::WrongEvaluationCount { num_evals, domain_size } => write!(
+                f,
+                "{} evaluations given for a domain of size {}",
+                num_evals, domain_size
+            ),
+            Error::MalformedSrsFile(reason) => write!(f, "malformed srs file: {}", reason),
+            Error::InvalidSrsPoint => write!(f, "srs point is not in the prime order subgroup"),
+            Error::InconsistentSrs => write!(f, "srs powers do not have the same ratio"),
+            Error::InvalidContribution(index) => write!(f, "invalid srs contribution at position {}", index),
+            Error::MalformedEncoding(reason) => write!(f, "malformed encoding: {}", reason),
+            Error::UnsupportedVersion(version) => write!(f, "unsupported encoding version {}", version),
+            Error::InvalidEncodedData => write!(f, "encoded data is not well formed"),
+            Error::DuplicateOpeningPoint => write!(f, "point set of a claim repeats a point"),
+            Error::InvalidBatch(reason) => write!(f, "invalid batch: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<SerializationError> for Error {
+    fn from(err: SerializationError) -> Self {
+        match err {
+            SerializationError::InvalidData | SerializationError::UnexpectedFlags => Error::InvalidEncodedData,
+            SerializationError::NotEnoughSpace => Error::MalformedEncoding("not enough space"),
+            SerializationError::IoError(_) => Error::MalformedEncoding("truncated data"),
+        }
+    }
+}
@@ -0,0 +1,787 @@
+use crate::error::Error;
+use crate::merkle::{verify_path, Digest, MerkleTree};
+use crate::{Absorb, Element, PolynomialCommitment};
+use ark_ff::{batch_inversion, PrimeField};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain, UVPolynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2b, Digest as _};
+use rand::RngCore;
+use std::marker::PhantomData;
+
+const PROTOCOL_NAME: &[u8] = b"plonk fri pcs";
+
+//collision resistance of the 256 bit merkle hash
+const HASH_SECURITY_BITS: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FriConfig {
+    //codeword length over degree bound, the code rate is 1 / blowup_factor
+    pub blowup_factor: usize,
+
+    //positions the verifier spot checks in every opening
+    pub num_queries: usize,
+
+    //every round folds folding_arity evaluations into one
+    pub folding_arity: usize,
+}
+
+impl Default for FriConfig {
+    //rate 1/8 with 43 queries, 128 conjectured bits
+    fn default() -> Self {
+        Self {
+            blowup_factor: 8,
+            num_queries: 43,
+            folding_arity: 4,
+        }
+    }
+}
+
+impl FriConfig {
+    pub fn new(blowup_factor: usize, num_queries: usize, folding_arity: usize) -> Result<Self, Error> {
+        let config = Self {
+            blowup_factor,
+            num_queries,
+            folding_arity,
+        };
+        config.check()?;
+        Ok(config)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        let valid = |x: usize| x >= 2 && x.is_power_of_two();
+        if valid(self.blowup_factor) && valid(self.folding_arity) && self.num_queries > 0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidFriConfig {
+                blowup_factor: self.blowup_factor,
+                num_queries: self.num_queries,
+                folding_arity: self.folding_arity,
+            })
+        }
+    }
+
+    fn rate_bits(&self) -> usize {
+        self.blowup_factor.trailing_zeros() as usize
+    }
+
+    //A query passes a codeword far from the code with probability about the rate,
+    //conjectured to hold up to the capacity bound
+    pub fn conjectured_security_bits(&self) -> usize {
+        (self.num_queries * self.rate_bits()).min(HASH_SECURITY_BITS)
+    }
+
+    //Provable up to the Johnson bound 1 - sqrt(rate), a query gives half the bits
+    pub fn proven_security_bits(&self) -> usize {
+        (self.num_queries * self.rate_bits() / 2).min(HASH_SECURITY_BITS)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct UniversalParams {
+    pub max_degree: usize,
+
+    pub config: FriConfig,
+}
+
+//Codewords are evaluations over the coset g * H of a Radix2EvaluationDomain H of size
+//degree_bound * blowup_factor, g is the multiplicative generator
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitterKey {
+    //committed polys have at most degree_bound coefficients, a power of two
+    pub degree_bound: usize,
+
+    pub config: FriConfig,
+}
+
+//Codewords are computed from coefficients, so the evaluations over domain are interpolated first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LagrangeKey<F: PrimeField> {
+    pub ck: CommitterKey,
+
+    pub domain: Radix2EvaluationDomain<F>,
+}
+
+impl CommitterKey {
+    pub fn max_degree(&self) -> usize {
+        self.degree_bound - 1
+    }
+}
+
+//The domain is stored as its size and rebuilt on deserialization
+impl<F: PrimeField> CanonicalSerialize for LagrangeKey<F> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.ck.serialize(&mut writer)?;
+        self.domain.size().serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.ck.serialized_size() + self.domain.size().serialized_size()
+    }
+}
+
+impl<F: PrimeField> CanonicalDeserialize for LagrangeKey<F> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let ck = CommitterKey::deserialize(&mut reader)?;
+        let domain_size = usize::deserialize(&mut reader)?;
+        let domain = Radix2EvaluationDomain::new(domain_size)
+            .filter(|domain| domain.size() == domain_size)
+            .ok_or(SerializationError::InvalidData)?;
+        Ok(Self { ck, domain })
+    }
+}
+
+pub type VerifierKey = CommitterKey;
+
+//Merkle roots are not homomorphic, so a combined commitment is the formal linear combination
+//sum scalars[i] * roots[i], a commitment to a single poly has one root and scalar one
+#[derive(Clone, Debug, Default, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<F: PrimeField> {
+    pub roots: Vec<Digest>,
+
+    pub scalars: Vec<F>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct QueryProof<F: PrimeField> {
+    //leaf of every committed codeword at the queried coset and its merkle path
+    pub leaves: Vec<Vec<F>>,
+
+    pub paths: Vec<Vec<Digest>>,
+
+    //leaf of every committed folded layer
+    pub layer_leaves: Vec<Vec<F>>,
+
+    pub layer_paths: Vec<Vec<Digest>>,
+}
+
+//FRI low degree test of the quotient (f(X) - value) / (X - point)
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<F: PrimeField> {
+    //roots of the folded layers, the last layer is sent as final_poly
+    pub layer_roots: Vec<Digest>,
+
+    pub final_poly: Vec<F>,
+
+    pub queries: Vec<QueryProof<F>>,
+}
+
+impl<F: PrimeField> Absorb<F> for Commitment<F> {
+    fn elements(&self) -> Vec<Element<F>> {
+        let roots = self.roots.iter().map(|root| Element::Digest(root.0));
+        roots.chain(self.scalars.iter().map(|s| Element::Scalar(*s))).collect()
+    }
+}
+
+//fields in order, every leaf and path flattened
+impl<F: PrimeField> Absorb<F> for Proof<F> {
+    fn elements(&self) -> Vec<Element<F>> {
+        let digests = |digests: &[Digest]| digests.iter().map(|d| Element::Digest(d.0)).collect::<Vec<_>>();
+        let scalars = |scalars: &[F]| scalars.iter().map(|s| Element::Scalar(*s)).collect::<Vec<_>>();
+        let mut elements = digests(&self.layer_roots);
+        elements.extend(scalars(&self.final_poly));
+        for query in self.queries.iter() {
+            elements.extend(scalars(&query.leaves.concat()));
+            elements.extend(digests(&query.paths.concat()));
+            elements.extend(scalars(&query.layer_leaves.concat()));
+            elements.extend(digests(&query.layer_paths.concat()));
+        }
+        elements
+    }
+}
+
+//Fiat-Shamir by hash chain
+struct FriTranscript(Vec<u8>);
+
+impl FriTranscript {
+    fn new<F: PrimeField>(key: &CommitterKey, commit: &Commitment<F>, point: F, value: F) -> Self {
+        let mut transcript = Self(PROTOCOL_NAME.to_vec());
+        transcript.absorb(key);
+        transcript.absorb(commit);
+        transcript.absorb(&point);
+        transcript.absorb(&value);
+        transcript
+    }
+
+    fn absorb<T: CanonicalSerialize>(&mut self, item: &T) {
+        item.serialize(&mut self.0).unwrap();
+    }
+
+    fn squeeze(&mut self) -> Vec<u8> {
+        self.0 = Blake2b::digest(&self.0).to_vec();
+        self.0.clone()
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        F::from_le_bytes_mod_order(&self.squeeze())
+    }
+
+    //bound is a power of two, so the index is uniform
+    fn index(&mut self, bound: usize) -> usize {
+        let bytes = self.squeeze();
+        u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize % bound
+    }
+}
+
+//Hash based commitment with Reed-Solomon codewords and FRI, transparent and without pairings
+#[derive(Clone, Copy, Debug)]
+pub struct FRI<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField> FRI<F> {
+    pub fn setup_with_config(max_degree: usize, config: FriConfig) -> Result<UniversalParams, Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        config.check()?;
+        Ok(UniversalParams { max_degree, config })
+    }
+
+    fn domain(key: &CommitterKey) -> Radix2EvaluationDomain<F> {
+        Radix2EvaluationDomain::new(key.degree_bound * key.config.blowup_factor).unwrap()
+    }
+
+    //Folds before the layer degree drops below the arity, and the final degree bound
+    fn num_folds(key: &CommitterKey) -> (usize, usize) {
+        let mut degree_bound = key.degree_bound;
+        let mut rounds = 0;
+        while degree_bound >= key.config.folding_arity {
+            degree_bound /= key.config.folding_arity;
+            rounds += 1;
+        }
+        (rounds, degree_bound)
+    }
+
+    //Leaf j holds the coset {j + k * n / arity}, the positions one fold combines
+    fn leaves(codeword: &[F], arity: usize) -> Vec<Vec<F>> {
+        let m = codeword.len() / arity;
+        (0..m)
+            .map(|j| (0..arity).map(|k| codeword[j + k * m]).collect())
+            .collect()
+    }
+
+    //f(X) = sum X^r * f_r(X^arity), fold to sum beta^r * f_r(Y) from the evaluations of f at
+    //x * mu^k, where mu is a primitive arity-th root of unity
+    fn fold(leaf: &[F], x: F, beta: F, mu_inv: F) -> F {
+        let arity = leaf.len();
+        let ratio = beta * x.inverse().unwrap();
+        let mut folded = F::zero();
+        let mut ratio_pow = F::one();
+        let mut mu_inv_pow = F::one();
+        for _ in 0..arity {
+            let mut coeff = F::zero();
+            let mut w = F::one();
+            for v in leaf.iter() {
+                coeff += *v * w;
+                w *= mu_inv_pow;
+            }
+            folded += coeff * ratio_pow;
+            ratio_pow *= ratio;
+            mu_inv_pow *= mu_inv;
+        }
+        folded * F::from(arity as u64).inverse().unwrap()
+    }
+}
+
+impl<F: PrimeField> PolynomialCommitment for FRI<F> {
+    type Field = F;
+
+    type UniversalParams = UniversalParams;
+
+    type CommitterKey = CommitterKey;
+
+    type LagrangeKey = LagrangeKey<F>;
+
+    type VerifierKey = VerifierKey;
+
+    type Commitment = Commitment<F>;
+
+    type Proof = Proof<F>;
+
+    //Transparent with the default config, rng is not used
+    fn setup<R: RngCore>(max_degree: usize, _rng: &mut R) -> Result<UniversalParams, Error> {
+        Self::setup_with_config(max_degree, FriConfig::default())
+    }
+
+    //Every key covers the max degree of the params, so codewords of all keys live on one
+    //domain and their commitments can be combined
+    fn trim(pp: &UniversalParams, degree: usize) -> Result<(CommitterKey, VerifierKey), Error> {
+        if degree > pp.max_degree {
+            return Err(Error::TrimmingDegreeTooLarge {
+                degree,
+                max_degree: pp.max_degree,
+            });
+        }
+        let ck = CommitterKey {
+            degree_bound: (pp.max_degree + 1).next_power_of_two(),
+            config: pp.config,
+        };
+        Ok((ck.clone(), ck))
+    }
+
+    fn commit(ck: &CommitterKey, poly: &DensePolynomial<F>) -> Result<Commitment<F>, Error> {
+        if poly.coeffs.len() > ck.degree_bound {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: poly.coeffs.len(),
+                num_powers: ck.degree_bound,
+            });
+        }
+        let codeword = Self::domain(ck).coset_fft(&poly.coeffs);
+        let tree = MerkleTree::new(&Self::leaves(&codeword, ck.config.folding_arity));
+        Ok(Commitment {
+            roots: vec![tree.root()],
+            scalars: vec![F::one()],
+        })
+    }
+
+    fn lagrange_key(ck: &CommitterKey, domain_size: usize) -> Result<LagrangeKey<F>, Error> {
+        let domain = Radix2EvaluationDomain::new(domain_size).ok_or(Error::InvalidDomainSize(domain_size))?;
+        if domain.size() > ck.degree_bound {
+            return Err(Error::TrimmingDegreeTooLarge {
+                degree: domain.size() - 1,
+                max_degree: ck.max_degree(),
+            });
+        }
+        Ok(LagrangeKey { ck: ck.clone(), domain })
+    }
+
+    fn commit_evals(lk: &LagrangeKey<F>, evals: &[F], blinder: &DensePolynomial<F>) -> Result<Commitment<F>, Error> {
+        if evals.len() != lk.domain.size() {
+            return Err(Error::WrongEvaluationCount {
+                num_evals: evals.len(),
+                domain_size: lk.domain.size(),
+            });
+        }
+        let poly = DensePolynomial::from_coefficients_vec(lk.domain.ifft(evals));
+        Self::commit(&lk.ck, &(&poly + &blinder.mul_by_vanishing_poly(lk.domain)))
+    }
+
+    fn open(ck: &CommitterKey, poly: &DensePolynomial<F>, point: F) -> Result<Proof<F>, Error> {
+        Self::open_combination(ck, &[poly], &[F::one()], point)
+    }
+
+    //The codewords are recomputed from the polys, the verifier reads every committed codeword
+    //at the queried positions and combines them
+    fn open_combination(
+        ck: &CommitterKey,
+        polys: &[&DensePolynomial<F>],
+        scalars: &[F],
+        point: F,
+    ) -> Result<Proof<F>, Error> {
+        assert_eq!(polys.len(), scalars.len());
+        let arity = ck.config.folding_arity;
+        let domain = Self::domain(ck);
+        let n = domain.size();
+        let mut codewords = vec![];
+        let mut trees = vec![];
+        for poly in polys.iter() {
+            if poly.coeffs.len() > ck.degree_bound {
+                return Err(Error::TooManyCoefficients {
+                    num_coefficients: poly.coeffs.len(),
+                    num_powers: ck.degree_bound,
+                });
+            }
+            let codeword = domain.coset_fft(&poly.coeffs);
+            trees.push(MerkleTree::new(&Self::leaves(&codeword, arity)));
+            codewords.push(codeword);
+        }
+        let value = polys
+            .iter()
+            .zip(scalars.iter())
+            .fold(F::zero(), |acc, (p, s)| acc + p.evaluate(&point) * s);
+        let commit = Commitment {
+            roots: trees.iter().map(|tree| tree.root()).collect(),
+            scalars: scalars.to_vec(),
+        };
+        let mut transcript = FriTranscript::new(ck, &commit, point, value);
+
+        //quotient q(X) = (f(X) - value) / (X - point) over the coset
+        let mut offset = F::multiplicative_generator();
+        let mut denoms: Vec<F> = domain.elements().map(|x| offset * x - point).collect();
+        batch_inversion(&mut denoms);
+        let mut layer: Vec<F> = (0..n)
+            .map(|j| {
+                let f = codewords
+                    .iter()
+                    .zip(scalars.iter())
+                    .fold(F::zero(), |acc, (c, s)| acc + c[j] * s);
+                (f - value) * denoms[j]
+            })
+            .collect();
+
+        let (rounds, final_bound) = Self::num_folds(ck);
+        let mut gen = domain.group_gen;
+        let mu_inv = gen.pow([(n / arity) as u64]).inverse().unwrap();
+        let mut layers = vec![];
+        for round in 0..rounds {
+            let beta = transcript.challenge();
+            let m = layer.len() / arity;
+            let mut x = offset;
+            let mut next = Vec::with_capacity(m);
+            for leaf in Self::leaves(&layer, arity) {
+                next.push(Self::fold(&leaf, x, beta, mu_inv));
+                x *= gen;
+            }
+            layer = next;
+            offset = offset.pow([arity as u64]);
+            gen = gen.pow([arity as u64]);
+            if round + 1 < rounds {
+                let tree = MerkleTree::new(&Self::leaves(&layer, arity));
+                transcript.absorb(&tree.root());
+                layers.push((layer.clone(), tree));
+            }
+        }
+
+        //the last layer is a codeword of degree < final_bound over offset * <gen>
+        let final_domain = Radix2EvaluationDomain::<F>::new(layer.len()).unwrap();
+        let mut final_poly = final_domain.ifft(&layer);
+        let offset_inv = offset.inverse().unwrap();
+        let mut offset_pow = F::one();
+        for coeff in final_poly.iter_mut() {
+            *coeff *= offset_pow;
+            offset_pow *= offset_inv;
+        }
+        final_poly.truncate(final_bound);
+        transcript.absorb(&final_poly);
+
+        let mut queries = vec![];
+        for _ in 0..ck.config.num_queries {
+            let index = transcript.index(n / arity);
+            let leaves = codewords
+                .iter()
+                .map(|codeword| (0..arity).map(|k| codeword[index + k * n / arity]).collect())
+                .collect();
+            let paths = trees.iter().map(|tree| tree.open(index)).collect();
+            let mut layer_leaves = vec![];
+            let mut layer_paths = vec![];
+            let mut pos = index;
+            for (values, tree) in layers.iter() {
+                let m = values.len() / arity;
+                let coset = pos % m;
+                layer_leaves.push((0..arity).map(|k| values[coset + k * m]).collect());
+                layer_paths.push(tree.open(coset));
+                pos = coset;
+            }
+            queries.push(QueryProof {
+                leaves,
+                paths,
+                layer_leaves,
+                layer_paths,
+            });
+        }
+        Ok(Proof {
+            layer_roots: layers.iter().map(|(_, tree)| tree.root()).collect(),
+            final_poly,
+            queries,
+        })
+    }
+
+    fn verify(vk: &VerifierKey, commit: &Commitment<F>, point: F, value: F, proof: &Proof<F>) -> bool {
+        let arity = vk.config.folding_arity;
+        let (rounds, final_bound) = Self::num_folds(vk);
+        if commit.roots.len() != commit.scalars.len()
+            || proof.layer_roots.len() != rounds.saturating_sub(1)
+            || proof.final_poly.len() > final_bound
+            || proof.queries.len() != vk.config.num_queries
+        {
+            return false;
+        }
+        let mut transcript = FriTranscript::new(vk, commit, point, value);
+        let mut betas = vec![];
+        for round in 0..rounds {
+            betas.push(transcript.challenge::<F>());
+            if round + 1 < rounds {
+                transcript.absorb(&proof.layer_roots[round]);
+            }
+        }
+        transcript.absorb(&proof.final_poly);
+        let final_poly = DensePolynomial::from_coefficients_slice(&proof.final_poly);
+
+        let domain = Self::domain(vk);
+        let n = domain.size();
+        let m0 = n / arity;
+        let mu = domain.group_gen.pow([m0 as u64]);
+        let mu_inv = mu.inverse().unwrap();
+        for query in proof.queries.iter() {
+            let index = transcript.index(m0);
+            if query.leaves.len() != commit.roots.len()
+                || query.paths.len() != commit.roots.len()
+                || query.layer_leaves.len() != proof.layer_roots.len()
+                || query.layer_paths.len() != proof.layer_roots.len()
+                || query
+                    .leaves
+                    .iter()
+                    .chain(query.layer_leaves.iter())
+                    .any(|leaf| leaf.len() != arity)
+            {
+                return false;
+            }
+            for i in 0..commit.roots.len() {
+                if !verify_path(&commit.roots[i], index, &query.leaves[i], &query.paths[i]) {
+                    return false;
+                }
+            }
+
+            //quotient values at the queried coset from the committed codewords
+            let mut offset = F::multiplicative_generator();
+            let mut gen = domain.group_gen;
+            let x = offset * gen.pow([index as u64]);
+            let mut values = vec![];
+            let mut x_k = x;
+            for k in 0..arity {
+                let f = query
+                    .leaves
+                    .iter()
+                    .zip(commit.scalars.iter())
+                    .fold(F::zero(), |acc, (leaf, s)| acc + leaf[k] * s);
+                let denom = x_k - point;
+                if denom.is_zero() {
+                    return false;
+                }
+                values.push((f - value) * denom.inverse().unwrap());
+                x_k *= mu;
+            }
+            if rounds == 0 {
+                let mut x_k = x;
+                for v in values.iter() {
+                    if final_poly.evaluate(&x_k) != *v {
+                        return false;
+                    }
+                    x_k *= mu;
+                }
+                continue;
+            }
+
+            let mut folded = Self::fold(&values, x, betas[0], mu_inv);
+            let mut pos = index;
+            let mut size = n / arity;
+            offset = offset.pow([arity as u64]);
+            gen = gen.pow([arity as u64]);
+            for (((root, leaf), path), beta) in proof
+                .layer_roots
+                .iter()
+                .zip(query.layer_leaves.iter())
+                .zip(query.layer_paths.iter())
+                .zip(betas[1..].iter())
+            {
+                let m = size / arity;
+                let coset = pos % m;
+                if !verify_path(root, coset, leaf, path) || leaf[pos / m] != folded {
+                    return false;
+                }
+                folded = Self::fold(leaf, offset * gen.pow([coset as u64]), *beta, mu_inv);
+                pos = coset;
+                size = m;
+                offset = offset.pow([arity as u64]);
+                gen = gen.pow([arity as u64]);
+            }
+            if final_poly.evaluate(&(offset * gen.pow([pos as u64]))) != folded {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn combine_commitments(commits: &[Commitment<F>], scalars: &[F]) -> Commitment<F> {
+        let mut combined = Commitment::default();
+        for (commit, s) in commits.iter().zip(scalars.iter()) {
+            combined.roots.extend(commit.roots.iter().cloned());
+            combined.scalars.extend(commit.scalars.iter().map(|c| *c * s));
+        }
+        combined
+    }
+
+    //the prover only sends commitments to single polys
+    fn check_commitment(commit: &Commitment<F>) -> bool {
+        commit.roots.len() == 1 && commit.scalars == [F::one()]
+    }
+
+    fn check_proof(proof: &Proof<F>) -> bool {
+        proof.queries.iter().all(|query| {
+            query.leaves.len() == query.paths.len()
+                && query.layer_leaves.len() == query.layer_paths.len()
+                && query.layer_leaves.len() == proof.layer_roots.len()
+        })
+    }
+
+    fn check_params(pp: &UniversalParams) -> bool {
+        pp.max_degree > 0 && pp.config.check().is_ok()
+    }
+
+    //codewords need a domain of degree_bound * blowup_factor elements in the field
+    fn check_committer_key(ck: &CommitterKey) -> bool {
+        ck.config.check().is_ok()
+            && ck.degree_bound.is_power_of_two()
+            && ck
+                .degree_bound
+                .checked_mul(ck.config.blowup_factor)
+                .and_then(Radix2EvaluationDomain::<F>::new)
+                .is_some()
+    }
+
+    fn check_lagrange_key(lk: &LagrangeKey<F>) -> bool {
+        Self::check_committer_key(&lk.ck) && lk.domain.size() <= lk.ck.degree_bound
+    }
+
+    fn check_verifier_key(vk: &VerifierKey) -> bool {
+        Self::check_committer_key(vk)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::error::Error;
+    use crate::fri::{FriConfig, LagrangeKey, FRI};
+    use crate::PolynomialCommitment;
+    use ark_bls12_381::Fr;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{Polynomial, UVPolynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    type Fri = FRI<Fr>;
+
+    #[test]
+    fn test_commit_open_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for (max_degree, config) in [
+            (15, FriConfig::default()),
+            (31, FriConfig::new(2, 16, 2).unwrap()),
+            (7, FriConfig::new(4, 8, 8).unwrap()),
+        ] {
+            let pp = Fri::setup_with_config(max_degree, config).unwrap();
+            let (ck, vk) = Fri::trim(&pp, max_degree).unwrap();
+            assert_eq!(ck.max_degree(), max_degree);
+
+            for degree in [0, 1, max_degree / 2, max_degree] {
+                let poly = DensePolynomial::<Fr>::rand(degree, &mut rng);
+                let commit = Fri::commit(&ck, &poly).unwrap();
+                assert!(Fri::check_commitment(&commit));
+                let point = Fr::rand(&mut rng);
+                let proof = Fri::open(&ck, &poly, point).unwrap();
+                assert!(Fri::check_proof(&proof));
+                assert_eq!(proof.queries.len(), config.num_queries);
+                let value = poly.evaluate(&point);
+                assert!(Fri::verify(&vk, &commit, point, value, &proof));
+                assert!(!Fri::verify(&vk, &commit, point, value + Fr::one(), &proof));
+                //a constant opens to the same value everywhere
+                assert_eq!(
+                    Fri::verify(&vk, &commit, point + Fr::one(), value, &proof),
+                    degree == 0
+                );
+
+                let mut bad_proof = proof.clone();
+                bad_proof.final_poly[0] += Fr::one();
+                assert!(!Fri::verify(&vk, &commit, point, value, &bad_proof));
+                let mut bad_proof = proof;
+                bad_proof.queries[0].leaves[0][0] += Fr::one();
+                assert!(!Fri::verify(&vk, &commit, point, value, &bad_proof));
+            }
+        }
+    }
+
+    #[test]
+    fn test_open_combination() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let pp = Fri::setup(16, &mut rng).unwrap();
+        let (ck, vk) = Fri::trim(&pp, 16).unwrap();
+        let polys: Vec<_> = (1..6)
+            .map(|degree| DensePolynomial::<Fr>::rand(degree * 3, &mut rng))
+            .collect();
+        let commits: Vec<_> = polys.iter().map(|p| Fri::commit(&ck, p).unwrap()).collect();
+        let point = Fr::rand(&mut rng);
+        let challenge = Fr::rand(&mut rng);
+        let values: Vec<_> = polys.iter().map(|p| p.evaluate(&point)).collect();
+        let poly_refs: Vec<_> = polys.iter().collect();
+        let proof = Fri::batch_open(&ck, &poly_refs, point, challenge).unwrap();
+        assert!(Fri::batch_verify(&vk, &commits, point, &values, &proof, challenge));
+
+        let mut bad_values = values.clone();
+        bad_values.swap(0, 1);
+        assert!(!Fri::batch_verify(&vk, &commits, point, &bad_values, &proof, challenge));
+        let mut bad_commits = commits.clone();
+        bad_commits.swap(0, 1);
+        assert!(!Fri::batch_verify(&vk, &bad_commits, point, &values, &proof, challenge));
+
+        //a combined commitment is not a commitment sent by the prover
+        let combined = Fri::combine_commitments(&commits, &crate::powers(challenge, commits.len()));
+        assert_eq!(combined.roots.len(), commits.len());
+        assert!(!Fri::check_commitment(&combined));
+
+        //a poly over the degree bound fails the low degree test
+        let high = DensePolynomial::<Fr>::rand(40, &mut rng);
+        let (big_ck, _) = Fri::trim(&Fri::setup(40, &mut rng).unwrap(), 40).unwrap();
+        let mut proof = Fri::open(&big_ck, &high, point).unwrap();
+        proof.final_poly.truncate(1);
+        assert!(!Fri::verify(&vk, &Fri::commit(&big_ck, &high).unwrap(), point, high.evaluate(&point), &proof));
+    }
+
+    #[test]
+    fn test_security_bits() {
+        let config = FriConfig::default();
+        assert_eq!(config.conjectured_security_bits(), 128);
+        assert_eq!(config.proven_security_bits(), 64);
+        let config = FriConfig::new(4, 30, 2).unwrap();
+        assert_eq!(config.conjectured_security_bits(), 60);
+        assert_eq!(config.proven_security_bits(), 30);
+        assert_eq!(FriConfig::new(16, 100, 2).unwrap().conjectured_security_bits(), 128);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(Fri::setup(0, &mut rng).unwrap_err(), Error::DegreeIsZero);
+        for (blowup_factor, num_queries, folding_arity) in [(3, 8, 2), (1, 8, 2), (4, 0, 2), (4, 8, 6)] {
+            assert_eq!(
+                FriConfig::new(blowup_factor, num_queries, folding_arity).unwrap_err(),
+                Error::InvalidFriConfig {
+                    blowup_factor,
+                    num_queries,
+                    folding_arity
+                }
+            );
+        }
+        let pp = Fri::setup(3, &mut rng).unwrap();
+        assert_eq!(
+            Fri::trim(&pp, 4).unwrap_err(),
+            Error::TrimmingDegreeTooLarge {
+                degree: 4,
+                max_degree: 3
+            }
+        );
+        let (ck, _) = Fri::trim(&pp, 1).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(4, &mut rng);
+        assert_eq!(
+            Fri::commit(&ck, &poly).unwrap_err(),
+            Error::TooManyCoefficients {
+                num_coefficients: 5,
+                num_powers: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_key_serialization() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let pp = Fri::setup(15, &mut rng).unwrap();
+        let (ck, vk) = Fri::trim(&pp, 15).unwrap();
+        let lk = Fri::lagrange_key(&ck, 8).unwrap();
+        let mut bytes = vec![];
+        lk.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), lk.serialized_size());
+        let decoded = LagrangeKey::<Fr>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(decoded, lk);
+        assert!(Fri::check_lagrange_key(&decoded) && Fri::check_verifier_key(&vk) && Fri::check_params(&pp));
+
+        //the domain size must be a power of two
+        let len = bytes.len();
+        bytes[len - 8] = 6;
+        assert!(LagrangeKey::<Fr>::deserialize(&bytes[..]).is_err());
+        let mut bad = ck;
+        bad.degree_bound = 12;
+        assert!(!Fri::check_committer_key(&bad));
+        bad.degree_bound = 1 << 40;
+        assert!(!Fri::check_committer_key(&bad));
+    }
+}
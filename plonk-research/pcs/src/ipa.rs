@@ -0,0 +1,492 @@
+use crate::error::Error;
+use crate::lagrange::LagrangeBases;
+use crate::{msm, powers, Absorb, CurveAffine, Element, PolynomialCommitment};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Polynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2b, Digest};
+use rand::RngCore;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+const PROTOCOL_NAME: &[u8] = b"plonk ipa pcs";
+
+//Generators hashed to the curve, nobody knows their discrete logs, so there is no trapdoor.
+//The verifier needs every generator, verification is linear in the degree.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitterKey<G: AffineCurve> {
+    //power of two generators, one per coefficient
+    pub comm_key: Vec<G>,
+
+    //binds the inner product value
+    pub u: G,
+}
+
+impl<G: AffineCurve> CommitterKey<G> {
+    pub fn max_degree(&self) -> usize {
+        self.comm_key.len() - 1
+    }
+}
+
+pub type UniversalParams<G> = CommitterKey<G>;
+
+pub type VerifierKey<G> = CommitterKey<G>;
+
+//Pedersen vector commitment to the coefficients, not hiding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<G: AffineCurve>(pub G);
+
+impl<G: AffineCurve> Default for Commitment<G> {
+    fn default() -> Self {
+        Self(G::zero())
+    }
+}
+
+impl<G: CurveAffine> Absorb<G::ScalarField> for Commitment<G> {
+    fn elements(&self) -> Vec<Element<G::ScalarField>> {
+        let (x, y) = self.0.coordinates();
+        vec![Element::Point(x, y)]
+    }
+}
+
+//log(n) rounds of folding, the opening is not hiding either
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<G: AffineCurve> {
+    pub l_vec: Vec<G>,
+
+    pub r_vec: Vec<G>,
+
+    //coefficient vector folded to a single element
+    pub final_coeff: G::ScalarField,
+}
+
+impl<G: CurveAffine> Absorb<G::ScalarField> for Proof<G> {
+    fn elements(&self) -> Vec<Element<G::ScalarField>> {
+        let mut elements: Vec<_> = self
+            .l_vec
+            .iter()
+            .chain(self.r_vec.iter())
+            .map(|p| {
+                let (x, y) = p.coordinates();
+                Element::Point(x, y)
+            })
+            .collect();
+        elements.push(Element::Scalar(self.final_coeff));
+        elements
+    }
+}
+
+//Bulletproofs style inner product argument, transparent setup
+#[derive(Clone, Copy, Debug)]
+pub struct IPA<G: AffineCurve>(PhantomData<G>);
+
+impl<G: AffineCurve> IPA<G> {
+    //Try and increment until the hash is the x coordinate of a point
+    fn hash_to_curve(label: &[u8], id: u64) -> G {
+        let mut counter = 0u64;
+        loop {
+            let bytes = [
+                PROTOCOL_NAME,
+                label,
+                &id.to_le_bytes(),
+                &counter.to_le_bytes(),
+            ]
+            .concat();
+            if let Some(point) = G::from_random_bytes(&Blake2b::digest(&bytes)) {
+                let point = point.mul_by_cofactor();
+                if !point.is_zero() {
+                    return point;
+                }
+            }
+            counter += 1;
+        }
+    }
+
+    fn hash_to_field(bytes: &[u8]) -> G::ScalarField {
+        G::ScalarField::from_le_bytes_mod_order(&Blake2b::digest(bytes))
+    }
+
+    //Fiat-Shamir challenge binding the claim commit(point) = value
+    fn first_challenge(
+        commit: &Commitment<G>,
+        point: G::ScalarField,
+        value: G::ScalarField,
+    ) -> G::ScalarField {
+        let mut bytes = PROTOCOL_NAME.to_vec();
+        commit.serialize(&mut bytes).unwrap();
+        point.serialize(&mut bytes).unwrap();
+        value.serialize(&mut bytes).unwrap();
+        Self::hash_to_field(&bytes)
+    }
+
+    fn round_challenge(prev: G::ScalarField, l: &G, r: &G) -> G::ScalarField {
+        let mut bytes = vec![];
+        prev.serialize(&mut bytes).unwrap();
+        l.serialize(&mut bytes).unwrap();
+        r.serialize(&mut bytes).unwrap();
+        Self::hash_to_field(&bytes)
+    }
+
+    fn inner_product(a: &[G::ScalarField], b: &[G::ScalarField]) -> G::ScalarField {
+        a.iter().zip(b.iter()).map(|(a, b)| *a * b).sum()
+    }
+}
+
+impl<G: CurveAffine> PolynomialCommitment for IPA<G> {
+    type Field = G::ScalarField;
+
+    type UniversalParams = UniversalParams<G>;
+
+    type CommitterKey = CommitterKey<G>;
+
+    type LagrangeKey = LagrangeBases<G>;
+
+    type VerifierKey = VerifierKey<G>;
+
+    type Commitment = Commitment<G>;
+
+    type Proof = Proof<G>;
+
+    //Deterministic, rng is not used
+    fn setup<R: RngCore>(max_degree: usize, _rng: &mut R) -> Result<Self::UniversalParams, Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        let size = (max_degree + 1).next_power_of_two();
+        Ok(CommitterKey {
+            comm_key: (0..size as u64)
+                .map(|i| Self::hash_to_curve(b"commitment key", i))
+                .collect(),
+            u: Self::hash_to_curve(b"inner product key", 0),
+        })
+    }
+
+    //Keep the first power of two generators covering degree + 1 coefficients
+    fn trim(
+        pp: &Self::UniversalParams,
+        degree: usize,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Error> {
+        if degree > pp.max_degree() {
+            return Err(Error::TrimmingDegreeTooLarge {
+                degree,
+                max_degree: pp.max_degree(),
+            });
+        }
+        let ck = CommitterKey {
+            comm_key: pp.comm_key[..(degree + 1).next_power_of_two()].to_vec(),
+            u: pp.u,
+        };
+        Ok((ck.clone(), ck))
+    }
+
+    fn commit(
+        ck: &Self::CommitterKey,
+        poly: &DensePolynomial<Self::Field>,
+    ) -> Result<Self::Commitment, Error> {
+        if poly.coeffs.len() > ck.comm_key.len() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: poly.coeffs.len(),
+                num_powers: ck.comm_key.len(),
+            });
+        }
+        let bases = &ck.comm_key[..poly.coeffs.len()];
+        Ok(Commitment(msm(bases, &poly.coeffs).into_affine()))
+    }
+
+    fn lagrange_key(ck: &Self::CommitterKey, domain_size: usize) -> Result<Self::LagrangeKey, Error> {
+        LagrangeBases::new(&ck.comm_key, domain_size)
+    }
+
+    fn commit_evals(
+        lk: &Self::LagrangeKey,
+        evals: &[Self::Field],
+        blinder: &DensePolynomial<Self::Field>,
+    ) -> Result<Self::Commitment, Error> {
+        Ok(Commitment(lk.commit(evals, blinder)?.into_affine()))
+    }
+
+    //Prove <a, b> = value for the coefficients a and b = (1, point, point^2, ...).
+    //Every round folds a' = a_l + x * a_r, b' = b_l + x^-1 * b_r, G' = G_l + x^-1 * G_r
+    fn open(
+        ck: &Self::CommitterKey,
+        poly: &DensePolynomial<Self::Field>,
+        point: Self::Field,
+    ) -> Result<Self::Proof, Error> {
+        let commit = Self::commit(ck, poly)?;
+        let n = ck.comm_key.len();
+        let mut a = poly.coeffs.clone();
+        a.resize(n, Self::Field::zero());
+        let mut b = powers(point, n);
+        let value = poly.evaluate(&point);
+
+        let mut challenge = Self::first_challenge(&commit, point, value);
+        let u = ck.u.mul(challenge.into_repr());
+        //the first round folds the borrowed key, later rounds their own halves
+        let mut g = Cow::Borrowed(&ck.comm_key[..]);
+        let mut l_vec = vec![];
+        let mut r_vec = vec![];
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_l, a_r) = a.split_at(half);
+            let (b_l, b_r) = b.split_at(half);
+            let (g_l, g_r) = g.split_at(half);
+            let l = (msm(g_r, a_l) + u.mul(Self::inner_product(a_l, b_r).into_repr())).into_affine();
+            let r = (msm(g_l, a_r) + u.mul(Self::inner_product(a_r, b_l).into_repr())).into_affine();
+            challenge = Self::round_challenge(challenge, &l, &r);
+            let challenge_inv = challenge.inverse().unwrap();
+
+            let a_next = (0..half).map(|i| a_l[i] + challenge * a_r[i]).collect();
+            let b_next = (0..half).map(|i| b_l[i] + challenge_inv * b_r[i]).collect();
+            let g_next: Vec<_> = (0..half)
+                .map(|i| g_r[i].mul(challenge_inv.into_repr()).add_mixed(&g_l[i]))
+                .collect();
+            a = a_next;
+            b = b_next;
+            g = Cow::Owned(ProjectiveCurve::batch_normalization_into_affine(&g_next));
+            l_vec.push(l);
+            r_vec.push(r);
+        }
+        Ok(Proof {
+            l_vec,
+            r_vec,
+            final_coeff: a[0],
+        })
+    }
+
+    //C + value * U + sum(x_j^-1 * L_j + x_j * R_j) = a * (<s, G> + b' * U), where s_i is the
+    //product of x_j^-1 over the bits j of i and b' = prod(1 + x_j^-1 * point^(n / 2^(j+1)))
+    fn verify(
+        vk: &Self::VerifierKey,
+        commit: &Self::Commitment,
+        point: Self::Field,
+        value: Self::Field,
+        proof: &Self::Proof,
+    ) -> bool {
+        let n = vk.comm_key.len();
+        let rounds = n.trailing_zeros() as usize;
+        if !n.is_power_of_two() || proof.l_vec.len() != rounds || proof.r_vec.len() != rounds {
+            return false;
+        }
+        let first = Self::first_challenge(commit, point, value);
+        let mut challenges = Vec::with_capacity(rounds);
+        let mut challenge = first;
+        for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+            challenge = Self::round_challenge(challenge, l, r);
+            challenges.push(challenge);
+        }
+        let mut challenge_invs = challenges.clone();
+        if challenge_invs.iter().any(|c| c.is_zero()) {
+            return false;
+        }
+        ark_ff::batch_inversion(&mut challenge_invs);
+
+        //the first round folds the highest bit
+        let mut s = vec![Self::Field::one()];
+        for c in challenge_invs.iter().rev() {
+            let high: Vec<_> = s.iter().map(|s| *s * c).collect();
+            s.extend(high);
+        }
+        let mut point_pows = vec![point];
+        for i in 1..rounds {
+            point_pows.push(point_pows[i - 1].square());
+        }
+        let b_final = challenge_invs
+            .iter()
+            .zip(point_pows.iter().rev())
+            .fold(Self::Field::one(), |acc, (c, p)| acc * (Self::Field::one() + *c * p));
+        let a = proof.final_coeff;
+
+        let mut bases = vk.comm_key.clone();
+        let mut scalars: Vec<Self::Field> = s.iter().map(|s| -a * s).collect();
+        bases.push(commit.0);
+        scalars.push(Self::Field::one());
+        bases.push(vk.u);
+        scalars.push(first * (value - a * b_final));
+        for j in 0..rounds {
+            bases.push(proof.l_vec[j]);
+            scalars.push(challenge_invs[j]);
+            bases.push(proof.r_vec[j]);
+            scalars.push(challenges[j]);
+        }
+        msm(&bases, &scalars).is_zero()
+    }
+
+    fn combine_commitments(commits: &[Self::Commitment], scalars: &[Self::Field]) -> Self::Commitment {
+        let bases: Vec<_> = commits.iter().map(|commit| commit.0).collect();
+        Commitment(msm(&bases, scalars).into_affine())
+    }
+
+    //the shorter key is a prefix of the longer one
+    fn same_params(vk: &Self::VerifierKey, other: &Self::VerifierKey) -> bool {
+        let len = vk.comm_key.len().min(other.comm_key.len());
+        vk.u == other.u && vk.comm_key[..len] == other.comm_key[..len]
+    }
+
+    //on curve and in the prime order subgroup
+    fn check_commitment(commit: &Self::Commitment) -> bool {
+        commit.0.is_valid()
+    }
+
+    fn check_proof(proof: &Self::Proof) -> bool {
+        proof.l_vec.iter().chain(proof.r_vec.iter()).all(CurveAffine::is_valid)
+    }
+
+    //a power of two generators, opening folds the key in halves
+    fn check_params(pp: &Self::UniversalParams) -> bool {
+        pp.comm_key.len().is_power_of_two() && pp.comm_key.iter().chain([&pp.u]).all(CurveAffine::is_valid)
+    }
+
+    fn check_committer_key(ck: &Self::CommitterKey) -> bool {
+        Self::check_params(ck)
+    }
+
+    fn check_lagrange_key(lk: &Self::LagrangeKey) -> bool {
+        lk.check()
+    }
+
+    fn check_verifier_key(vk: &Self::VerifierKey) -> bool {
+        Self::check_params(vk)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::error::Error;
+    use crate::ipa::{Commitment, IPA};
+    use crate::PolynomialCommitment;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::{Affine, Fr};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{Polynomial, UVPolynomial};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    type Ipa = IPA<Affine>;
+
+    #[test]
+    fn test_commit_open_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pp = Ipa::setup(16, &mut rng).unwrap();
+        assert_eq!(pp.comm_key.len(), 32);
+        //transparent setup does not depend on rng
+        assert_eq!(pp, Ipa::setup(16, &mut StdRng::seed_from_u64(1)).unwrap());
+        let (ck, vk) = Ipa::trim(&pp, 10).unwrap();
+        assert_eq!(ck.max_degree(), 15);
+
+        for degree in [0, 1, 7, 10, 15] {
+            let poly = DensePolynomial::<Fr>::rand(degree, &mut rng);
+            let commit = Ipa::commit(&ck, &poly).unwrap();
+            let point = Fr::rand(&mut rng);
+            let proof = Ipa::open(&ck, &poly, point).unwrap();
+            assert_eq!(proof.l_vec.len(), 4);
+            let value = poly.evaluate(&point);
+            assert!(Ipa::verify(&vk, &commit, point, value, &proof));
+            assert!(!Ipa::verify(&vk, &commit, point, value + Fr::one(), &proof));
+            assert!(!Ipa::verify(&vk, &commit, point + Fr::one(), value, &proof));
+
+            let mut bad_proof = proof.clone();
+            bad_proof.final_coeff += Fr::one();
+            assert!(!Ipa::verify(&vk, &commit, point, value, &bad_proof));
+            let mut bad_proof = proof;
+            bad_proof.l_vec.swap(0, 1);
+            assert!(!Ipa::verify(&vk, &commit, point, value, &bad_proof));
+        }
+
+        //commitment is linear in the polynomial
+        let poly = DensePolynomial::<Fr>::rand(15, &mut rng);
+        let other = DensePolynomial::<Fr>::rand(4, &mut rng);
+        let sum = Ipa::commit(&ck, &(&poly + &other)).unwrap();
+        let commit = Ipa::commit(&ck, &poly).unwrap();
+        let other_commit = Ipa::commit(&ck, &other).unwrap();
+        assert_eq!(
+            sum.0,
+            (commit.0.into_projective() + other_commit.0.into_projective()).into_affine()
+        );
+
+        //zero polynomial
+        let zero = DensePolynomial::<Fr>::from_coefficients_vec(vec![]);
+        assert_eq!(Ipa::commit(&ck, &zero).unwrap(), Commitment::default());
+        let point = Fr::rand(&mut rng);
+        let proof = Ipa::open(&ck, &zero, point).unwrap();
+        assert!(Ipa::verify(&vk, &Commitment::default(), point, Fr::from(0u32), &proof));
+    }
+
+    #[test]
+    fn test_batch_open_verify() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let pp = Ipa::setup(8, &mut rng).unwrap();
+        let (ck, vk) = Ipa::trim(&pp, 8).unwrap();
+        let polys: Vec<_> = (1..6)
+            .map(|degree| DensePolynomial::<Fr>::rand(degree, &mut rng))
+            .collect();
+        let commits: Vec<_> = polys.iter().map(|p| Ipa::commit(&ck, p).unwrap()).collect();
+        let point = Fr::rand(&mut rng);
+        let challenge = Fr::rand(&mut rng);
+        let values: Vec<_> = polys.iter().map(|p| p.evaluate(&point)).collect();
+        let poly_refs: Vec<_> = polys.iter().collect();
+        let proof = Ipa::batch_open(&ck, &poly_refs, point, challenge).unwrap();
+        assert!(Ipa::batch_verify(&vk, &commits, point, &values, &proof, challenge));
+
+        let mut bad_values = values.clone();
+        bad_values.swap(0, 1);
+        assert!(!Ipa::batch_verify(&vk, &commits, point, &bad_values, &proof, challenge));
+        assert!(!Ipa::batch_verify(&vk, &commits[1..], point, &values[1..], &proof, challenge));
+
+        //every opening is checked on its own
+        let points = [point, point + Fr::one()];
+        let proofs = [
+            proof,
+            Ipa::open(&ck, &polys[0], points[1]).unwrap(),
+        ];
+        let combined = Ipa::combine_commitments(&commits, &crate::powers(challenge, commits.len()));
+        let combined_value = values
+            .iter()
+            .zip(crate::powers(challenge, values.len()))
+            .map(|(v, c)| *v * c)
+            .sum();
+        let mp_commits = [combined, commits[0]];
+        let mp_values = [combined_value, polys[0].evaluate(&points[1])];
+        assert!(Ipa::multi_point_verify(&vk, &mp_commits, &points, &mp_values, &proofs, challenge));
+        assert!(!Ipa::multi_point_verify(&vk, &mp_commits, &points, &values[..2], &proofs, challenge));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(Ipa::setup(0, &mut rng).unwrap_err(), Error::DegreeIsZero);
+        let pp = Ipa::setup(3, &mut rng).unwrap();
+        assert_eq!(
+            Ipa::trim(&pp, 4).unwrap_err(),
+            Error::TrimmingDegreeTooLarge {
+                degree: 4,
+                max_degree: 3
+            }
+        );
+        let (ck, _) = Ipa::trim(&pp, 1).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(2, &mut rng);
+        assert_eq!(
+            Ipa::commit(&ck, &poly).unwrap_err(),
+            Error::TooManyCoefficients {
+                num_coefficients: 3,
+                num_powers: 2
+            }
+        );
+        let (_, vk) = Ipa::trim(&pp, 3).unwrap();
+        let (_, small_vk) = Ipa::trim(&pp, 1).unwrap();
+        assert!(Ipa::same_params(&vk, &small_vk));
+        let mut other_vk = vk.clone();
+        other_vk.comm_key.swap(0, 1);
+        assert!(!Ipa::same_params(&other_vk, &small_vk));
+
+        assert!(Ipa::check_commitment(&Commitment(pp.comm_key[0])));
+        assert!(Ipa::check_commitment(&Commitment(Affine::prime_subgroup_generator())));
+        assert!(!Ipa::check_commitment(&Commitment(Affine::new(
+            pp.u.x,
+            pp.u.x,
+            false
+        ))));
+    }
+}
@@ -0,0 +1,700 @@
+use crate::error::Error;
+use crate::lagrange::LagrangeBases;
+use crate::pippenger::{self, FixedBaseTable};
+use crate::{msm, powers, Absorb, CurveAffine, Element, PolynomialCommitment};
+use ark_ec::msm::FixedBaseMSM;
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{Polynomial, UVPolynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2b, Digest};
+use rand::RngCore;
+use std::marker::PhantomData;
+
+//Powers g^(beta^i) of the setup, beta is the trapdoor
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct UniversalParams<E: PairingEngine> {
+    pub powers_of_g: Vec<E::G1Affine>,
+
+    pub h: E::G2Affine,
+
+    pub beta_h: E::G2Affine,
+}
+
+impl<E: PairingEngine> UniversalParams<E> {
+    //params without any power, e.g. deserialized from untrusted input, are inconsistent
+    pub fn max_degree(&self) -> Result<usize, Error> {
+        self.powers_of_g.len().checked_sub(1).ok_or(Error::InconsistentSrs)
+    }
+
+    //Keep the powers up to degree
+    pub fn truncate(&self, degree: usize) -> Result<Self, Error> {
+        let max_degree = self.max_degree()?;
+        if degree > max_degree {
+            return Err(Error::TrimmingDegreeTooLarge { degree, max_degree });
+        }
+        Ok(Self {
+            powers_of_g: self.powers_of_g[..=degree].to_vec(),
+            h: self.h,
+            beta_h: self.beta_h,
+        })
+    }
+}
+
+impl<E> UniversalParams<E>
+where
+    E: PairingEngine,
+    E::G1Affine: CurveAffine,
+    E::G2Affine: CurveAffine,
+{
+    //Params from a ceremony must have every point in the prime order subgroup and powers of one beta,
+    //e(sum r^i * g_i, beta_h) = e(sum r^i * g_(i+1), h) with r hashed from the params
+    pub fn check(&self) -> Result<(), Error> {
+        let max_degree = self.max_degree()?;
+        let in_subgroup = self.powers_of_g.iter().all(CurveAffine::is_valid)
+            && self.h.is_valid()
+            && self.beta_h.is_valid();
+        if !in_subgroup {
+            return Err(Error::InvalidSrsPoint);
+        }
+        if self.powers_of_g[0].is_zero() || self.h.is_zero() {
+            return Err(Error::InconsistentSrs);
+        }
+
+        let mut bytes = b"plonk kzg10 srs".to_vec();
+        self.powers_of_g.serialize(&mut bytes).unwrap();
+        self.h.serialize(&mut bytes).unwrap();
+        self.beta_h.serialize(&mut bytes).unwrap();
+        let r = E::Fr::from_le_bytes_mod_order(&Blake2b::digest(&bytes));
+        let r_pows = powers(r, max_degree);
+        let left = msm(&self.powers_of_g[..max_degree], &r_pows);
+        let right = msm(&self.powers_of_g[1..], &r_pows);
+        let same_ratio = E::product_of_pairings(&[
+            (left.into_affine().into(), self.beta_h.into()),
+            ((-right).into_affine().into(), self.h.into()),
+        ])
+        .is_one();
+        if same_ratio {
+            Ok(())
+        } else {
+            Err(Error::InconsistentSrs)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CommitterKey<E: PairingEngine> {
+    pub powers_of_g: Vec<E::G1Affine>,
+
+    //fixed base table of powers_of_g, built on request and not serialized
+    table: Option<FixedBaseTable<E::G1Affine>>,
+}
+
+impl<E: PairingEngine> CommitterKey<E> {
+    pub fn new(powers_of_g: Vec<E::G1Affine>) -> Self {
+        Self {
+            powers_of_g,
+            table: None,
+        }
+    }
+
+    //a key without powers, e.g. deserialized from untrusted input, is inconsistent
+    pub fn max_degree(&self) -> Result<usize, Error> {
+        self.powers_of_g.len().checked_sub(1).ok_or(Error::InconsistentSrs)
+    }
+}
+
+impl<E> CommitterKey<E>
+where
+    E: PairingEngine,
+    E::G1Affine: CurveAffine,
+{
+    //Precompute the powers shifted by every window, commitments then skip the doublings of the
+    //msm at the cost of about 256 / window times the memory of the powers
+    pub fn precompute(&mut self, window: usize) {
+        self.table = Some(FixedBaseTable::new(&self.powers_of_g, window));
+    }
+
+    pub fn has_table(&self) -> bool {
+        self.table.is_some()
+    }
+
+    //sum coeffs[i] * g^(beta^i), coeffs must not outnumber the powers
+    fn msm(&self, coeffs: &[E::Fr]) -> E::G1Projective {
+        match &self.table {
+            Some(table) => table.msm(coeffs),
+            None => pippenger::msm(&self.powers_of_g[..coeffs.len()], coeffs),
+        }
+    }
+}
+
+//Only the powers are stored, a table is rebuilt after reading if it is wanted
+impl<E: PairingEngine> CanonicalSerialize for CommitterKey<E> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.powers_of_g.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.powers_of_g.serialize_uncompressed(writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.powers_of_g.uncompressed_size()
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for CommitterKey<E> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Vec::deserialize(reader).map(Self::new)
+    }
+
+    fn deserialize_uncompressed<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Vec::deserialize_uncompressed(reader).map(Self::new)
+    }
+
+    fn deserialize_unchecked<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Vec::deserialize_unchecked(reader).map(Self::new)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VerifierKey<E: PairingEngine> {
+    pub g: E::G1Affine,
+
+    pub h: E::G2Affine,
+
+    pub beta_h: E::G2Affine,
+
+    pub prepared_h: E::G2Prepared,
+
+    pub prepared_beta_h: E::G2Prepared,
+}
+
+//Commitment to a polynomial, opening proofs are commitments to witness polynomials
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Commitment<E: PairingEngine>(pub E::G1Affine);
+
+impl<E: PairingEngine> Default for Commitment<E> {
+    fn default() -> Self {
+        Self(E::G1Affine::zero())
+    }
+}
+
+impl<E> Absorb<E::Fr> for Commitment<E>
+where
+    E: PairingEngine,
+    E::G1Affine: CurveAffine,
+{
+    fn elements(&self) -> Vec<Element<E::Fr>> {
+        let (x, y) = self.0.coordinates();
+        vec![Element::Point(x, y)]
+    }
+}
+
+//prepared elements are derived from h and beta_h
+impl<E: PairingEngine> PartialEq for VerifierKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.g == other.g && self.h == other.h && self.beta_h == other.beta_h
+    }
+}
+
+impl<E: PairingEngine> CanonicalSerialize for VerifierKey<E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.g.serialize(&mut writer)?;
+        self.h.serialize(&mut writer)?;
+        self.beta_h.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.g.serialized_size() + self.h.serialized_size() + self.beta_h.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.g.serialize_uncompressed(&mut writer)?;
+        self.h.serialize_uncompressed(&mut writer)?;
+        self.beta_h.serialize_uncompressed(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.g.uncompressed_size() + self.h.uncompressed_size() + self.beta_h.uncompressed_size()
+    }
+}
+
+impl<E: PairingEngine> VerifierKey<E> {
+    fn from_points(g: E::G1Affine, h: E::G2Affine, beta_h: E::G2Affine) -> Self {
+        Self {
+            g,
+            h,
+            beta_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        }
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for VerifierKey<E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::from_points(
+            CanonicalDeserialize::deserialize(&mut reader)?,
+            CanonicalDeserialize::deserialize(&mut reader)?,
+            CanonicalDeserialize::deserialize(&mut reader)?,
+        ))
+    }
+
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::from_points(
+            CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+        ))
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::from_points(
+            CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+            CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+            CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct KZG10<E: PairingEngine>(PhantomData<E>);
+
+impl<E> PolynomialCommitment for KZG10<E>
+where
+    E: PairingEngine,
+    E::G1Affine: CurveAffine,
+    E::G2Affine: CurveAffine,
+{
+    type Field = E::Fr;
+
+    type UniversalParams = UniversalParams<E>;
+
+    type CommitterKey = CommitterKey<E>;
+
+    type LagrangeKey = LagrangeBases<E::G1Affine>;
+
+    type VerifierKey = VerifierKey<E>;
+
+    type Commitment = Commitment<E>;
+
+    //commitment to the witness poly
+    type Proof = Commitment<E>;
+
+    //Sample the trapdoor beta from rng and drop it, only suitable for testing
+    fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<UniversalParams<E>, Error> {
+        if max_degree < 1 {
+            return Err(Error::DegreeIsZero);
+        }
+        let beta = E::Fr::rand(rng);
+        let g = E::G1Projective::rand(rng);
+        let h = E::G2Projective::rand(rng);
+
+        let mut powers_of_beta = vec![E::Fr::one()];
+        let mut cur = beta;
+        for _ in 0..max_degree {
+            powers_of_beta.push(cur);
+            cur *= beta;
+        }
+        let scalar_bits = E::Fr::size_in_bits();
+        let window_size = FixedBaseMSM::get_mul_window_size(max_degree + 1);
+        let g_table = FixedBaseMSM::get_window_table(scalar_bits, window_size, g);
+        let powers_of_g = FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(
+            scalar_bits,
+            window_size,
+            &g_table,
+            &powers_of_beta,
+        );
+        let powers_of_g = E::G1Projective::batch_normalization_into_affine(&powers_of_g);
+
+        Ok(UniversalParams {
+            powers_of_g,
+            h: h.into_affine(),
+            beta_h: h.mul(beta.into_repr()).into_affine(),
+        })
+    }
+
+    fn trim(
+        pp: &UniversalParams<E>,
+        degree: usize,
+    ) -> Result<(CommitterKey<E>, VerifierKey<E>), Error> {
+        let max_degree = pp.max_degree()?;
+        if degree > max_degree {
+            return Err(Error::TrimmingDegreeTooLarge { degree, max_degree });
+        }
+        let ck = CommitterKey::new(pp.powers_of_g[..=degree].to_vec());
+        let vk = VerifierKey::from_points(pp.powers_of_g[0], pp.h, pp.beta_h);
+        Ok((ck, vk))
+    }
+
+    fn commit(
+        ck: &CommitterKey<E>,
+        poly: &DensePolynomial<E::Fr>,
+    ) -> Result<Commitment<E>, Error> {
+        if poly.coeffs.len() > ck.powers_of_g.len() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: poly.coeffs.len(),
+                num_powers: ck.powers_of_g.len(),
+            });
+        }
+        Ok(Commitment(ck.msm(&poly.coeffs).into_affine()))
+    }
+
+    fn lagrange_key(ck: &CommitterKey<E>, domain_size: usize) -> Result<LagrangeBases<E::G1Affine>, Error> {
+        LagrangeBases::new(&ck.powers_of_g, domain_size)
+    }
+
+    fn commit_evals(
+        lk: &LagrangeBases<E::G1Affine>,
+        evals: &[E::Fr],
+        blinder: &DensePolynomial<E::Fr>,
+    ) -> Result<Commitment<E>, Error> {
+        Ok(Commitment(lk.commit(evals, blinder)?.into_affine()))
+    }
+
+    //Commitment to witness poly (p(X) - p(point)) / (X - point)
+    fn open(
+        ck: &CommitterKey<E>,
+        poly: &DensePolynomial<E::Fr>,
+        point: E::Fr,
+    ) -> Result<Commitment<E>, Error> {
+        let divisor = DensePolynomial::from_coefficients_vec(vec![-point, E::Fr::one()]);
+        let mut numerator = poly.clone();
+        if !numerator.coeffs.is_empty() {
+            numerator.coeffs[0] -= poly.evaluate(&point);
+        }
+        let witness_poly = &numerator / &divisor;
+        Self::commit(ck, &witness_poly)
+    }
+
+    //e(proof, [beta]_2) = e(commit - value * g + point * proof, [1]_2)
+    fn verify(
+        vk: &VerifierKey<E>,
+        commit: &Commitment<E>,
+        point: E::Fr,
+        value: E::Fr,
+        proof: &Commitment<E>,
+    ) -> bool {
+        Self::multi_point_verify(vk, &[*commit], &[point], &[value], &[*proof], E::Fr::one())
+    }
+
+    fn combine_commitments(commits: &[Commitment<E>], scalars: &[E::Fr]) -> Commitment<E> {
+        let bases: Vec<E::G1Affine> = commits.iter().map(|commit| commit.0).collect();
+        Commitment(msm(&bases, scalars).into_affine())
+    }
+
+    //One pairing equation for all openings, scaled by challenge^i:
+    //e(sum c^i * W_i, [beta]_2) = e(sum c^i * (C_i - v_i * g + z_i * W_i), [1]_2)
+    fn multi_point_verify(
+        vk: &VerifierKey<E>,
+        commits: &[Commitment<E>],
+        points: &[E::Fr],
+        values: &[E::Fr],
+        proofs: &[Commitment<E>],
+        challenge: E::Fr,
+    ) -> bool {
+        if commits.len() != points.len()
+            || commits.len() != values.len()
+            || commits.len() != proofs.len()
+        {
+            return false;
+        }
+        let challenge_pows = powers(challenge, commits.len());
+        let mut bases = vec![];
+        let mut scalars = vec![];
+        let mut value = E::Fr::zero();
+        for i in 0..commits.len() {
+            bases.push(commits[i].0);
+            scalars.push(challenge_pows[i]);
+            bases.push(proofs[i].0);
+            scalars.push(challenge_pows[i] * points[i]);
+            value += challenge_pows[i] * values[i];
+        }
+        bases.push(vk.g);
+        scalars.push(-value);
+        let right = msm(&bases, &scalars);
+        let proof_bases: Vec<E::G1Affine> = proofs.iter().map(|proof| proof.0).collect();
+        let left = msm(&proof_bases, &challenge_pows);
+        E::product_of_pairings(&[
+            (left.into_affine().into(), vk.prepared_beta_h.clone()),
+            ((-right).into_affine().into(), vk.prepared_h.clone()),
+        ])
+        .is_one()
+    }
+
+    //on curve and in the prime order subgroup
+    fn check_commitment(commit: &Commitment<E>) -> bool {
+        commit.0.is_valid()
+    }
+
+    fn check_proof(proof: &Commitment<E>) -> bool {
+        Self::check_commitment(proof)
+    }
+
+    fn check_params(pp: &UniversalParams<E>) -> bool {
+        !pp.powers_of_g.is_empty()
+            && pp.powers_of_g.iter().all(CurveAffine::is_valid)
+            && pp.h.is_valid()
+            && pp.beta_h.is_valid()
+    }
+
+    fn check_committer_key(ck: &CommitterKey<E>) -> bool {
+        !ck.powers_of_g.is_empty() && ck.powers_of_g.iter().all(CurveAffine::is_valid)
+    }
+
+    fn check_lagrange_key(lk: &LagrangeBases<E::G1Affine>) -> bool {
+        lk.check()
+    }
+
+    fn check_verifier_key(vk: &VerifierKey<E>) -> bool {
+        vk.g.is_valid() && vk.h.is_valid() && vk.beta_h.is_valid()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::error::Error;
+    use crate::kzg10::{Commitment, CommitterKey, UniversalParams, VerifierKey, KZG10};
+    use crate::PolynomialCommitment;
+    use ark_bls12_381::{Bls12_381, Fq, Fr};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::{One, UniformRand};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{Polynomial, UVPolynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    type Kzg = KZG10<Bls12_381>;
+
+    #[test]
+    fn test_commit_open_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pp = Kzg::setup(16, &mut rng).unwrap();
+        let (ck, vk) = Kzg::trim(&pp, 10).unwrap();
+        assert_eq!(ck.max_degree(), Ok(10));
+
+        let poly = DensePolynomial::<Fr>::rand(10, &mut rng);
+        let commit = Kzg::commit(&ck, &poly).unwrap();
+        let point = Fr::rand(&mut rng);
+        let proof = Kzg::open(&ck, &poly, point).unwrap();
+        let value = poly.evaluate(&point);
+        assert!(Kzg::verify(&vk, &commit, point, value, &proof));
+        assert!(!Kzg::verify(&vk, &commit, point, value + Fr::one(), &proof));
+        assert!(!Kzg::verify(&vk, &commit, point + Fr::one(), value, &proof));
+
+        //commitment is linear in the polynomial
+        let other = DensePolynomial::<Fr>::rand(4, &mut rng);
+        let sum = Kzg::commit(&ck, &(&poly + &other)).unwrap();
+        let other_commit = Kzg::commit(&ck, &other).unwrap();
+        assert_eq!(
+            sum.0,
+            (commit.0.into_projective() + other_commit.0.into_projective()).into_affine()
+        );
+
+        //constant and zero polynomials
+        let zero = DensePolynomial::<Fr>::from_coefficients_vec(vec![]);
+        assert_eq!(Kzg::commit(&ck, &zero).unwrap(), Commitment::default());
+        let proof = Kzg::open(&ck, &zero, point).unwrap();
+        assert!(Kzg::verify(&vk, &Commitment::default(), point, Fr::from(0u32), &proof));
+    }
+
+    #[test]
+    fn test_batch_open_verify() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let pp = Kzg::setup(8, &mut rng).unwrap();
+        let (ck, vk) = Kzg::trim(&pp, 8).unwrap();
+        let polys: Vec<_> = (1..6)
+            .map(|degree| DensePolynomial::<Fr>::rand(degree, &mut rng))
+            .collect();
+        let commits: Vec<_> = polys.iter().map(|p| Kzg::commit(&ck, p).unwrap()).collect();
+        let point = Fr::rand(&mut rng);
+        let challenge = Fr::rand(&mut rng);
+        let values: Vec<_> = polys.iter().map(|p| p.evaluate(&point)).collect();
+        let poly_refs: Vec<_> = polys.iter().collect();
+        let proof = Kzg::batch_open(&ck, &poly_refs, point, challenge).unwrap();
+        assert!(Kzg::batch_verify(&vk, &commits, point, &values, &proof, challenge));
+
+        let mut bad_values = values.clone();
+        bad_values.swap(0, 1);
+        assert!(!Kzg::batch_verify(&vk, &commits, point, &bad_values, &proof, challenge));
+        assert!(!Kzg::batch_verify(&vk, &commits[1..], point, &values[1..], &proof, challenge));
+        assert!(!Kzg::batch_verify(&vk, &commits, point, &values[1..], &proof, challenge));
+    }
+
+    #[test]
+    fn test_multi_point_verify() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let pp = Kzg::setup(8, &mut rng).unwrap();
+        let (ck, vk) = Kzg::trim(&pp, 8).unwrap();
+        let polys: Vec<_> = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(8, &mut rng))
+            .collect();
+        let points: Vec<_> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let commits: Vec<_> = polys.iter().map(|p| Kzg::commit(&ck, p).unwrap()).collect();
+        let values: Vec<_> = polys.iter().zip(points.iter()).map(|(p, x)| p.evaluate(x)).collect();
+        let proofs: Vec<_> = polys
+            .iter()
+            .zip(points.iter())
+            .map(|(p, x)| Kzg::open(&ck, p, *x).unwrap())
+            .collect();
+        let challenge = Fr::rand(&mut rng);
+        assert!(Kzg::multi_point_verify(&vk, &commits, &points, &values, &proofs, challenge));
+
+        let mut bad_points = points.clone();
+        bad_points.swap(1, 2);
+        assert!(!Kzg::multi_point_verify(&vk, &commits, &bad_points, &values, &proofs, challenge));
+        assert!(!Kzg::multi_point_verify(&vk, &commits, &points[1..], &values, &proofs, challenge));
+
+        //combined commitment opens to the combined value
+        let scalars = [Fr::from(2u32), Fr::from(3u32)];
+        let combined = Kzg::combine_commitments(&commits[..2], &scalars);
+        let combined_poly = &(&polys[0] * scalars[0]) + &(&polys[1] * scalars[1]);
+        assert_eq!(combined, Kzg::commit(&ck, &combined_poly).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(Kzg::setup(0, &mut rng).unwrap_err(), Error::DegreeIsZero);
+        let pp = Kzg::setup(4, &mut rng).unwrap();
+        assert_eq!(
+            Kzg::trim(&pp, 5).unwrap_err(),
+            Error::TrimmingDegreeTooLarge {
+                degree: 5,
+                max_degree: 4
+            }
+        );
+        let (ck, _) = Kzg::trim(&pp, 2).unwrap();
+        let poly = DensePolynomial::<Fr>::rand(3, &mut rng);
+        assert_eq!(
+            Kzg::commit(&ck, &poly).unwrap_err(),
+            Error::TooManyCoefficients {
+                num_coefficients: 4,
+                num_powers: 3
+            }
+        );
+
+        //powers are g^(beta^i)
+        let pairing = |a, b| Bls12_381::pairing(a, b);
+        assert_eq!(
+            pairing(pp.powers_of_g[1], pp.h),
+            pairing(pp.powers_of_g[0], pp.beta_h)
+        );
+        assert!(Kzg::check_commitment(&Commitment(pp.powers_of_g[0])));
+        assert!(!Kzg::check_commitment(&Commitment(
+            ark_bls12_381::G1Affine::new(Fq::one(), Fq::one(), false)
+        )));
+    }
+
+    #[test]
+    fn test_check_params() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let pp = Kzg::setup(8, &mut rng).unwrap();
+        assert_eq!(pp.check(), Ok(()));
+        let truncated = pp.truncate(5).unwrap();
+        assert_eq!(truncated.powers_of_g, pp.powers_of_g[..6].to_vec());
+        assert_eq!(truncated.check(), Ok(()));
+        assert!(pp.truncate(9).is_err());
+
+        let mut bad = pp.clone();
+        bad.powers_of_g.swap(3, 4);
+        assert_eq!(bad.check(), Err(Error::InconsistentSrs));
+        let mut bad = pp.clone();
+        bad.beta_h = pp.h;
+        assert_eq!(bad.check(), Err(Error::InconsistentSrs));
+        let mut bad = pp;
+        bad.powers_of_g[2] = ark_bls12_381::G1Affine::new(Fq::one(), Fq::one(), false);
+        assert_eq!(bad.check(), Err(Error::InvalidSrsPoint));
+
+        //an empty srs is rejected instead of underflowing its degree
+        let mut empty = bad;
+        empty.powers_of_g.clear();
+        assert_eq!(empty.max_degree(), Err(Error::InconsistentSrs));
+        assert_eq!(empty.check(), Err(Error::InconsistentSrs));
+        assert_eq!(empty.truncate(0).unwrap_err(), Error::InconsistentSrs);
+        assert_eq!(Kzg::trim(&empty, 0).unwrap_err(), Error::InconsistentSrs);
+    }
+
+    #[test]
+    fn test_key_serialization() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let pp = Kzg::setup(8, &mut rng).unwrap();
+        let (ck, vk) = Kzg::trim(&pp, 8).unwrap();
+        let lk = Kzg::lagrange_key(&ck, 8).unwrap();
+
+        let mut bytes = vec![];
+        vk.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), vk.serialized_size());
+        let compressed = VerifierKey::<Bls12_381>::deserialize(&bytes[..]).unwrap();
+        let mut bytes = vec![];
+        vk.serialize_uncompressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), vk.uncompressed_size());
+        let uncompressed = VerifierKey::<Bls12_381>::deserialize_uncompressed(&bytes[..]).unwrap();
+        //prepared elements are rebuilt, the keys still verify openings
+        let poly = DensePolynomial::<Fr>::rand(8, &mut rng);
+        let commit = Kzg::commit(&ck, &poly).unwrap();
+        let point = Fr::rand(&mut rng);
+        let proof = Kzg::open(&ck, &poly, point).unwrap();
+        for key in [compressed, uncompressed] {
+            assert!(key == vk && Kzg::check_verifier_key(&key));
+            assert!(Kzg::verify(&key, &commit, point, poly.evaluate(&point), &proof));
+        }
+
+        let mut bytes = vec![];
+        ck.serialize_uncompressed(&mut bytes).unwrap();
+        let decoded = CommitterKey::<Bls12_381>::deserialize_uncompressed(&bytes[..]).unwrap();
+        assert!(decoded.powers_of_g == ck.powers_of_g && Kzg::check_committer_key(&decoded));
+        let mut bytes = vec![];
+        lk.serialize(&mut bytes).unwrap();
+        assert_eq!(<Kzg as PolynomialCommitment>::LagrangeKey::deserialize(&bytes[..]).unwrap(), lk);
+        assert!(Kzg::check_params(&pp) && Kzg::check_lagrange_key(&lk));
+
+        //uncompressed points are not checked to be on the curve by arkworks
+        let mut bad = pp;
+        bad.powers_of_g[2] = ark_bls12_381::G1Affine::new(Fq::one(), Fq::one(), false);
+        let mut bytes = vec![];
+        bad.serialize_uncompressed(&mut bytes).unwrap();
+        let decoded = UniversalParams::<Bls12_381>::deserialize_unchecked(&bytes[..]).unwrap();
+        assert!(!Kzg::check_params(&decoded));
+        let mut bad_ck = ck;
+        bad_ck.powers_of_g[1] = bad.powers_of_g[2];
+        assert!(!Kzg::check_committer_key(&bad_ck));
+
+        //a decoded key without powers has no degree
+        let mut bytes = vec![];
+        CommitterKey::<Bls12_381>::new(vec![]).serialize(&mut bytes).unwrap();
+        let empty_ck = CommitterKey::<Bls12_381>::deserialize(&bytes[..]).unwrap();
+        assert_eq!(empty_ck.max_degree(), Err(Error::InconsistentSrs));
+        assert!(Kzg::commit(&empty_ck, &poly).is_err());
+    }
+
+    #[test]
+    fn test_fixed_base_table() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let pp = Kzg::setup(40, &mut rng).unwrap();
+        let (ck, vk) = Kzg::trim(&pp, 40).unwrap();
+        let mut table_ck = ck.clone();
+        table_ck.precompute(10);
+        assert!(table_ck.has_table() && !ck.has_table());
+        for degree in [0, 7, 40] {
+            let poly = DensePolynomial::<Fr>::rand(degree, &mut rng);
+            let commit = Kzg::commit(&table_ck, &poly).unwrap();
+            assert_eq!(commit, Kzg::commit(&ck, &poly).unwrap());
+            let point = Fr::rand(&mut rng);
+            let proof = Kzg::open(&table_ck, &poly, point).unwrap();
+            assert!(Kzg::verify(&vk, &commit, point, poly.evaluate(&point), &proof));
+        }
+        //the table is not serialized
+        let mut bytes = vec![];
+        table_ck.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), ck.serialized_size());
+        assert!(!CommitterKey::<Bls12_381>::deserialize(&bytes[..]).unwrap().has_table());
+    }
+}
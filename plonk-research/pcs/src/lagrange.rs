@@ -0,0 +1,184 @@
+use crate::error::Error;
+use crate::curve::CurveAffine;
+use crate::pippenger;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+//Commitment bases of the Lagrange polys L_i(X) of a domain H, derived from the monomial
+//bases by an inverse FFT in the group, and of X^j * Z_H(X) that blinds the committed polys
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct LagrangeBases<G: AffineCurve> {
+    pub lagrange_bases: Vec<G>,
+
+    pub vanishing_bases: Vec<G>,
+}
+
+impl<G: AffineCurve> LagrangeBases<G> {
+    //bases[i] commits to X^i, there must be one for every power below the domain size
+    pub fn new(bases: &[G], domain_size: usize) -> Result<Self, Error> {
+        let domain = Radix2EvaluationDomain::<G::ScalarField>::new(domain_size)
+            .ok_or(Error::InvalidDomainSize(domain_size))?;
+        let n = domain.size();
+        if n > bases.len() {
+            return Err(Error::TrimmingDegreeTooLarge {
+                degree: n - 1,
+                max_degree: bases.len() - 1,
+            });
+        }
+        let mut lagrange_bases: Vec<_> = bases[..n].iter().map(|g| g.into_projective()).collect();
+        group_ifft(&mut lagrange_bases, &domain);
+        //Z_H(X) = X^n - 1
+        let vanishing_bases: Vec<_> = bases[n..]
+            .iter()
+            .zip(bases.iter())
+            .map(|(high, low)| high.into_projective() - low.into_projective())
+            .collect();
+        Ok(Self {
+            lagrange_bases: G::Projective::batch_normalization_into_affine(&lagrange_bases),
+            vanishing_bases: G::Projective::batch_normalization_into_affine(&vanishing_bases),
+        })
+    }
+
+    pub fn domain_size(&self) -> usize {
+        self.lagrange_bases.len()
+    }
+}
+
+impl<G: CurveAffine> LagrangeBases<G> {
+    //sum evals[i] * L_i + sum blinder_j * X^j * Z_H(X)
+    pub fn commit(
+        &self,
+        evals: &[G::ScalarField],
+        blinder: &DensePolynomial<G::ScalarField>,
+    ) -> Result<G::Projective, Error> {
+        if evals.len() != self.domain_size() {
+            return Err(Error::WrongEvaluationCount {
+                num_evals: evals.len(),
+                domain_size: self.domain_size(),
+            });
+        }
+        if blinder.coeffs.len() > self.vanishing_bases.len() {
+            return Err(Error::TooManyCoefficients {
+                num_coefficients: self.domain_size() + blinder.coeffs.len(),
+                num_powers: self.domain_size() + self.vanishing_bases.len(),
+            });
+        }
+        Ok(pippenger::msm(&self.lagrange_bases, evals)
+            + pippenger::msm(&self.vanishing_bases[..blinder.coeffs.len()], &blinder.coeffs))
+    }
+
+    //A power of two of Lagrange bases, every base on the curve and in the prime order subgroup
+    pub fn check(&self) -> bool {
+        self.domain_size().is_power_of_two()
+            && self
+                .lagrange_bases
+                .iter()
+                .chain(self.vanishing_bases.iter())
+                .all(CurveAffine::is_valid)
+    }
+}
+
+//In place inverse FFT of group elements over domain, radix 2 decimation in time
+fn group_ifft<G: ProjectiveCurve>(points: &mut [G], domain: &Radix2EvaluationDomain<G::ScalarField>) {
+    let n = points.len();
+    let log_n = domain.log_size_of_group;
+    if n == 1 {
+        return;
+    }
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            points.swap(i, j);
+        }
+    }
+    let mut m = 1;
+    while m < n {
+        let w_m = domain.group_gen_inv.pow([(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = G::ScalarField::one();
+            for j in 0..m {
+                //scalar muls dominate, the first twiddle of every block is one
+                let t = if j == 0 {
+                    points[k + m]
+                } else {
+                    points[k + j + m].mul(w.into_repr())
+                };
+                let u = points[k + j];
+                points[k + j] = u + t;
+                points[k + j + m] = u - t;
+                w *= w_m;
+            }
+        }
+        m *= 2;
+    }
+    let size_inv = domain.size_inv.into_repr();
+    for p in points.iter_mut() {
+        *p = p.mul(size_inv);
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::error::Error;
+    use crate::fri::FRI;
+    use crate::ipa::IPA;
+    use crate::kzg10::KZG10;
+    use crate::PolynomialCommitment;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain, UVPolynomial};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    //Committing evaluations in the Lagrange basis equals committing the interpolated coefficients
+    fn check_lagrange_commit<PC: PolynomialCommitment>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let pp = PC::setup(18, &mut rng).unwrap();
+        let (ck, _) = PC::trim(&pp, 18).unwrap();
+        for domain_size in [1, 2, 8, 16] {
+            let domain = Radix2EvaluationDomain::<PC::Field>::new(domain_size).unwrap();
+            let lk = PC::lagrange_key(&ck, domain_size).unwrap();
+            let evals: Vec<_> = (0..domain_size).map(|_| PC::Field::rand(&mut rng)).collect();
+            let poly = DensePolynomial::from_coefficients_vec(domain.ifft(&evals));
+            assert_eq!(
+                PC::commit_evals(&lk, &evals, &DensePolynomial::from_coefficients_vec(vec![])).unwrap(),
+                PC::commit(&ck, &poly).unwrap()
+            );
+            let blinder = DensePolynomial::rand(1, &mut rng);
+            assert_eq!(
+                PC::commit_evals(&lk, &evals, &blinder).unwrap(),
+                PC::commit(&ck, &(&poly + &blinder.mul_by_vanishing_poly(domain))).unwrap()
+            );
+            assert_eq!(
+                PC::commit_evals(&lk, &evals[1..], &blinder).unwrap_err(),
+                Error::WrongEvaluationCount {
+                    num_evals: domain_size - 1,
+                    domain_size
+                }
+            );
+        }
+        assert!(PC::lagrange_key(&ck, 64).is_err());
+        let lk = PC::lagrange_key(&ck, 16).unwrap();
+        let evals = vec![PC::Field::rand(&mut rng); 16];
+        assert!(PC::commit_evals(&lk, &evals, &DensePolynomial::rand(20, &mut rng)).is_err());
+    }
+
+    #[test]
+    fn test_kzg_lagrange_commit() {
+        check_lagrange_commit::<KZG10<Bls12_381>>(0);
+    }
+
+    #[test]
+    fn test_ipa_lagrange_commit() {
+        check_lagrange_commit::<IPA<ark_pallas::Affine>>(1);
+    }
+
+    #[test]
+    fn test_fri_lagrange_commit() {
+        check_lagrange_commit::<FRI<ark_bls12_381::Fr>>(2);
+    }
+}
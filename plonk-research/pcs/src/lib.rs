@@ -1 +1,192 @@
+pub mod absorb;
+pub mod ceremony;
+pub mod curve;
+pub mod error;
+pub mod fri;
+pub mod ipa;
+pub mod kzg10;
+pub mod lagrange;
+pub mod merkle;
+pub mod pippenger;
+pub mod ptau;
+pub mod shplonk;
 
+pub use absorb::{Absorb, Element};
+pub use curve::CurveAffine;
+pub use error::Error;
+
+use ark_ec::msm::VariableBaseMSM;
+use ark_ec::AffineCurve;
+use ark_ff::{Field, PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::RngCore;
+use std::fmt::Debug;
+
+//sum scalars[i] * bases[i]
+pub fn msm<G: AffineCurve>(bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+    let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+    VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+}
+
+//1, c, c^2, ..., c^(n-1)
+pub fn powers<F: Field>(c: F, n: usize) -> Vec<F> {
+    let mut pows = Vec::with_capacity(n);
+    let mut pow = F::one();
+    for _ in 0..n {
+        pows.push(pow);
+        pow *= c;
+    }
+    pows
+}
+
+//Polynomial commitment scheme of the PLONK IOP. Commitments must be additively homomorphic,
+//the verifier combines them into the commitment of the linearization poly.
+pub trait PolynomialCommitment {
+    type Field: PrimeField;
+
+    type UniversalParams: CanonicalSerialize + CanonicalDeserialize;
+
+    //Keys are shared by the threads committing in parallel
+    type CommitterKey: Sync + CanonicalSerialize + CanonicalDeserialize;
+
+    //Committer key in the Lagrange basis of a domain, commits to polys from their evaluations
+    type LagrangeKey: Sync + CanonicalSerialize + CanonicalDeserialize;
+
+    type VerifierKey: Clone + PartialEq + CanonicalSerialize + CanonicalDeserialize;
+
+    type Commitment: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + CanonicalSerialize
+        + CanonicalDeserialize
+        + Send
+        + Absorb<Self::Field>;
+
+    type Proof: Clone + Debug + PartialEq + CanonicalSerialize + CanonicalDeserialize + Absorb<Self::Field>;
+
+    fn setup<R: RngCore>(max_degree: usize, rng: &mut R) -> Result<Self::UniversalParams, Error>;
+
+    //Keep what is needed to commit polynomials of degree <= degree
+    fn trim(
+        pp: &Self::UniversalParams,
+        degree: usize,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Error>;
+
+    fn commit(
+        ck: &Self::CommitterKey,
+        poly: &DensePolynomial<Self::Field>,
+    ) -> Result<Self::Commitment, Error>;
+
+    //Key for the radix 2 domain of domain_size elements
+    fn lagrange_key(ck: &Self::CommitterKey, domain_size: usize) -> Result<Self::LagrangeKey, Error>;
+
+    //Commitment of the poly evaluating to evals over the domain plus blinder(X) * Z_H(X),
+    //equal to commit on its coefficients
+    fn commit_evals(
+        lk: &Self::LagrangeKey,
+        evals: &[Self::Field],
+        blinder: &DensePolynomial<Self::Field>,
+    ) -> Result<Self::Commitment, Error>;
+
+    fn open(
+        ck: &Self::CommitterKey,
+        poly: &DensePolynomial<Self::Field>,
+        point: Self::Field,
+    ) -> Result<Self::Proof, Error>;
+
+    fn verify(
+        vk: &Self::VerifierKey,
+        commit: &Self::Commitment,
+        point: Self::Field,
+        value: Self::Field,
+        proof: &Self::Proof,
+    ) -> bool;
+
+    //Open sum scalars[i] * p_i(X) at point. The proof verifies against
+    //combine_commitments(commits, scalars) with commits in the same order as polys,
+    //schemes that are not homomorphic need the polys one by one.
+    fn open_combination(
+        ck: &Self::CommitterKey,
+        polys: &[&DensePolynomial<Self::Field>],
+        scalars: &[Self::Field],
+        point: Self::Field,
+    ) -> Result<Self::Proof, Error> {
+        let mut poly = DensePolynomial::zero();
+        for (p, s) in polys.iter().zip(scalars.iter()) {
+            poly += (*s, *p);
+        }
+        Self::open(ck, &poly, point)
+    }
+
+    //Open sum challenge^i * p_i(X) at point
+    fn batch_open(
+        ck: &Self::CommitterKey,
+        polys: &[&DensePolynomial<Self::Field>],
+        point: Self::Field,
+        challenge: Self::Field,
+    ) -> Result<Self::Proof, Error> {
+        Self::open_combination(ck, polys, &powers(challenge, polys.len()), point)
+    }
+
+    fn batch_verify(
+        vk: &Self::VerifierKey,
+        commits: &[Self::Commitment],
+        point: Self::Field,
+        values: &[Self::Field],
+        proof: &Self::Proof,
+        challenge: Self::Field,
+    ) -> bool {
+        if commits.len() != values.len() {
+            return false;
+        }
+        let challenge_pows = powers(challenge, commits.len());
+        let commit = Self::combine_commitments(commits, &challenge_pows);
+        let value = values
+            .iter()
+            .zip(challenge_pows.iter())
+            .fold(Self::Field::zero(), |acc, (v, c)| acc + *v * c);
+        Self::verify(vk, &commit, point, value, proof)
+    }
+
+    //Commitment of sum scalars[i] * p_i(X) from the commitments of p_i(X)
+    fn combine_commitments(commits: &[Self::Commitment], scalars: &[Self::Field]) -> Self::Commitment;
+
+    //Check commits[i] opens to values[i] at points[i], schemes that can merge the checks
+    //with challenge override this
+    fn multi_point_verify(
+        vk: &Self::VerifierKey,
+        commits: &[Self::Commitment],
+        points: &[Self::Field],
+        values: &[Self::Field],
+        proofs: &[Self::Proof],
+        _challenge: Self::Field,
+    ) -> bool {
+        commits.len() == points.len()
+            && commits.len() == values.len()
+            && commits.len() == proofs.len()
+            && (0..commits.len())
+                .all(|i| Self::verify(vk, &commits[i], points[i], values[i], &proofs[i]))
+    }
+
+    //Keys trimmed from the same universal params, so their commitments can be combined
+    fn same_params(vk: &Self::VerifierKey, other: &Self::VerifierKey) -> bool {
+        vk == other
+    }
+
+    //Commitments and proofs from untrusted input must be well formed
+    fn check_commitment(commit: &Self::Commitment) -> bool;
+
+    fn check_proof(proof: &Self::Proof) -> bool;
+
+    //Params and keys read from untrusted storage must be well formed too, points on the curve
+    //and in the prime order subgroup. These do not check params came from an honest setup.
+    fn check_params(pp: &Self::UniversalParams) -> bool;
+
+    fn check_committer_key(ck: &Self::CommitterKey) -> bool;
+
+    fn check_lagrange_key(lk: &Self::LagrangeKey) -> bool;
+
+    fn check_verifier_key(vk: &Self::VerifierKey) -> bool;
+}
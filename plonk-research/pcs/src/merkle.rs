@@ -0,0 +1,116 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2s, Digest as _};
+
+//Blake2s output, root of a merkle tree or a node on a path
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Digest(pub [u8; 32]);
+
+impl CanonicalSerialize for Digest {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0)?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        32
+    }
+}
+
+impl CanonicalDeserialize for Digest {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; 32];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+fn hash_leaf<T: CanonicalSerialize>(leaf: &[T]) -> Digest {
+    let mut bytes = vec![0u8];
+    leaf.serialize(&mut bytes).unwrap();
+    Digest(Blake2s::digest(&bytes).into())
+}
+
+fn hash_node(left: &Digest, right: &Digest) -> Digest {
+    let bytes = [&[1u8][..], &left.0, &right.0].concat();
+    Digest(Blake2s::digest(&bytes).into())
+}
+
+//Binary merkle tree over a power of two leaves, every leaf is a slice of serialized elements
+pub struct MerkleTree {
+    //layers[0] are the leaf hashes, the last layer is the root
+    layers: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    pub fn new<T: CanonicalSerialize>(leaves: &[Vec<T>]) -> Self {
+        assert!(leaves.len().is_power_of_two());
+        let mut layers = vec![leaves.iter().map(|leaf| hash_leaf(leaf)).collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn root(&self) -> Digest {
+        self.layers.last().unwrap()[0]
+    }
+
+    //Siblings from the leaf up to the root
+    pub fn open(&self, mut index: usize) -> Vec<Digest> {
+        let mut path = vec![];
+        for layer in self.layers[..self.layers.len() - 1].iter() {
+            path.push(layer[index ^ 1]);
+            index >>= 1;
+        }
+        path
+    }
+}
+
+pub fn verify_path<T: CanonicalSerialize>(
+    root: &Digest,
+    mut index: usize,
+    leaf: &[T],
+    path: &[Digest],
+) -> bool {
+    let mut node = hash_leaf(leaf);
+    for sibling in path.iter() {
+        node = if index & 1 == 0 {
+            hash_node(&node, sibling)
+        } else {
+            hash_node(sibling, &node)
+        };
+        index >>= 1;
+    }
+    index == 0 && node == *root
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::merkle::{verify_path, MerkleTree};
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_merkle_path() {
+        let leaves: Vec<Vec<Fr>> = (0..8u64)
+            .map(|i| vec![Fr::from(i), Fr::from(i + 8)])
+            .collect();
+        let tree = MerkleTree::new(&leaves);
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = tree.open(i);
+            assert_eq!(path.len(), 3);
+            assert!(verify_path(&tree.root(), i, leaf, &path));
+            assert!(!verify_path(&tree.root(), i ^ 1, leaf, &path));
+            assert!(!verify_path(&tree.root(), i + 8, leaf, &path));
+        }
+        assert!(!verify_path(&tree.root(), 0, &leaves[1], &tree.open(0)));
+
+        let single = MerkleTree::new(&leaves[..1]);
+        assert!(single.open(0).is_empty());
+        assert!(verify_path(&single.root(), 0, &leaves[0], &[]));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::curve::CurveAffine;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, Zero};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+//Windows wider than this need i32 digits and more buckets than any input here fills
+const MAX_WINDOW: usize = 16;
+
+//Window size of a variable base msm of n points, signed digits halve the buckets of a window
+fn window_size(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        let log_n = (usize::BITS - n.leading_zeros()) as usize;
+        (log_n * 69 / 100 + 3).min(MAX_WINDOW)
+    }
+}
+
+//Digits of every scalar in base 2^c, each in (-2^(c-1), 2^(c-1)], scalar i has digits
+//digits[i * num_windows..(i + 1) * num_windows] starting from the least significant
+fn signed_digits<F: PrimeField>(scalars: &[F], c: usize) -> (usize, Vec<i32>) {
+    //one more window than the bits need holds the last carry
+    let num_windows = F::size_in_bits() / c + 1;
+    let half = 1i64 << (c - 1);
+    let mut digits = Vec::with_capacity(scalars.len() * num_windows);
+    for scalar in scalars {
+        let repr = scalar.into_repr();
+        let limbs = repr.as_ref();
+        let mut carry = 0;
+        for j in 0..num_windows {
+            let (limb, shift) = (j * c / 64, j * c % 64);
+            let mut raw = limbs.get(limb).map_or(0, |l| l >> shift);
+            if shift + c > 64 {
+                raw |= limbs.get(limb + 1).map_or(0, |l| l << (64 - shift));
+            }
+            let raw = (raw & ((1 << c) - 1)) as i64;
+            let mut digit = raw + carry;
+            carry = 0;
+            if digit > half {
+                digit -= 1 << c;
+                carry = 1;
+            }
+            digits.push(digit as i32);
+        }
+    }
+    (num_windows, digits)
+}
+
+//sum scalars[i] * bases[i] with Pippenger's bucket method on signed digits,
+//buckets are accumulated in affine coordinates with batched inversions
+pub fn msm<G: CurveAffine>(bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+    let n = bases.len().min(scalars.len());
+    if n == 0 {
+        return G::Projective::zero();
+    }
+    let c = window_size(n);
+    let (num_windows, digits) = signed_digits(&scalars[..n], c);
+    let window_sum = |j: usize| {
+        let digits = &digits;
+        bucket_sum(1 << (c - 1), || (0..n).map(move |i| (digits[i * num_windows + j], &bases[i])))
+    };
+
+    #[cfg(feature = "parallel")]
+    let window_sums: Vec<_> = (0..num_windows).into_par_iter().map(window_sum).collect();
+    #[cfg(not(feature = "parallel"))]
+    let window_sums: Vec<_> = (0..num_windows).map(window_sum).collect();
+
+    //sum window_sums[j] * 2^(c * j), from the most significant window
+    let mut result = G::Projective::zero();
+    for window in window_sums.into_iter().rev() {
+        for _ in 0..c {
+            result.double_in_place();
+        }
+        result += window;
+    }
+    result
+}
+
+//Bases multiplied by every power 2^(window * j) a digit can shift them by, so an msm takes a
+//single pass over one set of buckets and no doublings. The table has as many points as the
+//bases times the number of windows, about 16 times the bases for 255 bit scalars and window 16.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBaseTable<G: AffineCurve> {
+    window: usize,
+
+    num_windows: usize,
+
+    //table[i * num_windows + j] is bases[i] * 2^(window * j)
+    table: Vec<G>,
+}
+
+impl<G: CurveAffine> FixedBaseTable<G> {
+    //window is clamped to 2..=16
+    pub fn new(bases: &[G], window: usize) -> Self {
+        let window = window.clamp(2, MAX_WINDOW);
+        let num_windows = G::ScalarField::size_in_bits() / window + 1;
+        let shifted = |base: &G| {
+            let mut point = base.into_projective();
+            let mut row = Vec::with_capacity(num_windows);
+            for _ in 0..num_windows {
+                row.push(point);
+                for _ in 0..window {
+                    point.double_in_place();
+                }
+            }
+            row
+        };
+
+        #[cfg(feature = "parallel")]
+        let table: Vec<_> = bases.par_iter().flat_map_iter(shifted).collect();
+        #[cfg(not(feature = "parallel"))]
+        let table: Vec<_> = bases.iter().flat_map(shifted).collect();
+
+        Self {
+            window,
+            num_windows,
+            table: G::Projective::batch_normalization_into_affine(&table),
+        }
+    }
+
+    //number of bases
+    pub fn len(&self) -> usize {
+        self.table.len() / self.num_windows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    //sum scalars[i] * bases[i] over the first scalars.len() bases
+    pub fn msm(&self, scalars: &[G::ScalarField]) -> G::Projective {
+        let n = self.len().min(scalars.len());
+        let (num_windows, digits) = signed_digits(&scalars[..n], self.window);
+        let num_buckets = 1 << (self.window - 1);
+        let chunk_sum = |range: std::ops::Range<usize>| {
+            let (digits, table) = (&digits, &self.table);
+            bucket_sum(num_buckets, move || {
+                let range = range.clone();
+                (range.start * num_windows..range.end * num_windows).map(move |k| (digits[k], &table[k]))
+            })
+        };
+
+        //every thread sums its own chunk of the scalars
+        #[cfg(feature = "parallel")]
+        {
+            let chunk = (n / rayon::current_num_threads()).max(1);
+            (0..n)
+                .step_by(chunk)
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(|start| chunk_sum(start..(start + chunk).min(n)))
+                .sum()
+        }
+        #[cfg(not(feature = "parallel"))]
+        chunk_sum(0..n)
+    }
+}
+
+//sum d * p over the (digit, point) pairs of entries, the sum of |d| * (+-p) over bucket |d|.
+//entries is iterated twice, once to size the buckets and once to fill them.
+fn bucket_sum<'a, G, I>(num_buckets: usize, entries: impl Fn() -> I) -> G::Projective
+where
+    G: CurveAffine,
+    I: Iterator<Item = (i32, &'a G)>,
+{
+    let mut starts = vec![0; num_buckets + 1];
+    for (digit, point) in entries() {
+        if digit != 0 && !point.is_zero() {
+            starts[digit.unsigned_abs() as usize] += 1;
+        }
+    }
+    for b in 0..num_buckets {
+        starts[b + 1] += starts[b];
+    }
+    //bucket b holds digit b + 1 in points[starts[b]..starts[b] + lens[b]]
+    let mut lens = vec![0; num_buckets];
+    let mut points = vec![G::zero(); starts[num_buckets]];
+    for (digit, point) in entries() {
+        if digit != 0 && !point.is_zero() {
+            let b = digit.unsigned_abs() as usize - 1;
+            points[starts[b] + lens[b]] = if digit < 0 { -*point } else { *point };
+            lens[b] += 1;
+        }
+    }
+
+    //halve every bucket in rounds, the additions of a round share one inversion
+    let mut pairs = vec![];
+    loop {
+        pairs.clear();
+        for b in 0..num_buckets {
+            for i in 0..lens[b] / 2 {
+                pairs.push(starts[b] + 2 * i);
+            }
+        }
+        if pairs.is_empty() {
+            break;
+        }
+        let sums = G::batch_add(&points, &pairs);
+        let mut sums = sums.into_iter();
+        for b in 0..num_buckets {
+            let half = lens[b] / 2;
+            if lens[b] % 2 == 1 {
+                points[starts[b] + half] = points[starts[b] + lens[b] - 1];
+            }
+            for i in 0..half {
+                points[starts[b] + i] = sums.next().unwrap();
+            }
+            lens[b] -= half;
+        }
+    }
+
+    //sum (b + 1) * bucket_b as a sum of running sums from the largest digit
+    let mut running = G::Projective::zero();
+    let mut sum = G::Projective::zero();
+    for b in (0..num_buckets).rev() {
+        if lens[b] == 1 {
+            running.add_assign_mixed(&points[starts[b]]);
+        }
+        sum += running;
+    }
+    sum
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::pippenger::{msm, FixedBaseTable};
+    use ark_ec::msm::VariableBaseMSM;
+    use ark_ec::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+    use ark_ec::{ProjectiveCurve, SWModelParameters};
+    use ark_ff::{One, PrimeField, UniformRand, Zero};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn reference<P: SWModelParameters>(bases: &[GroupAffine<P>], scalars: &[P::ScalarField]) -> GroupProjective<P> {
+        let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+        VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+    }
+
+    //Random inputs, and the ones that fill buckets with equal, opposite and zero points
+    fn check_msm<P: SWModelParameters>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let random_bases = |n: usize, rng: &mut StdRng| {
+            let points: Vec<_> = (0..n).map(|_| GroupProjective::<P>::rand(rng)).collect();
+            GroupProjective::batch_normalization_into_affine(&points)
+        };
+        for n in [0, 1, 2, 3, 31, 32, 100, 1000] {
+            let bases = random_bases(n, &mut rng);
+            let scalars: Vec<_> = (0..n).map(|_| P::ScalarField::rand(&mut rng)).collect();
+            assert_eq!(msm(&bases, &scalars), reference(&bases, &scalars));
+            //extra scalars or bases are ignored
+            assert_eq!(msm(&bases, &[&scalars[..], &[P::ScalarField::one()]].concat()), reference(&bases, &scalars));
+        }
+
+        let base = random_bases(1, &mut rng)[0];
+        let one = P::ScalarField::one();
+        let cases: Vec<(Vec<_>, Vec<_>)> = vec![
+            //one bucket holds every point, and doublings of equal points
+            (vec![base; 64], vec![one; 64]),
+            (vec![base; 65], vec![-one; 65]),
+            (random_bases(200, &mut rng), vec![one; 200]),
+            //opposite points cancel in a bucket
+            (vec![base, -base, base, -base], vec![P::ScalarField::from(7u32); 4]),
+            (vec![base, base], vec![one, -one]),
+            (vec![GroupAffine::zero(), base, GroupAffine::zero()], vec![one, one, one]),
+            (random_bases(50, &mut rng), vec![P::ScalarField::zero(); 50]),
+        ];
+        for (bases, scalars) in cases {
+            assert_eq!(msm(&bases, &scalars), reference(&bases, &scalars));
+            let table = FixedBaseTable::new(&bases, 4);
+            assert_eq!(table.msm(&scalars), reference(&bases, &scalars));
+        }
+
+        let bases = random_bases(300, &mut rng);
+        for window in [2, 3, 8, 13, 16] {
+            let table = FixedBaseTable::new(&bases, window);
+            assert_eq!((table.len(), table.window()), (300, window));
+            let scalars: Vec<_> = (0..300).map(|_| P::ScalarField::rand(&mut rng)).collect();
+            assert_eq!(table.msm(&scalars), reference(&bases, &scalars));
+            //commitments to shorter polys use a prefix of the table
+            assert_eq!(table.msm(&scalars[..17]), reference(&bases[..17], &scalars[..17]));
+            assert_eq!(table.msm(&[]), GroupProjective::zero());
+        }
+        assert_eq!(FixedBaseTable::new(&bases, 40).window(), 16);
+        assert!(FixedBaseTable::<GroupAffine<P>>::new(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn test_bls12_381_msm() {
+        check_msm::<ark_bls12_381::g1::Parameters>(0);
+    }
+
+    #[test]
+    fn test_bn254_msm() {
+        check_msm::<ark_bn254::g1::Parameters>(1);
+    }
+
+    #[test]
+    fn test_signed_digits() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let scalars = [ark_bn254::Fr::rand(&mut rng), -ark_bn254::Fr::one(), ark_bn254::Fr::zero()];
+        for c in [2, 5, 13, 16] {
+            let (num_windows, digits) = super::signed_digits(&scalars, c);
+            for (i, scalar) in scalars.iter().enumerate() {
+                let mut acc = ark_bn254::Fr::zero();
+                for digit in digits[i * num_windows..(i + 1) * num_windows].iter().rev() {
+                    assert!(digit.unsigned_abs() <= 1 << (c - 1));
+                    let magnitude = ark_bn254::Fr::from(digit.unsigned_abs());
+                    acc = acc * ark_bn254::Fr::from(1u64 << c) + if *digit < 0 { -magnitude } else { magnitude };
+                }
+                assert_eq!(acc, *scalar);
+            }
+        }
+        for n in [1, 32, 1 << 10, 1 << 30] {
+            assert!(super::window_size(n) <= super::MAX_WINDOW);
+        }
+    }
+}
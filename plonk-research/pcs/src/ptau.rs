@@ -0,0 +1,477 @@
+use crate::error::Error;
+use crate::kzg10::UniversalParams;
+use ark_ec::short_weierstrass_jacobian::GroupAffine;
+use ark_ec::{AffineCurve, PairingEngine, SWModelParameters};
+use ark_ff::{BigInteger, FromBytes, Field, PrimeField, Zero};
+use std::io::{Read, Seek, SeekFrom};
+
+const PTAU_MAGIC: &[u8] = b"ptau";
+const PTAU_VERSION: u32 = 1;
+const PTAU_HEADER: u32 = 1;
+const PTAU_TAU_G1: u32 = 2;
+const PTAU_TAU_G2: u32 = 3;
+
+//Every perpetual powers of tau file starts with the blake2b hash of the previous round
+const PPOT_HASH_SIZE: u64 = 64;
+const PPOT_COMPRESSED: u8 = 1 << 7;
+const PPOT_INFINITY: u8 = 1 << 6;
+
+//Side of a perpetual powers of tau round, a challenge holds uncompressed points and
+//a response compressed points followed by the contribution's public key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PpotFile {
+    Challenge,
+    Response,
+}
+
+//Loads the powers up to max_degree from a snarkjs .ptau file (phase 1 or prepared phase 2)
+//and checks them, see UniversalParams::check.
+//Coordinates are little endian Montgomery forms, a point at infinity is all zero.
+pub fn read_ptau<E, P1, P2, R>(mut reader: R, max_degree: usize) -> Result<UniversalParams<E>, Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+    P1::BaseField: PrimeField,
+    P2::BaseField: Field<BasePrimeField = P1::BaseField>,
+    R: Read + Seek,
+{
+    if read_vec(&mut reader, 4)? != PTAU_MAGIC {
+        return Err(Error::MalformedSrsFile("not a ptau file"));
+    }
+    if read_u32(&mut reader)? != PTAU_VERSION {
+        return Err(Error::MalformedSrsFile("unsupported ptau version"));
+    }
+    //section id, offset and size
+    let mut sections: Vec<(u32, u64, u64)> = vec![];
+    for _ in 0..read_u32(&mut reader)? {
+        let id = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        if sections.iter().any(|s| s.0 == id) {
+            return Err(Error::MalformedSrsFile("duplicate ptau section"));
+        }
+        let offset = reader.stream_position().map_err(io_error)?;
+        sections.push((id, offset, size));
+        reader.seek(SeekFrom::Current(size as i64)).map_err(io_error)?;
+    }
+    let end = reader.stream_position().map_err(io_error)?;
+    if reader.seek(SeekFrom::End(0)).map_err(io_error)? < end {
+        return Err(Error::MalformedSrsFile("truncated ptau section"));
+    }
+    let section = |id: u32| {
+        sections
+            .iter()
+            .find(|s| s.0 == id)
+            .map(|s| (s.1, s.2))
+            .ok_or(Error::MalformedSrsFile("missing ptau section"))
+    };
+
+    let (offset, _) = section(PTAU_HEADER)?;
+    reader.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+    let n8 = read_u32(&mut reader)? as usize;
+    if n8 != field_size::<P1::BaseField>() {
+        return Err(Error::MalformedSrsFile("field element size does not match the curve"));
+    }
+    let q = read_vec(&mut reader, n8)?;
+    if q != P1::BaseField::characteristic().iter().flat_map(|l| l.to_le_bytes()).collect::<Vec<_>>() {
+        return Err(Error::MalformedSrsFile("prime does not match the curve"));
+    }
+    let power = read_u32(&mut reader)?;
+    let (g1_count, g2_count) = power_counts(power as u64)?;
+
+    let g1_size = 2 * n8 as u64;
+    let g2_size = 2 * n8 as u64 * P2::BaseField::extension_degree();
+    let (g1_offset, g1_section) = section(PTAU_TAU_G1)?;
+    let (g2_offset, g2_section) = section(PTAU_TAU_G2)?;
+    if g1_section != g1_count * g1_size || g2_section != g2_count * g2_size {
+        return Err(Error::MalformedSrsFile("tau section size does not match the power"));
+    }
+    check_degree(max_degree, g1_count)?;
+
+    let read_point = |reader: &mut R, degree: u64| -> Result<_, Error> {
+        let bytes = read_vec(reader, (degree * n8 as u64) as usize)?;
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        bytes
+            .chunks(n8)
+            .map(from_montgomery_le::<P1::BaseField>)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    };
+    reader.seek(SeekFrom::Start(g1_offset)).map_err(io_error)?;
+    let mut powers_of_g = Vec::with_capacity(max_degree + 1);
+    for _ in 0..=max_degree {
+        powers_of_g.push(match read_point(&mut reader, 2)? {
+            Some(coords) => GroupAffine::new(coords[0], coords[1], false),
+            None => GroupAffine::zero(),
+        });
+    }
+    reader.seek(SeekFrom::Start(g2_offset)).map_err(io_error)?;
+    let mut g2 = vec![];
+    for _ in 0..2 {
+        g2.push(match read_point(&mut reader, g2_size / n8 as u64)? {
+            Some(coords) => {
+                let (x, y) = coords.split_at(coords.len() / 2);
+                GroupAffine::new(extension_elem(x)?, extension_elem(y)?, false)
+            }
+            None => GroupAffine::zero(),
+        });
+    }
+    checked_params(powers_of_g, g2[0], g2[1])
+}
+
+//Loads the powers up to max_degree from a perpetual powers of tau challenge or response of a
+//ceremony of 2^power powers and checks them, see UniversalParams::check.
+//Coordinates are big endian with pairing_ce point flags in the spare top bits, so it needs a
+//base field with at least two spare bits like BN254's. G2 coordinates start with the highest coefficient.
+pub fn read_ppot<E, P1, P2, R>(
+    mut reader: R,
+    file: PpotFile,
+    power: usize,
+    max_degree: usize,
+) -> Result<UniversalParams<E>, Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+    P1::BaseField: PrimeField,
+    P2::BaseField: Field<BasePrimeField = P1::BaseField>,
+    R: Read + Seek,
+{
+    let n8 = field_size::<P1::BaseField>();
+    if P1::BaseField::size_in_bits() + 2 > 8 * n8 {
+        return Err(Error::MalformedSrsFile("curve has no spare bits for point flags"));
+    }
+    let (g1_count, _) = power_counts(power as u64)?;
+    check_degree(max_degree, g1_count)?;
+    let compressed = file == PpotFile::Response;
+    let coords = if compressed { 1 } else { 2 };
+    let g1_size = (coords * n8) as u64;
+    let g2_size = g1_size * P2::BaseField::extension_degree();
+
+    reader.seek(SeekFrom::Start(PPOT_HASH_SIZE)).map_err(io_error)?;
+    let mut powers_of_g = Vec::with_capacity(max_degree + 1);
+    for _ in 0..=max_degree {
+        powers_of_g.push(read_ppot_point::<R, P1>(&mut reader, compressed, g1_size, n8)?);
+    }
+    reader
+        .seek(SeekFrom::Start(PPOT_HASH_SIZE + g1_count * g1_size))
+        .map_err(io_error)?;
+    let h = read_ppot_point::<R, P2>(&mut reader, compressed, g2_size, n8)?;
+    let beta_h = read_ppot_point::<R, P2>(&mut reader, compressed, g2_size, n8)?;
+    checked_params(powers_of_g, h, beta_h)
+}
+
+//G1 and G2 points only differ in their coordinates, every coordinate is a list of base prime field
+//elements starting with the highest coefficient
+fn read_ppot_point<R: Read, P: SWModelParameters>(
+    reader: &mut R,
+    compressed: bool,
+    size: u64,
+    n8: usize,
+) -> Result<GroupAffine<P>, Error>
+where
+    <P::BaseField as Field>::BasePrimeField: PrimeField,
+{
+    let mut bytes = read_vec(reader, size as usize)?;
+    let flags = bytes[0] & (PPOT_COMPRESSED | PPOT_INFINITY);
+    bytes[0] &= !(PPOT_COMPRESSED | PPOT_INFINITY);
+    if flags & PPOT_INFINITY != 0 {
+        if flags == PPOT_INFINITY && bytes.iter().all(|b| *b == 0) {
+            return Ok(GroupAffine::zero());
+        }
+        return Err(Error::MalformedSrsFile("invalid encoding of the point at infinity"));
+    }
+    let elems = bytes
+        .chunks(n8)
+        .rev()
+        .map(from_be::<<P::BaseField as Field>::BasePrimeField>)
+        .collect::<Result<Vec<_>, _>>()?;
+    //elems are reversed, so the last coordinate comes first
+    let coord_size = elems.len() / if compressed { 1 } else { 2 };
+    let x = extension_elem(&elems[elems.len() - coord_size..])?;
+    if compressed {
+        GroupAffine::get_point_from_x(x, flags & PPOT_COMPRESSED != 0).ok_or(Error::InvalidSrsPoint)
+    } else if flags & PPOT_COMPRESSED != 0 {
+        Err(Error::MalformedSrsFile("unexpected compressed point"))
+    } else {
+        Ok(GroupAffine::new(x, extension_elem(&elems[..coord_size])?, false))
+    }
+}
+
+//A ceremony starts its powers at the generators
+fn checked_params<E, P1, P2>(
+    powers_of_g: Vec<GroupAffine<P1>>,
+    h: GroupAffine<P2>,
+    beta_h: GroupAffine<P2>,
+) -> Result<UniversalParams<E>, Error>
+where
+    E: PairingEngine<G1Affine = GroupAffine<P1>, G2Affine = GroupAffine<P2>>,
+    P1: SWModelParameters<ScalarField = E::Fr>,
+    P2: SWModelParameters<ScalarField = E::Fr>,
+{
+    if powers_of_g[0] != GroupAffine::prime_subgroup_generator() || h != GroupAffine::prime_subgroup_generator() {
+        return Err(Error::MalformedSrsFile("powers do not start at the generators"));
+    }
+    let params = UniversalParams {
+        powers_of_g,
+        h,
+        beta_h,
+    };
+    params.check()?;
+    Ok(params)
+}
+
+//Number of G1 and G2 powers of a ceremony of 2^power powers
+fn power_counts(power: u64) -> Result<(u64, u64), Error> {
+    if power >= 32 {
+        return Err(Error::MalformedSrsFile("ceremony power is too large"));
+    }
+    Ok(((1 << (power + 1)) - 1, 1 << power))
+}
+
+fn check_degree(degree: usize, g1_count: u64) -> Result<(), Error> {
+    if degree as u64 >= g1_count {
+        return Err(Error::TrimmingDegreeTooLarge {
+            degree,
+            max_degree: g1_count as usize - 1,
+        });
+    }
+    Ok(())
+}
+
+//Bytes of a serialized base field element, a whole number of 64 bit limbs
+fn field_size<F: PrimeField>() -> usize {
+    F::zero().into_repr().to_bytes_le().len()
+}
+
+fn from_le<F: PrimeField>(bytes: &[u8]) -> Result<F, Error> {
+    let repr = F::BigInt::read(bytes).map_err(|_| Error::MalformedSrsFile("unexpected end of file"))?;
+    F::from_repr(repr).ok_or(Error::MalformedSrsFile("coordinate is not a canonical field element"))
+}
+
+fn from_be<F: PrimeField>(bytes: &[u8]) -> Result<F, Error> {
+    let bytes: Vec<u8> = bytes.iter().rev().cloned().collect();
+    from_le(&bytes)
+}
+
+//a * R mod q with R = 2^(8 * n8)
+fn from_montgomery_le<F: PrimeField>(bytes: &[u8]) -> Result<F, Error> {
+    let r = F::from(2u64).pow([8 * bytes.len() as u64]);
+    Ok(from_le::<F>(bytes)? * r.inverse().unwrap())
+}
+
+fn extension_elem<F: Field>(elems: &[F::BasePrimeField]) -> Result<F, Error> {
+    F::from_base_prime_field_elems(elems).ok_or(Error::MalformedSrsFile("wrong number of coordinates"))
+}
+
+fn read_vec<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(bytes)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).map_err(io_error)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn io_error(_: std::io::Error) -> Error {
+    Error::MalformedSrsFile("unexpected end of file")
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::error::Error;
+    use crate::kzg10::UniversalParams;
+    use crate::ptau::{read_ppot, read_ptau, PpotFile};
+    use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::io::Cursor;
+
+    const POWER: usize = 3;
+
+    //Powers of a known tau in the layout of a ceremony of 2^POWER powers
+    fn ceremony_powers(tau: Fr) -> (Vec<G1Affine>, Vec<G2Affine>) {
+        let mut pow = Fr::one();
+        let (mut g1, mut g2) = (vec![], vec![]);
+        for i in 0..(1 << (POWER + 1)) - 1 {
+            g1.push(G1Affine::prime_subgroup_generator().mul(pow).into_affine());
+            if i < 1 << POWER {
+                g2.push(G2Affine::prime_subgroup_generator().mul(pow).into_affine());
+            }
+            pow *= tau;
+        }
+        (g1, g2)
+    }
+
+    fn write_ptau(g1: &[G1Affine], g2: &[G2Affine]) -> Vec<u8> {
+        let montgomery = |x: &Fq| (*x * Fq::from(2u64).pow([256])).into_repr().to_bytes_le();
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend(Fq::characteristic().iter().flat_map(|l| l.to_le_bytes()));
+        header.extend((POWER as u32).to_le_bytes());
+        header.extend((POWER as u32).to_le_bytes());
+        let mut tau_g1 = vec![];
+        for p in g1.iter() {
+            tau_g1.extend(montgomery(&p.x));
+            tau_g1.extend(montgomery(&p.y));
+        }
+        let mut tau_g2 = vec![];
+        for p in g2.iter() {
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1].iter() {
+                tau_g2.extend(montgomery(c));
+            }
+        }
+
+        let mut bytes = b"ptau".to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(3u32.to_le_bytes());
+        for (id, section) in [header, tau_g1, tau_g2].iter().enumerate() {
+            bytes.extend((id as u32 + 1).to_le_bytes());
+            bytes.extend((section.len() as u64).to_le_bytes());
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    fn write_ppot(g1: &[G1Affine], g2: &[G2Affine], file: PpotFile) -> Vec<u8> {
+        let be = |x: &Fq| x.into_repr().to_bytes_be();
+        let compressed = file == PpotFile::Response;
+        let mut bytes = vec![0u8; 64];
+        for p in g1.iter() {
+            let mut point = be(&p.x);
+            if compressed {
+                point[0] |= ((p.y > -p.y) as u8) << 7;
+            } else {
+                point.extend(be(&p.y));
+            }
+            bytes.extend(point);
+        }
+        for p in g2.iter() {
+            let mut point = [be(&p.x.c1), be(&p.x.c0)].concat();
+            if compressed {
+                point[0] |= ((p.y > -p.y) as u8) << 7;
+            } else {
+                point.extend([be(&p.y.c1), be(&p.y.c0)].concat());
+            }
+            bytes.extend(point);
+        }
+        bytes
+    }
+
+    fn expected(g1: &[G1Affine], g2: &[G2Affine], degree: usize) -> UniversalParams<Bn254> {
+        UniversalParams {
+            powers_of_g: g1[..=degree].to_vec(),
+            h: g2[0],
+            beta_h: g2[1],
+        }
+    }
+
+    #[test]
+    fn test_read_ptau() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (g1, g2) = ceremony_powers(Fr::rand(&mut rng));
+        let bytes = write_ptau(&g1, &g2);
+        let params: UniversalParams<Bn254> = read_ptau(Cursor::new(&bytes), 10).unwrap();
+        let expected = expected(&g1, &g2, 10);
+        assert_eq!(params.powers_of_g, expected.powers_of_g);
+        assert_eq!((params.h, params.beta_h), (expected.h, expected.beta_h));
+        assert!(read_ptau::<Bn254, _, _, _>(Cursor::new(&bytes), g1.len() - 1).is_ok());
+        assert_eq!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(&bytes), g1.len()).unwrap_err(),
+            Error::TrimmingDegreeTooLarge {
+                degree: g1.len(),
+                max_degree: g1.len() - 1
+            }
+        );
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'q';
+        assert_eq!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(&bad_magic), 10).unwrap_err(),
+            Error::MalformedSrsFile("not a ptau file")
+        );
+        assert!(matches!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(&bytes[..bytes.len() - 1]), 10).unwrap_err(),
+            Error::MalformedSrsFile(_)
+        ));
+    }
+
+    #[test]
+    fn test_read_ppot() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (g1, g2) = ceremony_powers(Fr::rand(&mut rng));
+        for file in [PpotFile::Challenge, PpotFile::Response] {
+            let bytes = write_ppot(&g1, &g2, file);
+            let params: UniversalParams<Bn254> = read_ppot(Cursor::new(&bytes), file, POWER, 7).unwrap();
+            let expected = expected(&g1, &g2, 7);
+            assert_eq!(params.powers_of_g, expected.powers_of_g);
+            assert_eq!((params.h, params.beta_h), (expected.h, expected.beta_h));
+            assert!(matches!(
+                read_ppot::<Bn254, _, _, _>(Cursor::new(&bytes), file, POWER, g1.len()).unwrap_err(),
+                Error::TrimmingDegreeTooLarge { .. }
+            ));
+            assert!(matches!(
+                read_ppot::<Bn254, _, _, _>(Cursor::new(&bytes[..200]), file, POWER, 7).unwrap_err(),
+                Error::MalformedSrsFile(_)
+            ));
+        }
+        //a challenge read as a response has the wrong point sizes
+        let bytes = write_ppot(&g1, &g2, PpotFile::Challenge);
+        assert!(read_ppot::<Bn254, _, _, _>(Cursor::new(&bytes), PpotFile::Response, POWER, 7).is_err());
+    }
+
+    #[test]
+    fn test_reject_bad_powers() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (mut g1, g2) = ceremony_powers(Fr::rand(&mut rng));
+        let honest = g1[5];
+
+        //a valid point that is not the next power of tau
+        g1[5] = G1Affine::prime_subgroup_generator().mul(Fr::rand(&mut rng)).into_affine();
+        assert_eq!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(write_ptau(&g1, &g2)), 10).unwrap_err(),
+            Error::InconsistentSrs
+        );
+        assert_eq!(
+            read_ppot::<Bn254, _, _, _>(Cursor::new(write_ppot(&g1, &g2, PpotFile::Response)), PpotFile::Response, POWER, 10)
+                .unwrap_err(),
+            Error::InconsistentSrs
+        );
+        //powers past the trimmed degree are not read
+        assert!(read_ptau::<Bn254, _, _, _>(Cursor::new(write_ptau(&g1, &g2)), 4).is_ok());
+
+        //off the curve
+        g1[5] = G1Affine::new(honest.x, honest.y + Fq::one(), false);
+        assert_eq!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(write_ptau(&g1, &g2)), 10).unwrap_err(),
+            Error::InvalidSrsPoint
+        );
+        assert_eq!(
+            read_ppot::<Bn254, _, _, _>(Cursor::new(write_ppot(&g1, &g2, PpotFile::Challenge)), PpotFile::Challenge, POWER, 10)
+                .unwrap_err(),
+            Error::InvalidSrsPoint
+        );
+
+        //beta_h of another tau
+        g1[5] = honest;
+        let (_, other_g2) = ceremony_powers(Fr::rand(&mut rng));
+        let g2 = vec![g2[0], other_g2[1]];
+        assert_eq!(
+            read_ptau::<Bn254, _, _, _>(Cursor::new(write_ptau(&g1, &[g2.clone(), vec![g2[0]; 6]].concat())), 10)
+                .unwrap_err(),
+            Error::InconsistentSrs
+        );
+    }
+}
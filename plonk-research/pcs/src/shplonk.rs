@@ -0,0 +1,311 @@
+use crate::error::Error;
+use crate::{powers, PolynomialCommitment};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{Polynomial, UVPolynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2::{Blake2b, Digest};
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+const PROTOCOL_NAME: &[u8] = b"plonk shplonk";
+
+//Multi point opening, quot_commit commits to h(X) = sum gamma^i * Z_{T\S_i}(X) * (f_i(X) - r_i(X)) / Z_T(X),
+//opening_proof opens L(X) = sum gamma^i * Z_{T\S_i}(z) * f_i(X) - Z_T(z) * h(X) at the point z.
+//With KZG10 it is two group elements checked with one pairing equation.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<PC: PolynomialCommitment> {
+    pub quot_commit: PC::Commitment,
+
+    pub opening_proof: PC::Proof,
+}
+
+impl<PC: PolynomialCommitment> Clone for Proof<PC> {
+    fn clone(&self) -> Self {
+        Self {
+            quot_commit: self.quot_commit.clone(),
+            opening_proof: self.opening_proof.clone(),
+        }
+    }
+}
+
+impl<PC: PolynomialCommitment> Debug for Proof<PC> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Proof")
+            .field("quot_commit", &self.quot_commit)
+            .field("opening_proof", &self.opening_proof)
+            .finish()
+    }
+}
+
+impl<PC: PolynomialCommitment> PartialEq for Proof<PC> {
+    fn eq(&self, other: &Self) -> bool {
+        self.quot_commit == other.quot_commit && self.opening_proof == other.opening_proof
+    }
+}
+
+//SHPLONK multi point opening (BDFG20) on top of any scheme whose commitments can be combined.
+//Every claim f_i is opened at every point of its point set S_i, T is the union of the point sets.
+#[derive(Clone, Copy, Debug)]
+pub struct SHPLONK<PC: PolynomialCommitment>(PhantomData<PC>);
+
+impl<PC: PolynomialCommitment> SHPLONK<PC> {
+    //Claim i is f_i(X) = sum scalars[i][j] * polys[i][j], opened at every point of point_sets[i].
+    //challenge combines the claims and must be bound to their commitments, points and values.
+    pub fn open(
+        ck: &PC::CommitterKey,
+        polys: &[Vec<&DensePolynomial<PC::Field>>],
+        scalars: &[Vec<PC::Field>],
+        point_sets: &[Vec<PC::Field>],
+        challenge: PC::Field,
+    ) -> Result<Proof<PC>, Error> {
+        assert!(polys.len() == scalars.len() && polys.len() == point_sets.len());
+        if !point_sets.iter().all(|points| distinct(points)) {
+            return Err(Error::DuplicateOpeningPoint);
+        }
+        let all_points = union(point_sets);
+        let challenge_pows = powers(challenge, polys.len());
+        let mut values = vec![];
+        let mut numerator = DensePolynomial::zero();
+        for i in 0..polys.len() {
+            let mut claim = DensePolynomial::zero();
+            for (poly, s) in polys[i].iter().zip(scalars[i].iter()) {
+                claim += (*s, *poly);
+            }
+            let claim_values: Vec<_> = point_sets[i].iter().map(|x| claim.evaluate(x)).collect();
+            claim -= &interpolate(&point_sets[i], &claim_values);
+            let others = vanishing_poly(&difference(&all_points, &point_sets[i]));
+            numerator += (challenge_pows[i], &claim.naive_mul(&others));
+            values.push(claim_values);
+        }
+        let quot_poly = &numerator / &vanishing_poly(&all_points);
+        let quot_commit = PC::commit(ck, &quot_poly)?;
+        let z = Self::opening_point(challenge, &quot_commit, point_sets, &values);
+
+        let (mut terms, mut term_scalars) = (vec![], vec![]);
+        for i in 0..polys.len() {
+            let coeff = challenge_pows[i] * eval_vanishing(&difference(&all_points, &point_sets[i]), z);
+            terms.extend(polys[i].iter().cloned());
+            term_scalars.extend(scalars[i].iter().map(|s| coeff * s));
+        }
+        terms.push(&quot_poly);
+        term_scalars.push(-eval_vanishing(&all_points, z));
+        let opening_proof = PC::open_combination(ck, &terms, &term_scalars, z)?;
+        Ok(Proof {
+            quot_commit,
+            opening_proof,
+        })
+    }
+
+    //commits[i] is the commitment of claim i, evaluating to values[i][k] at point_sets[i][k]
+    pub fn verify(
+        vk: &PC::VerifierKey,
+        commits: &[PC::Commitment],
+        point_sets: &[Vec<PC::Field>],
+        values: &[Vec<PC::Field>],
+        proof: &Proof<PC>,
+        challenge: PC::Field,
+    ) -> bool {
+        if commits.len() != point_sets.len()
+            || commits.len() != values.len()
+            || point_sets
+                .iter()
+                .zip(values.iter())
+                .any(|(points, vals)| points.is_empty() || points.len() != vals.len() || !distinct(points))
+        {
+            return false;
+        }
+        let all_points = union(point_sets);
+        let z = Self::opening_point(challenge, &proof.quot_commit, point_sets, values);
+
+        //L(z) = sum gamma^i * Z_{T\S_i}(z) * r_i(z)
+        let mut bases = commits.to_vec();
+        let mut scalars = vec![];
+        let mut value = PC::Field::zero();
+        for (i, c) in powers(challenge, commits.len()).into_iter().enumerate() {
+            let coeff = c * eval_vanishing(&difference(&all_points, &point_sets[i]), z);
+            scalars.push(coeff);
+            value += coeff * interpolate(&point_sets[i], &values[i]).evaluate(&z);
+        }
+        bases.push(proof.quot_commit.clone());
+        scalars.push(-eval_vanishing(&all_points, z));
+        PC::verify(vk, &PC::combine_commitments(&bases, &scalars), z, value, &proof.opening_proof)
+    }
+
+    //Fiat-Shamir challenge z, squeezed after the prover commits to h(X)
+    fn opening_point(
+        challenge: PC::Field,
+        quot_commit: &PC::Commitment,
+        point_sets: &[Vec<PC::Field>],
+        values: &[Vec<PC::Field>],
+    ) -> PC::Field {
+        let mut bytes = PROTOCOL_NAME.to_vec();
+        challenge.serialize(&mut bytes).unwrap();
+        quot_commit.serialize(&mut bytes).unwrap();
+        point_sets.to_vec().serialize(&mut bytes).unwrap();
+        values.to_vec().serialize(&mut bytes).unwrap();
+        PC::Field::from_le_bytes_mod_order(&Blake2b::digest(&bytes))
+    }
+}
+
+//Distinct points of all point sets, in order of first appearance
+fn union<F: PrimeField>(point_sets: &[Vec<F>]) -> Vec<F> {
+    let mut points = vec![];
+    for x in point_sets.iter().flatten() {
+        if !points.contains(x) {
+            points.push(*x);
+        }
+    }
+    points
+}
+
+//Interpolation needs the points of a set to be distinct
+fn distinct<F: PrimeField>(points: &[F]) -> bool {
+    (0..points.len()).all(|k| !points[..k].contains(&points[k]))
+}
+
+//Points of all_points not in points
+fn difference<F: PrimeField>(all_points: &[F], points: &[F]) -> Vec<F> {
+    all_points.iter().filter(|x| !points.contains(x)).cloned().collect()
+}
+
+//prod (X - x_k)
+fn vanishing_poly<F: PrimeField>(points: &[F]) -> DensePolynomial<F> {
+    let mut poly = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+    for x in points.iter() {
+        poly = poly.naive_mul(&DensePolynomial::from_coefficients_vec(vec![-*x, F::one()]));
+    }
+    poly
+}
+
+fn eval_vanishing<F: PrimeField>(points: &[F], z: F) -> F {
+    points.iter().fold(F::one(), |acc, x| acc * (z - x))
+}
+
+//Lagrange interpolation of values at distinct points, open and verify reject other point sets
+fn interpolate<F: PrimeField>(points: &[F], values: &[F]) -> DensePolynomial<F> {
+    let mut poly = DensePolynomial::zero();
+    for (k, (x, v)) in points.iter().zip(values.iter()).enumerate() {
+        let others: Vec<F> = points
+            .iter()
+            .enumerate()
+            .filter(|(l, _)| *l != k)
+            .map(|(_, x)| *x)
+            .collect();
+        let basis = vanishing_poly(&others);
+        let scale = *v * eval_vanishing(&others, *x).inverse().unwrap();
+        poly += (scale, &basis);
+    }
+    poly
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fri::FRI;
+    use crate::ipa::IPA;
+    use crate::kzg10::KZG10;
+    use crate::shplonk::SHPLONK;
+    use crate::{Error, PolynomialCommitment};
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly::{Polynomial, UVPolynomial};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn check_multi_open<PC: PolynomialCommitment>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let pp = PC::setup(16, &mut rng).unwrap();
+        let (ck, vk) = PC::trim(&pp, 16).unwrap();
+        let polys: Vec<_> = (0..4)
+            .map(|i| DensePolynomial::<PC::Field>::rand(4 * i + 3, &mut rng))
+            .collect();
+        let commits: Vec<_> = polys.iter().map(|p| PC::commit(&ck, p).unwrap()).collect();
+        let x = PC::Field::rand(&mut rng);
+        let y = PC::Field::rand(&mut rng);
+        let w = PC::Field::rand(&mut rng);
+
+        //claim 0 is 3 * p_0 + p_1, the others are single polys at overlapping point sets
+        let scalar = PC::Field::from(3u32);
+        let poly_refs = vec![vec![&polys[0], &polys[1]], vec![&polys[2]], vec![&polys[3]]];
+        let scalars = vec![vec![scalar, PC::Field::one()], vec![PC::Field::one()], vec![PC::Field::one()]];
+        let point_sets = vec![vec![x], vec![x, y], vec![y, w, x]];
+        let claim_commits = vec![
+            PC::combine_commitments(&commits[..2], &scalars[0]),
+            commits[2].clone(),
+            commits[3].clone(),
+        ];
+        let values: Vec<Vec<_>> = point_sets
+            .iter()
+            .enumerate()
+            .map(|(i, points)| {
+                points
+                    .iter()
+                    .map(|p| {
+                        poly_refs[i]
+                            .iter()
+                            .zip(scalars[i].iter())
+                            .map(|(poly, s)| poly.evaluate(p) * s)
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+        let challenge = PC::Field::rand(&mut rng);
+        let proof = SHPLONK::<PC>::open(&ck, &poly_refs, &scalars, &point_sets, challenge).unwrap();
+        assert!(SHPLONK::<PC>::verify(&vk, &claim_commits, &point_sets, &values, &proof, challenge));
+        assert!(!SHPLONK::<PC>::verify(
+            &vk,
+            &claim_commits,
+            &point_sets,
+            &values,
+            &proof,
+            challenge + PC::Field::one()
+        ));
+
+        let mut bad_values = values.clone();
+        bad_values[2][1] += PC::Field::one();
+        assert!(!SHPLONK::<PC>::verify(&vk, &claim_commits, &point_sets, &bad_values, &proof, challenge));
+        let mut bad_commits = claim_commits.clone();
+        bad_commits.swap(1, 2);
+        assert!(!SHPLONK::<PC>::verify(&vk, &bad_commits, &point_sets, &values, &proof, challenge));
+        let mut bad_points = point_sets.clone();
+        bad_points[1].swap(0, 1);
+        assert!(!SHPLONK::<PC>::verify(&vk, &claim_commits, &bad_points, &values, &proof, challenge));
+        let mut bad_proof = proof.clone();
+        bad_proof.quot_commit = commits[0].clone();
+        assert!(!SHPLONK::<PC>::verify(&vk, &claim_commits, &point_sets, &values, &bad_proof, challenge));
+        assert!(!SHPLONK::<PC>::verify(
+            &vk,
+            &claim_commits[..2],
+            &point_sets[..2],
+            &values[..2],
+            &proof,
+            challenge
+        ));
+
+        //a repeated point is rejected instead of failing the interpolation
+        let mut repeated_points = point_sets.clone();
+        repeated_points[2][1] = x;
+        assert!(!SHPLONK::<PC>::verify(&vk, &claim_commits, &repeated_points, &values, &proof, challenge));
+        assert_eq!(
+            SHPLONK::<PC>::open(&ck, &poly_refs, &scalars, &repeated_points, challenge).unwrap_err(),
+            Error::DuplicateOpeningPoint
+        );
+    }
+
+    #[test]
+    fn test_kzg_multi_open() {
+        check_multi_open::<KZG10<Bls12_381>>(0);
+    }
+
+    #[test]
+    fn test_ipa_multi_open() {
+        check_multi_open::<IPA<ark_pallas::Affine>>(1);
+    }
+
+    #[test]
+    fn test_fri_multi_open() {
+        check_multi_open::<FRI<ark_bls12_381::Fr>>(2);
+    }
+}